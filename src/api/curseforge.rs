@@ -9,11 +9,12 @@ use serde_repr::{Deserialize_repr, Serialize_repr};
 use tokio::task::JoinSet;
 
 use crate::{
+    api::{request::{self, ratelimit_reset}, SearchSort},
     error::{Error, Result},
     structs::pack::ModLoader,
 };
 
-const API_URL: &str = "https://api.curseforge.com";
+pub const API_URL: &str = "https://api.curseforge.com";
 
 #[derive(Debug, Deserialize, Serialize, Clone)]
 #[serde(rename_all = "camelCase")]
@@ -32,6 +33,26 @@ pub struct Mod {
     pub summary: String,
     pub class_id: Option<i32>,
     pub allow_mod_distribution: Option<bool>,
+    #[serde(default)]
+    pub authors: Vec<ModAuthor>,
+    #[serde(default)]
+    pub download_count: f64,
+    pub date_modified: Option<DateTime<Utc>>,
+    #[serde(default)]
+    pub latest_files_indexes: Vec<FileIndex>,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ModAuthor {
+    pub name: String
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct FileIndex {
+    pub game_version: String,
+    pub mod_loader: Option<i32>,
 }
 
 #[derive(Debug, Deserialize, Serialize, Clone)]
@@ -47,6 +68,8 @@ pub struct File {
     pub mod_id: i32,
     pub is_available: bool,
     pub file_name: String,
+    pub release_type: FileReleaseType,
+    pub file_length: usize,
     pub hashes: Vec<FileHash>,
     pub file_date: DateTime<Utc>,
     pub download_url: Option<String>,
@@ -68,6 +91,14 @@ pub struct FileDependency {
     pub relation_type: FileRelationType,
 }
 
+#[derive(Deserialize_repr, Serialize_repr, Debug, Clone)]
+#[repr(u8)]
+pub enum FileReleaseType {
+    Release = 1,
+    Beta = 2,
+    Alpha = 3,
+}
+
 #[derive(Deserialize_repr, Serialize_repr, Debug, Clone)]
 #[repr(u8)]
 pub enum FileRelationType {
@@ -93,21 +124,24 @@ pub struct Match {
 
 pub struct CurseAPI {
     client: Client,
+    api_url: String,
 }
 impl CurseAPI {
-    pub fn new(api_key: &str) -> Self {
+    pub fn new(api_key: &str, api_url: &str) -> Self {
         let mut headers = HeaderMap::new();
         headers.insert("x-api-key", HeaderValue::from_str(api_key).unwrap());
 
         CurseAPI {
             client: Client::builder().default_headers(headers).build().unwrap(),
+            api_url: api_url.to_owned(),
         }
     }
 
-    async fn fetch<T: DeserializeOwned>(&self, f: impl FnOnce() -> RequestBuilder) -> Result<T> {
-        let res = f().send().await?;
+    async fn fetch<T: DeserializeOwned>(&self, f: impl Fn() -> RequestBuilder) -> Result<T> {
+        let res = request::send_cached(f).await?;
         if let Err(err) = res.error_for_status_ref() {
             let err = match err.status().unwrap() {
+                StatusCode::TOO_MANY_REQUESTS => Error::RateLimitExceeded("curseforge".to_owned(), ratelimit_reset(&res)),
                 StatusCode::NOT_FOUND => Error::NotFound(err.url().unwrap().to_string()),
                 _ => Error::Reqwest(err),
             };
@@ -119,38 +153,63 @@ impl CurseAPI {
         Ok(curse_response.data)
     }
 
-    async fn get<T: DeserializeOwned>(&self, url: &str) -> Result<T> {
-        self.fetch(|| self.client.get(url)).await
+    async fn get<T: DeserializeOwned>(&self, path: &str) -> Result<T> {
+        self.fetch(|| self.client.get(format!("{}{path}", self.api_url))).await
     }
 
-    async fn post<T, B>(&self, url: &str, body: &B) -> Result<T>
+    async fn post<T, B>(&self, path: &str, body: &B) -> Result<T>
     where
         T: DeserializeOwned,
         B: Serialize + ?Sized,
     {
-        self.fetch(|| self.client.post(url).json(body)).await
+        self.fetch(|| self.client.post(format!("{}{path}", self.api_url)).json(body)).await
     }
 
+    // curseforge accepts at most 4 game versions and 5 loaders, the rest are left out
     pub async fn search(
         &self,
         query: &str,
-        game_version: &str,
-        mod_loader: &ModLoader,
+        game_versions: &[&String],
+        mod_loaders: &[&ModLoader],
+        class_id: i32,
+        sort: SearchSort,
         page_size: &i32,
     ) -> Result<Vec<Mod>> {
-        self.get(&format!("{API_URL}/v1/search?gameId=432&classId=6&searchFilter={query}&gameVersion={game_version}&modLoaderType={mod_loader}&pageSize={page_size}")).await
+        let game_versions: Vec<&String> = game_versions.iter().take(4).copied().collect();
+        // the query is whatever the user typed, so it has to be encoded
+        let mut params = vec![
+            ("gameId", "432".to_owned()),
+            ("classId", class_id.to_string()),
+            ("searchFilter", query.to_owned()),
+            ("gameVersions", serde_json::to_string(&game_versions).unwrap()),
+            ("pageSize", page_size.to_string()),
+        ];
+
+        if !mod_loaders.is_empty() {
+            let mod_loader_types: Vec<i32> = mod_loaders.iter().take(5).map(|l| mod_loader_type(l)).collect();
+            params.push(("modLoaderTypes", serde_json::to_string(&mod_loader_types).unwrap()));
+        }
+
+        // https://docs.curseforge.com/rest-api/#tocS_ModsSearchSortField
+        match sort {
+            SearchSort::Relevance => (),
+            SearchSort::Downloads => params.extend([("sortField", "6".to_owned()), ("sortOrder", "desc".to_owned())]),
+            SearchSort::Updated => params.extend([("sortField", "3".to_owned()), ("sortOrder", "desc".to_owned())]),
+        }
+
+        self.fetch(|| self.client.get(format!("{}/v1/mods/search", self.api_url)).query(&params)).await
     }
 
     pub async fn get_mod(&self, id: &i32) -> Result<Mod> {
-        self.get(&format!("{API_URL}/v1/mods/{id}")).await
+        self.get(&format!("/v1/mods/{id}")).await
     }
 
-    pub async fn get_mod_by_slug(&self, slug: &str) -> Result<Mod> {
+    // slugs are only unique within a class, like mods (6) or resourcepacks (12)
+    pub async fn get_mod_by_slug(&self, slug: &str, class_id: i32) -> Result<Mod> {
         // gameId=432 == minecraft
-        // classId=6 == mod
         let res: Vec<Mod> = self
             .get(&format!(
-                "{API_URL}/v1/mods/search?gameId=432&classId=6&slug={slug}"
+                "/v1/mods/search?gameId=432&classId={class_id}&slug={slug}"
             ))
             .await?;
         match res.is_empty() {
@@ -166,29 +225,39 @@ impl CurseAPI {
             mod_ids: Vec<i32>,
         }
 
-        self.post(&format!("{API_URL}/v1/mods"), &Body { mod_ids: ids }).await
+        self.post("/v1/mods", &Body { mod_ids: ids }).await
     }
 
     pub async fn get_mod_file(&self, mod_id: &i32, file_id: &i32) -> Result<File> {
-        self.get(&format!("{API_URL}/v1/mods/{mod_id}/files/{file_id}")).await
+        self.get(&format!("/v1/mods/{mod_id}/files/{file_id}")).await
+    }
+
+    // the changelog is html
+    pub async fn get_file_changelog(&self, mod_id: &i32, file_id: &i32) -> Result<String> {
+        self.get(&format!("/v1/mods/{mod_id}/files/{file_id}/changelog")).await
     }
 
     pub async fn get_mod_files(&self, id: &i32) -> Result<Vec<File>> {
-        self.get(&format!("{API_URL}/v1/mods/{id}/files")).await
+        self.get(&format!("/v1/mods/{id}/files")).await
     }
 
     // using this instead of cf's v1/mods/files path
     // because that has inconsistencies
+    // files that dont exist anymore are left out, like modrinth's bulk requests do
     pub async fn get_files(&'static self, id_pairs: Vec<(i32, i32)>) -> Result<Vec<File>> {
-        let mut tasks: JoinSet<Result<File>> = JoinSet::new();
+        let mut tasks: JoinSet<Result<Option<File>>> = JoinSet::new();
         for pair in id_pairs {
             let task = async move {
-                self.get_mod_file(&pair.0, &pair.1).await
+                match self.get_mod_file(&pair.0, &pair.1).await {
+                    Ok(file) => Ok(Some(file)),
+                    Err(Error::NotFound(_)) => Ok(None),
+                    Err(err) => Err(err),
+                }
             };
             tasks.spawn(task);
         }
         let mut files = Vec::new();
-        while let Some(res) = tasks.join_next().await { files.push(res??) };
+        while let Some(res) = tasks.join_next().await { files.extend(res??) };
         Ok(files)
     }
 
@@ -215,9 +284,29 @@ impl CurseAPI {
         }
 
         self.post(
-            &format!("{API_URL}/v1/fingerprints"),
+            "/v1/fingerprints",
             &Body { fingerprints },
         )
         .await
     }
 }
+
+// https://docs.curseforge.com/rest-api/#tocS_ModLoaderType
+fn mod_loader_type(loader: &ModLoader) -> i32 {
+    match loader {
+        ModLoader::Forge => 1,
+        ModLoader::Fabric => 4,
+        ModLoader::Quilt => 5,
+        ModLoader::NeoForge => 6,
+    }
+}
+
+pub fn mod_loader_from_type(mod_loader_type: i32) -> Option<ModLoader> {
+    match mod_loader_type {
+        1 => Some(ModLoader::Forge),
+        4 => Some(ModLoader::Fabric),
+        5 => Some(ModLoader::Quilt),
+        6 => Some(ModLoader::NeoForge),
+        _ => None,
+    }
+}
\ No newline at end of file
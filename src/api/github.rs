@@ -1,12 +1,13 @@
+use lazy_regex::regex_captures;
 use reqwest::{
-    header::{HeaderMap, HeaderValue}, Client, RequestBuilder, Response
+    header::{HeaderMap, HeaderValue}, Client, RequestBuilder, Response, StatusCode
 };
 use serde::{de::DeserializeOwned, Deserialize, Serialize};
 use tokio::task::JoinSet;
 
-use crate::error::{Error, Result};
+use crate::{api::request::{self, ratelimit_reset}, error::{Error, Result}, structs::index::ReleaseChannel};
 
-const API_URL: &str = "https://api.github.com";
+pub const API_URL: &str = "https://api.github.com";
 const API_VERSION: &str = "2022-11-28";
 
 #[derive(Debug, Deserialize, Serialize, Clone)]
@@ -20,9 +21,20 @@ pub struct GithubRelease {
     pub name: String,
     pub tag_name: String,
     pub prerelease: bool,
+    pub body: Option<String>,
     pub assets: Vec<ReleaseAsset>
 }
 
+impl GithubRelease {
+    // github only knows releases and prereleases
+    pub fn release_channel(&self) -> ReleaseChannel {
+        match self.prerelease {
+            true => ReleaseChannel::Beta,
+            false => ReleaseChannel::Release,
+        }
+    }
+}
+
 #[derive(Debug, Deserialize, Serialize, Clone)]
 pub struct ReleaseAsset {
     pub browser_download_url: String,
@@ -32,39 +44,84 @@ pub struct ReleaseAsset {
 
 pub struct GithubApi {
     client: Client,
+    api_url: String,
+    authenticated: bool,
 }
 impl GithubApi {
-    pub fn default() -> Self {
+    pub fn new(api_url: &str, token: Option<&str>) -> Self {
         let mut headers = HeaderMap::new();
         headers.insert("User-Agent", HeaderValue::from_str(&format!("{}/{}", env!("CARGO_PKG_NAME"), env!("CARGO_PKG_VERSION"))).unwrap());
         headers.insert("Accept", HeaderValue::from_str("application/vnd.github+json").unwrap());
         headers.insert("X-GitHub-Api-Version", HeaderValue::from_str(API_VERSION).unwrap());
+        if let Some(token) = token {
+            let mut auth = HeaderValue::from_str(&format!("Bearer {token}")).expect("invalid github token");
+            auth.set_sensitive(true);
+            headers.insert("Authorization", auth);
+        }
 
         GithubApi {
             client: Client::builder().default_headers(headers).build().unwrap(),
+            api_url: api_url.to_owned(),
+            authenticated: token.is_some(),
         }
     }
 
-    async fn fetch<T: DeserializeOwned>(&self, f: impl FnOnce() -> RequestBuilder) -> Result<T> {
-        let res = f().send().await?;
-        check_ratelimit(&res)?;
+    async fn send(&self, f: impl Fn() -> RequestBuilder) -> Result<Response> {
+        let res = request::send_cached(f).await?;
+        if let Err(err) = res.error_for_status_ref() {
+            let err = match err.status().unwrap() {
+                StatusCode::FORBIDDEN | StatusCode::TOO_MANY_REQUESTS => ratelimit_error(&res, self.authenticated).unwrap_or(Error::Reqwest(err)),
+                StatusCode::NOT_FOUND => Error::NotFound(err.url().unwrap().to_string()),
+                _ => Error::Reqwest(err),
+            };
 
-        Ok(res.error_for_status()?.json().await?)
+            return Err(err);
+        }
+
+        Ok(res)
+    }
+
+    async fn fetch<T: DeserializeOwned>(&self, f: impl Fn() -> RequestBuilder) -> Result<T> {
+        Ok(self.send(f).await?.json().await?)
     }
 
-    async fn get<T: DeserializeOwned>(&self, url: &str) -> Result<T> {
-        self.fetch(|| self.client.get(url)).await
+    async fn get<T: DeserializeOwned>(&self, path: &str) -> Result<T> {
+        self.fetch(|| self.client.get(format!("{}{path}", self.api_url))).await
+    }
+
+    // github lists at most 100 items per page, the Link header points to the next one
+    async fn get_page<T: DeserializeOwned>(&self, url: &str) -> Result<(Vec<T>, Option<String>)> {
+        let res = self.send(|| self.client.get(url)).await?;
+        let next = res.headers().get("link")
+            .and_then(|link| link.to_str().ok())
+            .and_then(|link| regex_captures!(r#"<([^>]+)>;\s*rel="next""#, link))
+            .map(|(_, url)| url.to_owned());
+
+        Ok((res.json().await?, next))
+    }
+
+    async fn get_all_pages<T: DeserializeOwned>(&self, path: &str) -> Result<Vec<T>> {
+        let mut items = Vec::new();
+        let mut next = Some(format!("{}{path}?per_page=100", self.api_url));
+
+        while let Some(url) = next {
+            let (page, next_url) = self.get_page(&url).await?;
+            items.extend(page);
+            next = next_url;
+        }
+
+        Ok(items)
     }
 
     pub async fn get_repo(&self, owner: &str, repo: &str) -> Result<Repository> {
-        self.get(&format!("{API_URL}/repos/{owner}/{repo}")).await
+        self.get(&format!("/repos/{owner}/{repo}")).await
     }
 
     pub async fn get_repos(&'static self, repos: Vec<(String, String)>) -> Result<Vec<Repository>> {
         let mut tasks: JoinSet<Result<Repository>> = JoinSet::new();
         for repo in repos {
             let task = async move {
-                self.get(&format!("{API_URL}/repos/{}/{}", repo.0, repo.1)).await
+                self.get(&format!("/repos/{}/{}", repo.0, repo.1)).await
             };
             tasks.spawn(task);
         }
@@ -76,31 +133,52 @@ impl GithubApi {
     }
 
     pub async fn list_releases(&self, owner: &str, repo: &str) -> Result<Vec<GithubRelease>> {
-        self.get(&format!("{API_URL}/repos/{owner}/{repo}/releases")).await
+        self.get_all_pages(&format!("/repos/{owner}/{repo}/releases")).await
+    }
+
+    // for callers that can stop before reaching the oldest releases
+    pub fn release_pages(&self, owner: &str, repo: &str) -> ReleasePages<'_> {
+        ReleasePages {
+            api: self,
+            next: Some(format!("{}/repos/{owner}/{repo}/releases?per_page=100", self.api_url)),
+        }
     }
 
     pub async fn get_release_by_tag(&self, owner: &str, repo: &str, tag: &str) -> Result<GithubRelease> {
-        self.get(&format!("{API_URL}/repos/{owner}/{repo}/releases/tags/{tag}")).await
+        self.get(&format!("/repos/{owner}/{repo}/releases/tags/{tag}")).await
     }
 }
 
-fn check_ratelimit(res: &Response) -> Result<()> {
-    let ratelimit_remaining = match res.headers().get("x-ratelimit-remaining") {
-        Some(val) => val.to_str().unwrap(),
-        None => return Ok(()),
-    };
-    
-    if ratelimit_remaining == "0" {
-        Err(Error::RateLimitExceeded(
-            "github".to_owned(),
-            res.headers()
-                .get("x-ratelimit-reset")
-                .unwrap()
-                .to_str()
-                .unwrap()
-                .to_owned(),
-        ))
-    } else {
-        Ok(())
+// releases newest first, one page at a time
+pub struct ReleasePages<'a> {
+    api: &'a GithubApi,
+    next: Option<String>,
+}
+impl ReleasePages<'_> {
+    // None after the last page
+    pub async fn next(&mut self) -> Result<Option<Vec<GithubRelease>>> {
+        let url = match self.next.take() {
+            Some(url) => url,
+            None => return Ok(None),
+        };
+
+        let (releases, next) = self.api.get_page(&url).await?;
+        self.next = next;
+        Ok(Some(releases))
     }
 }
+
+// forbidden is also used for other things, like missing permissions
+fn ratelimit_error(res: &Response, authenticated: bool) -> Option<Error> {
+    let ratelimited = res.status() == StatusCode::TOO_MANY_REQUESTS
+        || res.headers().get("x-ratelimit-remaining").is_some_and(|r| r == "0");
+    if !ratelimited {
+        return None;
+    }
+
+    let err = Error::RateLimitExceeded("github".to_owned(), ratelimit_reset(res));
+    Some(match authenticated {
+        true => err,
+        false => Error::Other(format!("{err}, or set GITHUB_TOKEN or github_token in the emm config for a higher limit")),
+    })
+}
\ No newline at end of file
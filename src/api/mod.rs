@@ -1,3 +1,16 @@
+use clap::ValueEnum;
+
 pub mod curseforge;
 pub mod modrinth;
-pub mod github;
\ No newline at end of file
+pub mod github;
+pub mod request;
+
+// how search results are ordered, modrinth and curseforge both support these
+#[derive(Debug, Clone, Copy, PartialEq, Default, ValueEnum)]
+#[clap(rename_all = "lowercase")]
+pub enum SearchSort {
+    #[default]
+    Relevance,
+    Downloads,
+    Updated
+}
\ No newline at end of file
@@ -6,8 +6,9 @@ use reqwest::{Client, RequestBuilder, StatusCode};
 use serde::{de::DeserializeOwned, Deserialize, Serialize};
 
 use crate::{
+    api::{request::{self, ratelimit_reset}, SearchSort},
     error::{Error, Result},
-    structs::{index::ProjectType, pack::Modpack},
+    structs::index::{ProjectType, ReleaseChannel},
 };
 
 #[derive(Debug, Deserialize, Serialize, Clone)]
@@ -44,8 +45,10 @@ pub struct Version {
     pub loaders: Vec<String>,
     pub id: String,
     pub project_id: String,
+    pub version_type: ReleaseChannel,
     pub date_published: DateTime<Utc>,
     pub files: Vec<VersionFile>,
+    pub changelog: Option<String>,
 }
 
 #[derive(Debug, Deserialize, Serialize, Clone)]
@@ -96,6 +99,12 @@ pub struct SearchResult {
 pub struct SearchHit {
     pub title: String,
     pub project_id: String,
+    pub slug: String,
+    pub author: String,
+    pub downloads: u64,
+    pub date_modified: DateTime<Utc>,
+    // loaders are categories too
+    pub categories: Vec<String>,
 }
 
 impl Serialize for SearchFacet {
@@ -106,7 +115,7 @@ impl Serialize for SearchFacet {
         let string = match self {
             SearchFacet::ProjectType(project_type) => format!(
                 "project_type: {}",
-                serde_json::to_string(&project_type).unwrap()
+                serde_json::to_string(&project_type).unwrap().trim_matches('"')
             ),
             SearchFacet::Categories(category) => format!("categories: {category}"),
             SearchFacet::Versions(version) => format!("versions: {version}"),
@@ -116,32 +125,26 @@ impl Serialize for SearchFacet {
     }
 }
 
-const API_URL: &str = "https://api.modrinth.com/v2";
+pub const API_URL: &str = "https://api.modrinth.com/v2";
 
 pub struct ModrinthAPI {
     client: Client,
+    api_url: String,
 }
 impl ModrinthAPI {
     // user agent: https://docs.modrinth.com/#section/User-Agents
-    pub fn new(user_agent: &str) -> Self {
+    pub fn new(user_agent: &str, api_url: &str) -> Self {
         ModrinthAPI {
             client: Client::builder().user_agent(user_agent).build().unwrap(),
+            api_url: api_url.to_owned(),
         }
     }
 
-    async fn fetch<T: DeserializeOwned>(&self, f: impl FnOnce() -> RequestBuilder) -> Result<T> {
-        let res = f().send().await?;
+    async fn fetch<T: DeserializeOwned>(&self, f: impl Fn() -> RequestBuilder) -> Result<T> {
+        let res = request::send_cached(f).await?;
         if let Err(err) = res.error_for_status_ref() {
             let err = match err.status().unwrap() {
-                StatusCode::TOO_MANY_REQUESTS => Error::RateLimitExceeded(
-                    "modrinth".to_owned(),
-                    res.headers()
-                        .get("X-Ratelimit-Reset")
-                        .unwrap()
-                        .to_str()
-                        .unwrap()
-                        .to_owned(),
-                ),
+                StatusCode::TOO_MANY_REQUESTS => Error::RateLimitExceeded("modrinth".to_owned(), ratelimit_reset(&res)),
                 StatusCode::NOT_FOUND => Error::NotFound(err.url().unwrap().to_string()),
                 StatusCode::GONE => Error::Deprecated(err.to_string()),
                 _ => Error::Reqwest(err),
@@ -153,40 +156,40 @@ impl ModrinthAPI {
         Ok(res.json().await?)
     }
 
-    async fn get<T: DeserializeOwned>(&self, url: &str) -> Result<T> {
-        self.fetch(|| self.client.get(url)).await
+    async fn get<T: DeserializeOwned>(&self, path: &str) -> Result<T> {
+        self.fetch(|| self.client.get(format!("{}{path}", self.api_url))).await
     }
 
-    async fn post<T, B>(&self, url: &str, body: &B) -> Result<T>
+    async fn post<T, B>(&self, path: &str, body: &B) -> Result<T>
     where
         T: DeserializeOwned,
         B: Serialize + ?Sized,
     {
-        self.fetch(|| self.client.post(url).json(body)).await
+        self.fetch(|| self.client.post(format!("{}{path}", self.api_url)).json(body)).await
     }
 
     pub async fn get_project(&self, id: &str) -> Result<Project> {
         check_id(id)?;
-        self.get(&format!("{API_URL}/project/{id}")).await
+        self.get(&format!("/project/{id}")).await
     }
 
     pub async fn get_multiple_projects(&self, ids: &[&str]) -> Result<Vec<Project>> {
-        self.get(&format!("{API_URL}/projects?ids={}", serde_json::to_string(ids).unwrap())).await
+        self.get(&format!("/projects?ids={}", serde_json::to_string(ids).unwrap())).await
     }
 
     pub async fn get_project_versions(&self, id: &str) -> Result<Vec<Version>> {
         check_id(id)?;
-        self.get(&format!("{API_URL}/project/{id}/version")).await
+        self.get(&format!("/project/{id}/version")).await
     }
 
     pub async fn get_version(&self, id: &str) -> Result<Version> {
         check_id(id)?;
-        self.get(&format!("{API_URL}/version/{id}")).await
+        self.get(&format!("/version/{id}")).await
     }
 
     pub async fn get_versions(&self, ids: &[&str]) -> Result<Vec<Version>> {
         self.get(&format!(
-            "{API_URL}/versions?ids={}",
+            "/versions?ids={}",
             serde_json::to_string(ids).unwrap()
         ))
         .await
@@ -200,7 +203,7 @@ impl ModrinthAPI {
         }
 
         self.post(
-            &format!("{API_URL}/version_files"),
+            "/version_files",
             &Body {
                 hashes,
                 algorithm: "sha1".to_owned(),
@@ -224,7 +227,7 @@ impl ModrinthAPI {
         }
 
         self.post(
-            &format!("{API_URL}/version_files/update"),
+            "/version_files/update",
             &Body {
                 hashes,
                 algorithm: "sha1".to_owned(),
@@ -239,13 +242,23 @@ impl ModrinthAPI {
         &self,
         query: &str,
         facets: Vec<Vec<SearchFacet>>,
+        sort: SearchSort,
         limit: &i32,
     ) -> Result<SearchResult> {
-        self.get(&format!(
-            "{API_URL}/search?query={query}&facets={}&limit={limit}",
-            serde_json::to_string(&facets).unwrap()
-        ))
-        .await
+        let index = match sort {
+            SearchSort::Relevance => "relevance",
+            SearchSort::Downloads => "downloads",
+            SearchSort::Updated => "updated",
+        };
+
+        // the query is whatever the user typed, so it has to be encoded
+        let params = [
+            ("query", query.to_owned()),
+            ("facets", serde_json::to_string(&facets).unwrap()),
+            ("index", index.to_owned()),
+            ("limit", limit.to_string()),
+        ];
+        self.fetch(|| self.client.get(format!("{}/search", self.api_url)).query(&params)).await
     }
 }
 
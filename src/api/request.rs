@@ -0,0 +1,176 @@
+use std::{fs, path::{Path, PathBuf}, sync::atomic::{AtomicBool, Ordering}, time::{Duration, SystemTime, UNIX_EPOCH}};
+
+use lazy_static::lazy_static;
+use reqwest::{header::IF_NONE_MATCH, Client, Method, RequestBuilder, Response, StatusCode};
+use serde::{Deserialize, Serialize};
+use sha1::{Digest, Sha1};
+use tokio::{sync::Semaphore, time::sleep};
+
+use crate::{error::{Error, Result}, util::{cache::cache_dir, files::write_atomic}, CONFIG};
+
+// every request of every api client goes through here
+const MAX_CONCURRENT_REQUESTS: usize = 16;
+const MAX_RETRIES: u32 = 4;
+const BACKOFF_BASE: Duration = Duration::from_millis(500);
+// longer rate limits are returned as errors instead of waiting them out
+const MAX_RATELIMIT_WAIT: Duration = Duration::from_secs(60);
+
+lazy_static! {
+    static ref PERMITS: Semaphore = Semaphore::new(MAX_CONCURRENT_REQUESTS);
+    static ref CLIENT: Client = Client::new();
+}
+
+// set by --refresh, cached responses are replaced instead of reused
+pub static REFRESH: AtomicBool = AtomicBool::new(false);
+
+// a GET response stored in the response cache
+#[derive(Deserialize, Serialize)]
+struct CachedResponse {
+    fetched: u64,
+    etag: Option<String>,
+    headers: Vec<(String, String)>,
+    body: String
+}
+
+// sends a request, retrying server errors, network errors and rate limits,
+// the request is rebuilt by f for every attempt
+pub async fn send(f: impl Fn() -> RequestBuilder) -> Result<Response> {
+    let mut attempt = 0;
+    loop {
+        // the permit is only held while sending, waiting out a rate limit shouldnt block other requests
+        let permit = PERMITS.acquire().await.unwrap();
+        let res = f().send().await;
+        drop(permit);
+
+        let wait = match &res {
+            Ok(res) if is_ratelimited(res) => ratelimit_wait(res).or(Some(backoff(attempt))).filter(|w| *w <= MAX_RATELIMIT_WAIT),
+            Ok(res) if res.status().is_server_error() => Some(backoff(attempt)),
+            Err(err) if err.is_connect() || err.is_timeout() => Some(backoff(attempt)),
+            _ => None,
+        };
+
+        match wait {
+            Some(wait) if attempt < MAX_RETRIES => {
+                sleep(wait).await;
+                attempt += 1;
+            },
+            _ => return Ok(res?),
+        }
+    }
+}
+
+// like send, but GET responses are reused from the response cache when it's turned on in the config.
+// after the ttl runs out the cached response is revalidated with its etag,
+// unchanged github responses dont count against the rate limit that way
+pub async fn send_cached(f: impl Fn() -> RequestBuilder) -> Result<Response> {
+    let ttl = match CONFIG.response_cache_ttl {
+        Some(ttl) => ttl,
+        None => return send(f).await,
+    };
+
+    let request = f().build()?;
+    if request.method() != Method::GET {
+        return send(f).await;
+    }
+
+    let path = response_path(request.url().as_str())?;
+    let cached = match REFRESH.load(Ordering::Relaxed) {
+        true => None,
+        false => fs::read_to_string(&path).ok().and_then(|c| serde_json::from_str::<CachedResponse>(&c).ok()),
+    };
+
+    if let Some(cached) = cached.as_ref().filter(|c| now().saturating_sub(c.fetched) < ttl) {
+        return cached.to_response();
+    }
+
+    let etag = cached.as_ref().and_then(|c| c.etag.clone());
+    let res = send(|| match &etag {
+        Some(etag) => f().header(IF_NONE_MATCH, etag),
+        None => f(),
+    }).await?;
+
+    let cached = match (res.status(), cached) {
+        (StatusCode::NOT_MODIFIED, Some(cached)) => CachedResponse { fetched: now(), ..cached },
+        (StatusCode::OK, _) => {
+            let headers = res.headers().iter()
+                .filter_map(|(name, value)| Some((name.to_string(), value.to_str().ok()?.to_owned())))
+                .collect();
+            let etag = res.headers().get("etag").and_then(|e| e.to_str().ok()).map(|e| e.to_owned());
+            CachedResponse { fetched: now(), etag, headers, body: res.text().await? }
+        },
+        _ => return Ok(res),
+    };
+
+    write_cached(&path, &cached)?;
+    cached.to_response()
+}
+
+// for urls that dont belong to an api client, like loader meta and downloads
+pub async fn get(url: &str) -> Result<Response> {
+    Ok(send(|| CLIENT.get(url)).await?.error_for_status()?)
+}
+
+pub async fn get_cached(url: &str) -> Result<Response> {
+    Ok(send_cached(|| CLIENT.get(url)).await?.error_for_status()?)
+}
+
+pub fn responses_dir() -> Result<PathBuf> {
+    Ok(cache_dir()?.join("responses"))
+}
+
+fn response_path(url: &str) -> Result<PathBuf> {
+    Ok(responses_dir()?.join(format!("{:x}.json", Sha1::digest(url))))
+}
+
+fn write_cached(path: &Path, cached: &CachedResponse) -> Result<()> {
+    fs::create_dir_all(path.parent().unwrap())?;
+    write_atomic(path, serde_json::to_string(cached)?.as_bytes())
+}
+
+impl CachedResponse {
+    fn to_response(&self) -> Result<Response> {
+        let mut builder = http::Response::builder().status(StatusCode::OK);
+        for (name, value) in &self.headers {
+            builder = builder.header(name, value);
+        }
+
+        Ok(builder.body(self.body.clone()).map_err(|err| Error::Other(err.to_string()))?.into())
+    }
+}
+
+fn now() -> u64 {
+    SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default().as_secs()
+}
+
+// seconds until the rate limit resets, for error messages
+pub fn ratelimit_reset(res: &Response) -> String {
+    ratelimit_wait(res).map(|w| w.as_secs().to_string()).unwrap_or("a few".into())
+}
+
+fn backoff(attempt: u32) -> Duration {
+    BACKOFF_BASE * 2u32.pow(attempt)
+}
+
+// github answers with 403 when the rate limit is used up
+fn is_ratelimited(res: &Response) -> bool {
+    res.status() == StatusCode::TOO_MANY_REQUESTS
+        || (res.status() == StatusCode::FORBIDDEN && header(res, "x-ratelimit-remaining").is_some_and(|r| r == 0))
+}
+
+fn ratelimit_wait(res: &Response) -> Option<Duration> {
+    if let Some(seconds) = header(res, "retry-after") {
+        return Some(Duration::from_secs(seconds));
+    }
+
+    // modrinth sends seconds until the reset, github the time of the reset
+    let reset = header(res, "x-ratelimit-reset")?;
+    let now = now();
+    match reset > now / 2 {
+        true => Some(Duration::from_secs(reset.saturating_sub(now))),
+        false => Some(Duration::from_secs(reset)),
+    }
+}
+
+fn header(res: &Response, name: &str) -> Option<u64> {
+    res.headers().get(name)?.to_str().ok()?.trim().parse().ok()
+}
\ No newline at end of file
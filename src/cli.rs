@@ -1,14 +1,35 @@
 use std::path::PathBuf;
-use clap::{Parser, Subcommand};
+use clap::{Parser, Subcommand, ValueEnum};
 use clap_complete::Shell;
 
-use crate::structs::{self, pack::ModLoader};
+use crate::{api::SearchSort, structs::{index::{ProjectType, Side}, pack::ModLoader}};
 
 #[derive(Parser)]
 #[command(version, about, long_about = None)]
 pub struct Args {
     #[command(subcommand)]
     pub subcommand: Commands,
+
+    /// Ask the apis again instead of using cached responses
+    #[arg(long, global = true)]
+    pub refresh: bool,
+
+    /// Never prompt, questions are answered with their default and
+    /// choices without one fail with the flag to use instead.
+    /// Also used when there is no terminal
+    #[arg(long, short = 'y', visible_alias = "no-input", global = true)]
+    pub yes: bool,
+
+    /// How to print results, json is supported by list, update, migrate, export and search
+    #[arg(long, value_enum, default_value_t = OutputFormat::Text, global = true)]
+    pub output: OutputFormat,
+}
+
+#[derive(Clone, Copy, PartialEq, ValueEnum)]
+#[clap(rename_all = "lowercase")]
+pub enum OutputFormat {
+    Text,
+    Json
 }
 
 #[derive(Subcommand)]
@@ -21,6 +42,10 @@ pub enum Commands {
     #[command(alias = "a")]
     Add(AddArgs),
 
+    /// Search modrinth and curseforge for projects
+    #[command(alias = "s")]
+    Search(SearchArgs),
+
     /// Remove a project from the current pack
     #[command(aliases = ["rm", "r"])]
     Remove(RemoveArgs),
@@ -29,6 +54,12 @@ pub enum Commands {
     #[command(alias = "up")]
     Update(UpdateArgs),
 
+    /// Show which addons have updates without changing anything
+    Outdated(OutdatedArgs),
+
+    /// Show the addons and versions that changed between two states of the modpack
+    Diff(DiffArgs),
+
     /// Pin an addon to exclude it from updates
     Pin(PinArgs),
 
@@ -47,6 +78,19 @@ pub enum Commands {
     /// Migrate your modpack to a new minecraft version
     Migrate(MigrateArgs),
 
+    /// Install the modpack's files into an instance folder
+    Install(InstallArgs),
+
+    /// Manage the shared download cache
+    Cache(CacheArgs),
+
+    /// Check the modpack for missing dependencies and incompatible addons
+    #[command(visible_alias = "check")]
+    Doctor,
+
+    /// Show why an addon is in the modpack
+    Why(WhyArgs),
+
     /// Print shell completions for specified shell
     Completion {
         #[clap(value_enum)]
@@ -95,16 +139,75 @@ pub struct AddArgs {
     pub subcommand: AddCommands
 }
 
+#[derive(clap::Args)]
+pub struct SearchArgs {
+    /// What to search for
+    #[arg(required = true)]
+    pub query: Vec<String>,
+
+    /// Where to search
+    #[arg(long, value_enum, default_value_t = SearchSource::All)]
+    pub source: SearchSource,
+
+    /// The type of project to search for, mod by default
+    #[arg(long = "type", value_name = "TYPE")]
+    pub project_type: Option<ProjectType>,
+
+    /// How to order the results
+    #[arg(long, value_enum, default_value_t = SearchSort::Relevance)]
+    pub sort: SearchSort,
+
+    /// Print the results as json
+    #[arg(long, conflicts_with = "add")]
+    pub json: bool,
+
+    /// Choose results to add to the modpack
+    #[arg(long, short = 'a')]
+    pub add: bool
+}
+
+#[derive(Clone, PartialEq, ValueEnum)]
+#[clap(rename_all = "lowercase")]
+pub enum SearchSource {
+    #[value(alias = "modrinth")]
+    Mr,
+    #[value(alias = "curseforge")]
+    Cf,
+    All
+}
+
 #[derive(clap::Args)]
 pub struct RemoveArgs {
     /// List of addon names/ids you want to remove from this modpack
-    pub addons: Vec<String>
+    pub addons: Vec<String>,
+
+    /// Remove dependencies that nothing needs anymore without asking
+    #[arg(long, short = 'd')]
+    pub dependencies: bool
+}
+
+#[derive(clap::Args)]
+pub struct WhyArgs {
+    /// The addon name/id to explain
+    pub addon: String
 }
 
 #[derive(clap::Args)]
 pub struct UpdateArgs {
-    /// List of addon names/ids to update
-    pub addons: Option<Vec<String>>
+    /// List of addon names/ids to update, changed local files are only re-pinned when named here
+    pub addons: Option<Vec<String>>,
+
+    /// Print the updates that would be applied without changing anything
+    #[arg(long)]
+    pub dry_run: bool,
+
+    /// Choose which updates to apply
+    #[arg(long, short = 'i', conflicts_with = "dry_run")]
+    pub interactive: bool,
+
+    /// Show the changelog of every update
+    #[arg(long, short = 'c')]
+    pub changelog: bool
 }
 
 #[derive(clap::Args)]
@@ -123,6 +226,33 @@ pub struct UnpinArgs {
     pub addon: String
 }
 
+#[derive(clap::Args)]
+pub struct OutdatedArgs {
+    /// List of addon names/ids to check
+    pub addons: Option<Vec<String>>,
+
+    /// Exit with an error when updates are available, pinned addons dont count
+    #[arg(long)]
+    pub exit_code: bool,
+
+    /// Print the report in markdown format
+    #[arg(long, short = 'm')]
+    pub markdown: bool
+}
+
+#[derive(clap::Args)]
+pub struct DiffArgs {
+    /// A git revision, a modpack or packwiz folder, or an mrpack file
+    pub from: String,
+
+    /// What to compare against, the current modpack if left out
+    pub to: Option<String>,
+
+    /// Print the changes in markdown format
+    #[arg(long, short = 'm')]
+    pub markdown: bool
+}
+
 #[derive(clap::Args)]
 pub struct ListArgs {
     /// List addons in markdown format
@@ -144,11 +274,53 @@ pub struct ExportArgs {
 
 #[derive(clap::Args)]
 pub struct MigrateArgs {
+    /// The minecraft version to migrate to, picked from a list if not set
+    pub game_version: Option<String>,
+
     /// Show snapshots in version select
     #[arg(long, short = 's', visible_alias = "snapshots")]
     pub show_snapshots: bool
 }
 
+#[derive(clap::Args)]
+pub struct ExportServerArgs {
+    /// Path to overrides, client only files like resourcepacks are left out
+    #[arg(long, short = 'o')]
+    pub overrides_path: Option<PathBuf>,
+
+    /// Export to this folder instead of a zip file
+    #[arg(long, short = 'd')]
+    pub dir: Option<PathBuf>,
+
+    /// Include addons with an unknown side
+    #[arg(long)]
+    pub include_unknown: bool,
+
+    /// Fail if emm.lock is missing or out of date instead of updating it
+    #[arg(long)]
+    pub locked: bool
+}
+
+#[derive(clap::Args)]
+pub struct InstallArgs {
+    /// The instance folder to install to
+    pub dir: PathBuf,
+
+    /// Only install addons for this side
+    #[arg(long, short = 's')]
+    pub side: Option<Side>,
+
+    /// Fail if emm.lock is missing or out of date instead of updating it
+    #[arg(long)]
+    pub locked: bool
+}
+
+#[derive(clap::Args)]
+pub struct CacheArgs {
+    #[command(subcommand)]
+    pub subcommand: CacheCommands
+}
+
 #[derive(Subcommand)]
 pub enum AddCommands {
     /// Add projects from modrinth
@@ -161,7 +333,14 @@ pub enum AddCommands {
 
     /// Add projects from a github repo's releases
     #[command(visible_alias = "gh")]
-    Github(AddGithubArgs)
+    Github(AddGithubArgs),
+
+    /// Add a file from the modpack folder,
+    /// looks it up on modrinth and curseforge first
+    File(AddFileArgs),
+
+    /// Add a file from a direct download url
+    Url(AddUrlArgs)
 }
 
 #[derive(clap::Args)]
@@ -172,7 +351,19 @@ pub struct AddModrinthArgs {
 
     /// The version id of the project to add, ignores compatability checks
     #[arg(long, short = 'v')]
-    pub version: Option<String>
+    pub version: Option<String>,
+
+    /// Also add the optional dependencies of the projects
+    #[arg(long, conflicts_with = "prompt_optional")]
+    pub with_optional: bool,
+
+    /// Choose which optional dependencies of the projects to add
+    #[arg(long)]
+    pub prompt_optional: bool,
+
+    /// The type of project to search for, mod by default
+    #[arg(long = "type", value_name = "TYPE")]
+    pub project_type: Option<ProjectType>
 }
 
 #[derive(clap::Args)]
@@ -183,7 +374,19 @@ pub struct AddCurseforgeArgs  {
 
     /// The version id of the project to add, ignores compatability checks
     #[arg(long, short = 'v')]
-    pub version: Option<i32>
+    pub version: Option<i32>,
+
+    /// Also add the optional dependencies of the projects
+    #[arg(long, conflicts_with = "prompt_optional")]
+    pub with_optional: bool,
+
+    /// Choose which optional dependencies of the projects to add
+    #[arg(long)]
+    pub prompt_optional: bool,
+
+    /// The type of project to search for, mod by default
+    #[arg(long = "type", value_name = "TYPE")]
+    pub project_type: Option<ProjectType>
 }
 
 #[derive(clap::Args)]
@@ -198,8 +401,46 @@ pub struct AddGithubArgs {
     pub tag: Option<String>,
 
     /// Use the first release asset
-    #[arg(long, short = 'f')]
-    pub first_asset: bool
+    #[arg(long, short = 'f', conflicts_with = "asset")]
+    pub first_asset: bool,
+
+    /// Glob pattern for the release asset to use, like 'mymod-fabric-*.jar'
+    #[arg(long)]
+    pub asset: Option<String>
+}
+
+#[derive(clap::Args)]
+pub struct AddFileArgs {
+    /// Path to the file, has to be inside the modpack folder
+    #[arg(required = true)]
+    pub path: PathBuf,
+
+    /// The name to use if the file is added as a local file
+    #[arg(long, short = 'n')]
+    pub name: Option<String>,
+
+    /// Dont look the file up on modrinth or curseforge
+    #[arg(long, short = 'l')]
+    pub local: bool
+}
+
+#[derive(clap::Args)]
+pub struct AddUrlArgs {
+    /// The url to download the file from
+    #[arg(required = true)]
+    pub url: String,
+
+    /// The name to use for the addon, defaults to the file name
+    #[arg(long, short = 'n')]
+    pub name: Option<String>,
+
+    /// The expected sha1 hash of the file
+    #[arg(long)]
+    pub sha1: Option<String>,
+
+    /// The expected sha512 hash of the file
+    #[arg(long)]
+    pub sha512: Option<String>
 }
 
 #[derive(Subcommand)]
@@ -247,25 +488,57 @@ pub enum ExportCommands {
 
     /// Export to a packwiz pack
     #[command(visible_alias = "pw")]
-    Packwiz(ExportPackwizArgs)
+    Packwiz(ExportPackwizArgs),
+
+    /// Export a server pack with only server side addons
+    #[command(visible_alias = "sv")]
+    Server(ExportServerArgs)
 }
 
 #[derive(clap::Args)]
 pub struct ExportModrinthArgs {
     /// Path to mrpack overrides
     #[arg(long, short = 'o')]
-    pub overrides_path: Option<PathBuf>
+    pub overrides_path: Option<PathBuf>,
+
+    /// Fail if emm.lock is missing or out of date instead of updating it
+    #[arg(long)]
+    pub locked: bool
 }
 
 #[derive(clap::Args)]
 pub struct ExportCurseforgeArgs {
     /// Path to overrides
     #[arg(long, short = 'o')]
-    pub overrides_path: Option<PathBuf>
+    pub overrides_path: Option<PathBuf>,
+
+    /// Fail if emm.lock is missing or out of date instead of updating it
+    #[arg(long)]
+    pub locked: bool
 }
 
 #[derive(clap::Args)]
 pub struct ExportPackwizArgs {
     /// Output folder path
-    pub export_path: PathBuf
+    pub export_path: PathBuf,
+
+    /// Fail if emm.lock is missing or out of date instead of updating it
+    #[arg(long)]
+    pub locked: bool
+}
+
+#[derive(Subcommand)]
+pub enum CacheCommands {
+    /// Show where the cache is and how big it is
+    Info,
+
+    /// Remove files that havent been used in a while
+    Prune {
+        /// Remove files unused for this many days
+        #[arg(long, short = 'd', default_value_t = 30)]
+        days: u64
+    },
+
+    /// Remove all cached files
+    Clear
 }
\ No newline at end of file
@@ -6,9 +6,9 @@ use dialoguer::Select;
 use indicatif::ProgressBar;
 use tokio::{task::JoinSet, try_join};
 
-use crate::{api::curseforge::{File, FileDependency, FileRelationType}, cli::AddCurseforgeArgs, error::{Error, Result}, structs::{index::{Addon, AddonOptions, AddonSource, CurseforgeSource, Index, ProjectType, Side}, pack::Modpack}, util::FilterVersions, CURSEFORGE};
+use crate::{api::{curseforge::{FileDependency, FileRelationType}, SearchSort}, cli::AddCurseforgeArgs, error::{Error, Result}, structs::{index::{Addon, AddonOptions, AddonSource, CurseforgeSource, Index, ProjectType}, pack::Modpack}, util::{curseforge::{detect_side, project_type_side}, get_version_filters, prompt, FilterVersions}, CURSEFORGE};
 
-use super::{add_to_index, handle_checked};
+use super::{add_to_index, as_dependency, handle_checked, record_dependents, select_optional, CheckedIds};
 
 pub async fn add_curseforge(args: AddCurseforgeArgs) -> Result<()> {
     let modpack = Arc::new(Modpack::read()?);
@@ -25,28 +25,28 @@ pub async fn add_curseforge(args: AddCurseforgeArgs) -> Result<()> {
         if !args.ids.len() == 1 {
             progress.set_message(format!("Adding mods {}/{}", idx + 1, args.ids.len()))
         }
-        match resolve_mod(&modpack, id, args.version).await {
+        match resolve_mod(&modpack, id, args.version, args.project_type.as_ref().unwrap_or(&ProjectType::Mod)).await {
             Ok(addon) => addons.push(addon),
             Err(err) => match err {
                 Error::NotFound(_) | Error::InvalidId(_) => to_search.push(id.as_str()),
-                Error::NoCompatibleVersions(err) => println!("{}", style(&err.to_string()).color256(166)),
+                Error::NoCompatibleVersions(_) => println!("{}", style(&err.to_string()).color256(166)),
                 _ => return Err(err)
             },
         };
     }
 
-    let search_res = search_ids(&modpack, to_search.as_slice(), &progress).await?;
+    let search_res = search_ids(&modpack, to_search.as_slice(), args.project_type.as_ref().unwrap_or(&ProjectType::Mod), &progress).await?;
     addons.extend(search_res);
 
     progress.set_message("Finding dependencies");
 
     let index = Index::read().await?;
-    let checked_ids = Arc::new(Mutex::new(
+    let checked_ids = CheckedIds::new(
         // use index & added mods for checked ids as default
         index.addons.iter().map(|m| m.generic_id())
             .chain(addons.iter().map(|m| m.generic_id()))
             .collect()
-    ));
+    );
     let mut tasks: JoinSet<Result<Vec<Addon>>> = JoinSet::new();
 
     for addon in addons.clone() {
@@ -61,14 +61,32 @@ pub async fn add_curseforge(args: AddCurseforgeArgs) -> Result<()> {
     }
 
     // wait for tasks to finish and push dependencies to addons
+    let added_count = addons.len();
     while let Some(res) = tasks.join_next().await { addons.extend(res??) }
 
+    if args.with_optional || args.prompt_optional {
+        progress.set_message("Finding optional dependencies");
+        let mut optional = Vec::new();
+        for addon in &addons[..added_count] {
+            optional.extend(get_optional_dependencies(&modpack, addon, &checked_ids).await?);
+        }
+
+        let optional = select_optional(optional, args.prompt_optional, &checked_ids, &progress)?;
+        for addon in &optional {
+            addons.extend(get_dependencies(&modpack, addon, &checked_ids).await?);
+        }
+        addons.extend(optional);
+    }
+
+    record_dependents(&mut addons, &index, &checked_ids).await?;
     progress.finish_and_clear();
-    add_to_index(addons, &index).await?;
+    // read again, record_dependents can change addons that were already in the index
+    add_to_index(addons, &Index::read().await?).await?;
     Ok(())
 }
 
-async fn resolve_mod(modpack: &Modpack, id: &str, version_id: Option<i32>) -> Result<Addon> {
+// slugs are looked up as project_type, ids can be any type
+pub async fn resolve_mod(modpack: &Modpack, id: &str, version_id: Option<i32>, project_type: &ProjectType) -> Result<Addon> {
     let (cf_mod, files) = if let Ok(id) = id.parse::<i32>() {
         match version_id {
             Some(version_id) => {
@@ -87,7 +105,8 @@ async fn resolve_mod(modpack: &Modpack, id: &str, version_id: Option<i32>) -> Re
             },
         }
     } else {
-        let cf_mod = CURSEFORGE.get_mod_by_slug(id).await?;
+        let class_id = project_type.curseforge_class_id().ok_or(Error::UnsupportedProjectType(id.to_owned()))?;
+        let cf_mod = CURSEFORGE.get_mod_by_slug(id, class_id).await?;
         let files = CURSEFORGE.get_mod_files(&cf_mod.id).await?;
 
         (cf_mod, files)
@@ -95,29 +114,43 @@ async fn resolve_mod(modpack: &Modpack, id: &str, version_id: Option<i32>) -> Re
 
     let project_type = ProjectType::try_from(cf_mod.class_id.unwrap()).map_err(|_| Error::UnsupportedProjectType(cf_mod.name.clone()))?;
 
-    let compatibles = files.filter_compatible(modpack, &project_type);
+    let compatibles = files
+        .filter_compatible(modpack, &project_type)
+        .filter_release_channel(modpack.options.min_release_channel.as_ref());
     if compatibles.is_empty() {
         return Err(Error::NoCompatibleVersions(cf_mod.name));
     }
 
+    let file = compatibles.best_match(modpack).unwrap();
+    let side = match project_type {
+        ProjectType::Mod => detect_side(&file).await?,
+        _ => project_type_side(&project_type),
+    };
+
     Ok(Addon {
         name: cf_mod.name,
         project_type,
-        side: Side::Both,
+        side,
         source: AddonSource::Curseforge(CurseforgeSource {
             id: cf_mod.id,
-            version: compatibles.best_match(modpack).unwrap().id,
+            version: file.id,
         }),
         options: Some(AddonOptions::default()),
         filename: Some(format!("{}.toml", cf_mod.slug))
     })
 }
 
-async fn search_ids(modpack: &Modpack, strings: &[&str], progress: &ProgressBar) -> Result<Vec<Addon>> {
+async fn search_ids(modpack: &Modpack, strings: &[&str], project_type: &ProjectType, progress: &ProgressBar) -> Result<Vec<Addon>> {
     let mut results = Vec::new();
+    let (game_versions, loaders) = get_version_filters(modpack);
+    // only mods are made for a loader
+    let loaders = match project_type {
+        ProjectType::Mod => loaders,
+        _ => Vec::new(),
+    };
     
     for string in strings {
-        let hits = CURSEFORGE.search(string, &modpack.versions.minecraft, &modpack.versions.loader, &20).await?;
+        let hits = CURSEFORGE.search(string, &game_versions, &loaders, project_type.curseforge_class_id().unwrap(), SearchSort::Relevance, &20).await?;
         if hits.is_empty() {
             println!("{}", style(format!("Searching for {} returned no results", string)).color256(166));
             continue;
@@ -129,6 +162,7 @@ async fn search_ids(modpack: &Modpack, strings: &[&str], progress: &ProgressBar)
         let chosen = if let Some(exact_match) = titles.iter().position(|t| t.to_lowercase() == string.to_lowercase()) {
             exact_match
         } else {
+            prompt::require_input(&format!("Choosing a search result for '{string}'"), "use the exact title, id or slug")?;
             let selected = progress.suspend(|| {
                 Select::new()
                     .with_prompt(format!("search results for '{}'", string))
@@ -144,7 +178,7 @@ async fn search_ids(modpack: &Modpack, strings: &[&str], progress: &ProgressBar)
             }
         };
 
-        let addon = resolve_mod(modpack, &hits[chosen].id.to_string(), None).await?;
+        let addon = resolve_mod(modpack, &hits[chosen].id.to_string(), None, project_type).await?;
         results.push(addon)
     }
 
@@ -152,7 +186,7 @@ async fn search_ids(modpack: &Modpack, strings: &[&str], progress: &ProgressBar)
 }
 
 #[async_recursion]
-async fn get_dependencies(modpack: &Modpack, addon: &Addon, checked_ids: &Arc<Mutex<Vec<String>>>) -> Result<Vec<Addon>> {
+pub async fn get_dependencies(modpack: &Modpack, addon: &Addon, checked_ids: &Arc<Mutex<CheckedIds>>) -> Result<Vec<Addon>> {
     let mut dependencies = Vec::new();
     let source = match &addon.source {
         AddonSource::Curseforge(source) => source,
@@ -168,14 +202,40 @@ async fn get_dependencies(modpack: &Modpack, addon: &Addon, checked_ids: &Arc<Mu
     
     for dep in mod_dependencies {
         // avoid rechecking a dependency multiple times
-        if handle_checked(&dep.mod_id.to_string(), checked_ids) {
+        if handle_checked(&dep.mod_id.to_string(), addon, checked_ids) {
             continue;
         }
         
-        let resolved_addon = resolve_mod(modpack, &dep.mod_id.to_string(), None).await?;
+        let resolved_addon = as_dependency(resolve_mod(modpack, &dep.mod_id.to_string(), None, &ProjectType::Mod).await?);
         dependencies.extend(get_dependencies(modpack, &resolved_addon, checked_ids).await?);
         dependencies.push(resolved_addon)
     }
 
+    Ok(dependencies)
+}
+
+async fn get_optional_dependencies(modpack: &Modpack, addon: &Addon, checked_ids: &Arc<Mutex<CheckedIds>>) -> Result<Vec<Addon>> {
+    let mut dependencies = Vec::new();
+    let source = match &addon.source {
+        AddonSource::Curseforge(source) => source,
+        _ => unreachable!()
+    };
+
+    let file = CURSEFORGE.get_mod_file(&source.id, &source.version).await?;
+    let optional_dependencies: Vec<FileDependency> = file.dependencies.into_iter().filter(|f| matches!(f.relation_type, FileRelationType::OptionalDependency)).collect();
+
+    for dep in optional_dependencies {
+        if handle_checked(&dep.mod_id.to_string(), addon, checked_ids) {
+            continue;
+        }
+
+        match resolve_mod(modpack, &dep.mod_id.to_string(), None, &ProjectType::Mod).await {
+            Ok(addon) => dependencies.push(addon),
+            // an optional dependency that cant be added is skipped
+            Err(Error::NotFound(_) | Error::NoCompatibleVersions(_) | Error::UnsupportedProjectType(_)) => continue,
+            Err(err) => return Err(err),
+        }
+    }
+
     Ok(dependencies)
 }
\ No newline at end of file
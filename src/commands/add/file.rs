@@ -0,0 +1,120 @@
+use std::{env, fs, path::{Path, PathBuf}, time::Duration};
+
+use console::style;
+use indicatif::ProgressBar;
+use path_clean::clean;
+
+use crate::{api::curseforge::CurseAPI, cli::AddFileArgs, error::{Error, Result}, structs::{index::{Addon, AddonOptions, AddonSource, Index, LocalSource, ProjectType, Side}, jar::JarMetadata, pack::Modpack}, util::{files::{hash_bytes, is_local_path}, get_version_filters, jar::detect_project_type, FilterVersions}, CURSEFORGE, MODRINTH};
+
+use super::{add_to_index, curseforge, modrinth, record_dependents, CheckedIds};
+
+pub async fn add_file(args: AddFileArgs) -> Result<()> {
+    let modpack = Modpack::read()?;
+    let path = pack_relative_path(&args.path)?;
+
+    if !path.is_file() {
+        return Err(Error::Other(format!("{} is not a file", path.to_string_lossy())));
+    }
+
+    // files in the overrides folder are already part of every export
+    if modpack.options.overrides_path.as_ref().is_some_and(|overrides| path.starts_with(clean(overrides))) {
+        return Err(Error::Other("Files in the overrides folder are already exported, local addons have to be outside of it".into()));
+    }
+
+    let bytes = fs::read(&path)?;
+    let (sha1, sha512) = hash_bytes(&bytes);
+    let index = Index::read().await?;
+
+    if !args.local {
+        let progress = ProgressBar::new_spinner().with_message("Looking up file on modrinth and curseforge");
+        progress.enable_steady_tick(Duration::from_millis(100));
+        let found = identify(&modpack, &index, &sha1, &bytes).await?;
+        progress.finish_and_clear();
+
+        if !found.is_empty() {
+            println!("{}", style(format!("Found {} online, {} can be deleted", found[0].name, path.to_string_lossy())).dim());
+            add_to_index(found, &Index::read().await?).await?;
+            return Ok(());
+        }
+    }
+
+    let metadata = JarMetadata::read(&path)?;
+    if let Some(metadata) = &metadata {
+        let (game_versions, loaders) = get_version_filters(&modpack);
+        if metadata.is_compatible(&game_versions, &loaders) == Some(false) {
+            println!("{}", style(format!("{} {} says it doesnt support this modpack's minecraft version or loader", metadata.id, metadata.version)).color256(166));
+        }
+    }
+
+    let name = match args.name {
+        Some(name) => name,
+        None => path.file_stem().unwrap_or_default().to_string_lossy().to_string(),
+    };
+
+    let addon = Addon {
+        name,
+        project_type: detect_project_type(&path)?,
+        side: metadata.map(|m| m.side).unwrap_or(Side::Unknown),
+        source: AddonSource::Local(LocalSource {
+            path: PathBuf::from(path.to_string_lossy().replace('\\', "/")),
+            sha1,
+            sha512,
+        }),
+        options: Some(AddonOptions::default()),
+        filename: None
+    };
+
+    add_to_index(vec![addon], &index).await?;
+    Ok(())
+}
+
+// the addon (and its dependencies) if the file is on modrinth or curseforge
+async fn identify(modpack: &Modpack, index: &Index, sha1: &str, bytes: &[u8]) -> Result<Vec<Addon>> {
+    let checked_ids = CheckedIds::new(index.addons.iter().map(|a| a.generic_id()).collect());
+
+    if let Some(version) = MODRINTH.versions_from_hashes(&[sha1]).await?.remove(sha1) {
+        let addon = modrinth::resolve_mod(modpack, &version.project_id, Some(&version.id)).await?;
+        // a pinned version isnt checked by resolve_mod, curseforge files are
+        if vec![version].filter_compatible(modpack, &addon.project_type).is_empty() {
+            println!("{}", style(format!("Found {} on modrinth but not for this modpack's version", addon.name)).color256(166));
+        } else {
+            let mut addons = modrinth::get_dependencies(modpack, &addon, &checked_ids).await?;
+            addons.insert(0, addon);
+            record_dependents(&mut addons, index, &checked_ids).await?;
+            return Ok(addons);
+        }
+    }
+
+    let matches = CURSEFORGE.get_fingerprint_matches(&[CurseAPI::hash_curse(bytes)]).await?;
+    if let Some(file) = matches.exact_matches.into_iter().next().map(|m| m.file) {
+        match curseforge::resolve_mod(modpack, &file.mod_id.to_string(), Some(file.id), &ProjectType::Mod).await {
+            Ok(addon) => {
+                let mut addons = curseforge::get_dependencies(modpack, &addon, &checked_ids).await?;
+                addons.insert(0, addon);
+                record_dependents(&mut addons, index, &checked_ids).await?;
+                return Ok(addons);
+            },
+            // curseforge doesnt list the file for this modpack, so keep it local
+            Err(Error::NoCompatibleVersions(name)) => println!("{}", style(format!("Found {name} on curseforge but not for this modpack's version")).color256(166)),
+            Err(err) => return Err(err),
+        }
+    }
+
+    // not found or not for this modpack's version, so it stays a local file
+    Ok(Vec::new())
+}
+
+// local addons are stored relative to the modpack folder
+fn pack_relative_path(path: &Path) -> Result<PathBuf> {
+    let current_dir = env::current_dir()?;
+    let path = match path.strip_prefix(&current_dir) {
+        Ok(relative) => relative.to_path_buf(),
+        Err(_) => path.to_path_buf(),
+    };
+
+    if !is_local_path(&path) {
+        return Err(Error::Other("The file has to be inside the modpack folder".into()));
+    }
+
+    Ok(clean(path))
+}
\ No newline at end of file
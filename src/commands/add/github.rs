@@ -2,7 +2,7 @@ use console::style;
 use dialoguer::Select;
 use lazy_regex::regex_captures;
 
-use crate::{cli::AddGithubArgs, error::{Error, Result}, structs::index::{Addon, AddonOptions, AddonSource, GithubSource, Index, ProjectType, Side}, GITHUB};
+use crate::{cli::AddGithubArgs, error::{Error, Result}, structs::{index::{Addon, AddonOptions, AddonSource, GithubSource, Index, Side}, jar::JarMetadata, lock::Lockfile, pack::Modpack}, util::{cache, get_version_filters, github::{asset_pattern, find_asset}, jar::detect_project_type, prompt}, GITHUB};
 
 pub async fn add_github(args: AddGithubArgs) -> Result<()> {
     // regex to extract user & repo
@@ -12,49 +12,94 @@ pub async fn add_github(args: AddGithubArgs) -> Result<()> {
         None => return Err(Error::Other(format!("{} \nuse a github url or user/repo'", style("Invalid github url").color256(166)))),
     };
 
-    let releases = GITHUB.list_releases(user, repo).await?;
-    let release_names: Vec<&str> = releases.iter().map(|r| r.name.as_str()).collect();
-
+    let modpack = Modpack::read()?;
     let release = match args.tag {
-            Some(tag) => {
-                match releases.iter().find(|r| r.tag_name == tag) {
-                    Some(release) => release.clone(),
-                    None => return Err(Error::Other(format!("Could not find a release tagged '{tag}'"))),
-                }
-            },
-            None => {
-                let idx = Select::new()
+        // only the tagged release is fetched, so old tags of repos with many releases work too
+        Some(tag) => match GITHUB.get_release_by_tag(user, repo, &tag).await {
+            Ok(release) => release,
+            Err(Error::NotFound(_)) => return Err(Error::Other(format!("Could not find a release tagged '{tag}'"))),
+            Err(err) => return Err(err),
+        },
+        None => {
+            let mut releases = GITHUB.list_releases(user, repo).await?;
+            releases.retain(|r| r.release_channel().allowed_by(modpack.options.min_release_channel.as_ref()));
+            if releases.is_empty() {
+                return Err(Error::Other(format!("{user}/{repo} does not have any releases on an allowed release channel")));
+            }
+
+            // without input the newest release is used, github lists those first
+            let release_names: Vec<&str> = releases.iter().map(|r| r.name.as_str()).collect();
+            let idx = match prompt::no_input() {
+                true => 0,
+                false => Select::new()
                     .with_prompt("Select a release")
                     .items(&release_names)
                     .interact()
-                    .unwrap();
-    
-                releases[idx].clone()
-            },
+                    .unwrap(),
+            };
+
+            releases.swap_remove(idx)
+        },
     };
 
-    let asset_index =
-    if args.first_asset || release.assets.len() == 1 {
-        0 
-    } else {
-        let asset_names: Vec<&str> = release.assets.iter().map(|a| a.name.as_str()).collect();
-        Select::new()
-            .with_prompt("Select a release asset")
-            .items(&asset_names)
-            .interact()
-            .unwrap()
+    if release.assets.is_empty() {
+        return Err(Error::Other(format!("Release '{}' does not have any assets", release.tag_name)));
+    }
+
+    let (asset, pattern) = match args.asset {
+        Some(pattern) => match find_asset(&release, &pattern) {
+            Some(asset) => (asset, pattern),
+            None => return Err(Error::Other(format!("No asset in '{}' matches '{pattern}'", release.tag_name))),
+        },
+        None => {
+            let asset_index =
+            if args.first_asset || release.assets.len() == 1 {
+                0 
+            } else {
+                prompt::require_input("Choosing a release asset", "use --asset or --first-asset")?;
+                let asset_names: Vec<&str> = release.assets.iter().map(|a| a.name.as_str()).collect();
+                Select::new()
+                    .with_prompt("Select a release asset")
+                    .items(&asset_names)
+                    .interact()
+                    .unwrap()
+            };
+
+            let asset = &release.assets[asset_index];
+            (asset, asset_pattern(&release, asset))
+        },
     };
+    println!("{}", style(format!("Using assets matching '{pattern}'")).dim());
+
+    // the asset is downloaded here anyway to lock it, so look inside it to see what it is
+    let cached = cache::fetch(&asset.browser_download_url, None, None).await?;
+    let metadata = JarMetadata::read(&cached.path)?;
+    let project_type = detect_project_type(&cached.path)?;
+
+    if let Some(metadata) = &metadata {
+        let (game_versions, loaders) = get_version_filters(&modpack);
+        if metadata.is_compatible(&game_versions, &loaders) == Some(false) {
+            println!("{}", style(format!("{} {} says it doesnt support this modpack's minecraft version or loader", metadata.id, metadata.version)).color256(166));
+        }
 
-    // todo: Ask what type of addon the user's adding (things are exported to "overrides/unknown" rn..)
+        let required: Vec<String> = metadata.dependencies.iter().filter(|d| d.required).map(|d| match d.versions.is_empty() {
+            true => d.id.clone(),
+            false => format!("{} {}", d.id, d.versions.iter().map(|v| v.to_string()).collect::<Vec<String>>().join(" || ")),
+        }).collect();
+        if !required.is_empty() {
+            println!("{}", style(format!("{repo} depends on: {}", required.join(", "))).dim());
+        }
+    }
 
     let addon = Addon {
         name: repo.to_owned(),
-        project_type: ProjectType::Unknown,
-        side: Side::Both,
+        project_type,
+        side: metadata.map(|m| m.side).unwrap_or(Side::Unknown),
         source: AddonSource::Github(GithubSource {
             repo: format!("{user}/{repo}"),
-            tag: release.tag_name,
-            asset_index,
+            tag: release.tag_name.clone(),
+            asset: pattern,
+            asset_index: None,
         }),
         options: Some(AddonOptions::default()),
         filename: None
@@ -62,5 +107,6 @@ pub async fn add_github(args: AddGithubArgs) -> Result<()> {
 
     println!("Adding {repo}");
     Index::write_addons(vec![addon]).await?;
+    Lockfile::update(false).await?;
     Ok(())
 }
@@ -1,18 +1,25 @@
 use std::{fmt::Write, sync::{Arc, Mutex}};
 
 use console::style;
+use dialoguer::MultiSelect;
+use indicatif::ProgressBar;
 
-use crate::{cli::{AddArgs, AddCommands}, error::Result, structs::index::{Addon, Index}};
+use crate::{cli::{AddArgs, AddCommands}, error::Result, structs::{index::{Addon, AddonOptions, Index}, lock::Lockfile}, util::prompt};
 
 pub mod curseforge;
+pub mod file;
 pub mod github;
 pub mod modrinth;
+pub mod url;
 
 pub async fn add(args: AddArgs) -> Result<()> {
+    Index::read_migrated().await?;
     match args.subcommand {
         AddCommands::Modrinth(args) => modrinth::add_modrinth(args).await,
         AddCommands::Curseforge(args) => curseforge::add_curseforge(args).await,
-        AddCommands::Github(args) => github::add_github(args).await
+        AddCommands::Github(args) => github::add_github(args).await,
+        AddCommands::File(args) => file::add_file(args).await,
+        AddCommands::Url(args) => url::add_url(args).await
     }
 }
 
@@ -24,26 +31,132 @@ pub async fn add_to_index(addons: Vec<Addon>, index: &Index) -> Result<()> {
 
     for addon in addons {
         // checking the name as well so you cant add the same mod from both modrinth or curseforge
-        if index.addons.iter().any(|idx_mod| idx_mod.name == addon.name || idx_mod.generic_id() == addon.generic_id()) {
+        if let Some(idx_mod) = index.addons.iter().find(|idx_mod| idx_mod.name == addon.name || idx_mod.generic_id() == addon.generic_id()) {
+            let idx_options = idx_mod.options.clone().unwrap_or_default();
+
+            // adding a dependency on purpose makes it an explicitly added addon
+            if idx_options.added_as_dependency && !addon.options.as_ref().is_some_and(|o| o.added_as_dependency) {
+                writeln!(&mut out, "{} {}", &addon.name, style("(already in the modpack, now added explicitly)").dim()).unwrap();
+                to_add.push(Addon { options: Some(AddonOptions { added_as_dependency: false, ..idx_options }), ..idx_mod.clone() });
+                continue;
+            }
+
             writeln!(&mut out, "{} {}", &addon.name, style("(already in the modpack)").dim()).unwrap();
             continue;
         }
 
-        writeln!(&mut out, "{}", &addon.name).unwrap();
+        match addon.options.as_ref().is_some_and(|o| o.optional) {
+            true => writeln!(&mut out, "{} {}", &addon.name, style("(optional)").dim()).unwrap(),
+            false => writeln!(&mut out, "{}", &addon.name).unwrap(),
+        }
         to_add.push(addon)
     }
 
     print!("{}", out);
     Index::write_addons(to_add).await?;
+    Lockfile::update(false).await?;
     Ok(())
 }
 
+// optional dependencies are either all added or picked from a list,
+// the ones that arent picked can still be added as a dependency of something else
+pub fn select_optional(optional: Vec<Addon>, prompt_optional: bool, checked_ids: &Arc<Mutex<CheckedIds>>, progress: &ProgressBar) -> Result<Vec<Addon>> {
+    let mut optional: Vec<Addon> = optional.into_iter().map(|addon| Addon {
+        options: Some(AddonOptions { optional: true, added_as_dependency: true, ..addon.options.unwrap_or_default() }),
+        ..addon
+    }).collect();
+
+    if !prompt_optional || optional.is_empty() {
+        return Ok(optional);
+    }
+
+    prompt::require_input("Choosing optional dependencies", "use --with-optional instead of --prompt-optional")?;
+
+    let names: Vec<&str> = optional.iter().map(|a| a.name.as_str()).collect();
+    let selected = progress.suspend(|| {
+        MultiSelect::new()
+            .with_prompt("Select optional dependencies to add")
+            .items(&names)
+            .report(false)
+            .interact()
+            .unwrap()
+    });
+
+    let mut index = 0;
+    optional.retain(|addon| {
+        let keep = selected.contains(&index);
+        index += 1;
+        if !keep {
+            let mut checked_lock = checked_ids.lock().unwrap();
+            checked_lock.ids.retain(|id| *id != addon.generic_id());
+            checked_lock.dependents.retain(|(id, _)| *id != addon.generic_id());
+        }
+        keep
+    });
+
+    Ok(optional)
+}
+
+// shared between the dependency lookups of an add, so every project is only resolved once
+pub struct CheckedIds {
+    ids: Vec<String>,
+    // (dependency id, dependent id), including dependencies that were already checked
+    dependents: Vec<(String, String)>,
+}
+
+impl CheckedIds {
+    pub fn new(ids: Vec<String>) -> Arc<Mutex<Self>> {
+        Arc::new(Mutex::new(CheckedIds { ids, dependents: Vec::new() }))
+    }
+}
+
+pub fn as_dependency(addon: Addon) -> Addon {
+    Addon {
+        options: Some(AddonOptions { added_as_dependency: true, ..addon.options.unwrap_or_default() }),
+        ..addon
+    }
+}
+
+// store which addons depend on which in the dependency_of option,
+// addons already in the index that gained a dependent are written back
+pub async fn record_dependents(addons: &mut [Addon], index: &Index, checked_ids: &Arc<Mutex<CheckedIds>>) -> Result<()> {
+    let dependents = checked_ids.lock().unwrap().dependents.clone();
+    let mut updated: Vec<Addon> = Vec::new();
+
+    for (id, dependent) in dependents.into_iter().filter(|(id, dependent)| id != dependent) {
+        let addon = match addons.iter_mut().find(|a| a.generic_id() == id) {
+            Some(addon) => addon,
+            None => match updated.iter().position(|a| a.generic_id() == id) {
+                Some(position) => &mut updated[position],
+                None => match index.addons.iter().find(|a| a.generic_id() == id) {
+                    Some(addon) => {
+                        updated.push(addon.clone());
+                        updated.last_mut().unwrap()
+                    },
+                    None => continue,
+                },
+            },
+        };
+
+        let options = addon.options.get_or_insert_with(Default::default);
+        if !options.dependency_of.contains(&dependent) {
+            options.dependency_of.push(dependent);
+        }
+    }
+
+    // only write index addons that actually changed
+    updated.retain(|a| !index.addons.contains(a));
+    Index::write_addons(updated).await
+}
+
 // using another non-async function to lock the mutex to not have a "held across await" error
-fn handle_checked(id: &String, checked_ids: &Arc<Mutex<Vec<String>>>) -> bool {
+fn handle_checked(id: &String, dependent: &Addon, checked_ids: &Arc<Mutex<CheckedIds>>) -> bool {
     let mut checked_lock = checked_ids.lock().unwrap();
-    let is_checked = checked_lock.contains(id);
+    checked_lock.dependents.push((id.to_owned(), dependent.generic_id()));
+
+    let is_checked = checked_lock.ids.contains(id);
     if !is_checked {
-        checked_lock.push(id.to_owned());
+        checked_lock.ids.push(id.to_owned());
     }
 
     is_checked
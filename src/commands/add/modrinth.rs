@@ -6,9 +6,9 @@ use dialoguer::Select;
 use indicatif::ProgressBar;
 use tokio::{task::JoinSet, try_join};
 
-use crate::{api::modrinth::{DependencyType, SearchFacet, Version, VersionDependency}, cli::AddModrinthArgs, error::{Error, Result}, structs::{index::{Addon, AddonOptions, AddonSource, Index, ModrinthSource, ProjectType}, pack::Modpack}, util::{modrinth::get_side, FilterVersions}, MODRINTH};
+use crate::{api::{modrinth::{DependencyType, Version, VersionDependency}, SearchSort}, cli::AddModrinthArgs, error::{Error, Result}, structs::{index::{Addon, AddonOptions, AddonSource, Index, ModrinthSource, ProjectType}, pack::Modpack}, util::{modrinth::{get_side, search_facets}, prompt, FilterVersions}, MODRINTH};
 
-use super::{add_to_index, handle_checked};
+use super::{add_to_index, as_dependency, handle_checked, record_dependents, select_optional, CheckedIds};
 
 pub async fn add_modrinth(args: AddModrinthArgs) -> Result<()> {
     let modpack = Arc::new(Modpack::read()?);
@@ -35,18 +35,18 @@ pub async fn add_modrinth(args: AddModrinthArgs) -> Result<()> {
         };
     }
 
-    let search_res = search_ids(&modpack, to_search.as_slice(), &progress).await?;
+    let search_res = search_ids(&modpack, to_search.as_slice(), args.project_type.as_ref().unwrap_or(&ProjectType::Mod), &progress).await?;
     addons.extend(search_res);
 
     progress.set_message("Finding dependencies");
 
     let index = Index::read().await?;
-    let checked_ids = Arc::new(Mutex::new(
+    let checked_ids = CheckedIds::new(
         // use index & added mods for checked ids as default
         index.addons.iter().map(|m| m.generic_id())
             .chain(addons.iter().map(|m| m.generic_id()))
             .collect()
-    ));
+    );
     let mut tasks: JoinSet<Result<Vec<Addon>>> = JoinSet::new();
 
     for addon in addons.clone() {
@@ -61,14 +61,31 @@ pub async fn add_modrinth(args: AddModrinthArgs) -> Result<()> {
     }
 
     // wait for tasks to finish and push dependencies to addons
+    let added_count = addons.len();
     while let Some(res) = tasks.join_next().await { addons.extend(res??) }
 
+    if args.with_optional || args.prompt_optional {
+        progress.set_message("Finding optional dependencies");
+        let mut optional = Vec::new();
+        for addon in &addons[..added_count] {
+            optional.extend(get_optional_dependencies(&modpack, addon, &checked_ids).await?);
+        }
+
+        let optional = select_optional(optional, args.prompt_optional, &checked_ids, &progress)?;
+        for addon in &optional {
+            addons.extend(get_dependencies(&modpack, addon, &checked_ids).await?);
+        }
+        addons.extend(optional);
+    }
+
+    record_dependents(&mut addons, &index, &checked_ids).await?;
     progress.finish_and_clear();
-    add_to_index(addons, &index).await?;
+    // read again, record_dependents can change addons that were already in the index
+    add_to_index(addons, &Index::read().await?).await?;
     Ok(())
 }
 
-async fn resolve_mod(modpack: &Modpack, id: &str, version_id: Option<&str>) -> Result<Addon> {
+pub async fn resolve_mod(modpack: &Modpack, id: &str, version_id: Option<&str>) -> Result<Addon> {
     let (project, version) = match version_id {
         Some(version_id) => {
             try_join!(
@@ -89,7 +106,9 @@ async fn resolve_mod(modpack: &Modpack, id: &str, version_id: Option<&str>) -> R
                 _ => ()
             }
 
-            let compatible_versions: Vec<Version> = versions.filter_compatible(modpack, &project.project_type);
+            let compatible_versions: Vec<Version> = versions
+                .filter_compatible(modpack, &project.project_type)
+                .filter_release_channel(modpack.options.min_release_channel.as_ref());
             if compatible_versions.is_empty() {
                 return Err(Error::NoCompatibleVersions(project.title));
             }
@@ -111,15 +130,11 @@ async fn resolve_mod(modpack: &Modpack, id: &str, version_id: Option<&str>) -> R
     })
 }
 
-async fn search_ids(modpack: &Modpack, strings: &[&str], progress: &ProgressBar) -> Result<Vec<Addon>> {
+async fn search_ids(modpack: &Modpack, strings: &[&str], project_type: &ProjectType, progress: &ProgressBar) -> Result<Vec<Addon>> {
     let mut results = Vec::new();
     
     for string in strings {
-        let facets = vec![
-            vec![SearchFacet::Versions(modpack.versions.minecraft.clone())]
-        ];
-
-        let hits = MODRINTH.search(string, facets, &20).await?.hits;
+        let hits = MODRINTH.search(string, search_facets(modpack, project_type), SearchSort::Relevance, &20).await?.hits;
         if hits.is_empty() {
             println!("{}", style(format!("Searching for {} returned no results", string)).color256(166));
             continue;
@@ -131,6 +146,7 @@ async fn search_ids(modpack: &Modpack, strings: &[&str], progress: &ProgressBar)
         let chosen = if let Some(exact_match) = titles.iter().position(|t| t.to_lowercase() == string.to_lowercase()) {
             exact_match
         } else {
+            prompt::require_input(&format!("Choosing a search result for '{string}'"), "use the exact title, id or slug")?;
             let selected = progress.suspend(|| {
                 Select::new()
                     .with_prompt(format!("search results for '{}'", string))
@@ -154,7 +170,7 @@ async fn search_ids(modpack: &Modpack, strings: &[&str], progress: &ProgressBar)
 }
 
 #[async_recursion]
-async fn get_dependencies(modpack: &Modpack, addon: &Addon, checked_ids: &Arc<Mutex<Vec<String>>>) -> Result<Vec<Addon>> {
+pub async fn get_dependencies(modpack: &Modpack, addon: &Addon, checked_ids: &Arc<Mutex<CheckedIds>>) -> Result<Vec<Addon>> {
     let mut dependencies = Vec::new();
     let version_id = match &addon.source {
         AddonSource::Modrinth(source) => &source.version,
@@ -170,15 +186,46 @@ async fn get_dependencies(modpack: &Modpack, addon: &Addon, checked_ids: &Arc<Mu
         }
         
         // avoid rechecking a dependency multiple times
-        if handle_checked(dep.project_id.as_ref().unwrap(), checked_ids) {
+        if handle_checked(dep.project_id.as_ref().unwrap(), addon, checked_ids) {
             continue;
         }
 
         // modrinth optionally provides version ids on dependencies so use those
-        let resolved_addon = resolve_mod(modpack, &dep.project_id.unwrap(), dep.version_id.as_deref()).await?;
+        let resolved_addon = as_dependency(resolve_mod(modpack, &dep.project_id.unwrap(), dep.version_id.as_deref()).await?);
         dependencies.extend(get_dependencies(modpack, &resolved_addon, checked_ids).await?);
         dependencies.push(resolved_addon)
     }
 
+    Ok(dependencies)
+}
+
+async fn get_optional_dependencies(modpack: &Modpack, addon: &Addon, checked_ids: &Arc<Mutex<CheckedIds>>) -> Result<Vec<Addon>> {
+    let mut dependencies = Vec::new();
+    let version_id = match &addon.source {
+        AddonSource::Modrinth(source) => &source.version,
+        _ => unreachable!(),
+    };
+
+    let mod_dependencies = MODRINTH.get_version(version_id).await?.dependencies;
+    let optional_dependencies: Vec<VersionDependency> = mod_dependencies.into_iter().filter(|d| matches!(d.dependency_type, DependencyType::Optional)).collect();
+
+    for dep in optional_dependencies {
+        let project_id = match dep.project_id {
+            Some(project_id) => project_id,
+            None => continue,
+        };
+
+        if handle_checked(&project_id, addon, checked_ids) {
+            continue;
+        }
+
+        match resolve_mod(modpack, &project_id, dep.version_id.as_deref()).await {
+            Ok(addon) => dependencies.push(addon),
+            // an optional dependency that cant be added is skipped
+            Err(Error::NotFound(_) | Error::NoCompatibleVersions(_) | Error::UnsupportedProjectType(_)) => continue,
+            Err(err) => return Err(err),
+        }
+    }
+
     Ok(dependencies)
 }
\ No newline at end of file
@@ -0,0 +1,61 @@
+use std::fs;
+
+use console::style;
+
+use crate::{cli::AddUrlArgs, error::{Error, Result}, structs::{index::{Addon, AddonOptions, AddonSource, Index, Side, UrlSource}, jar::JarMetadata, pack::Modpack}, util::{cache, files::hash_bytes, get_version_filters, jar::detect_project_type, lock::url_filename}};
+
+use super::add_to_index;
+
+pub async fn add_url(args: AddUrlArgs) -> Result<()> {
+    if !args.url.starts_with("https://") && !args.url.starts_with("http://") {
+        return Err(Error::Other(format!("{} is not a valid url", args.url)));
+    }
+
+    let modpack = Modpack::read()?;
+
+    // a download that doesnt match the hashes given fails here
+    let cached = cache::fetch(&args.url, args.sha1.as_deref(), args.sha512.as_deref()).await?;
+    // a cached file looked up by its sha1 doesnt come with its sha512
+    let sha512 = match &cached.sha512 {
+        Some(sha512) => sha512.clone(),
+        None => hash_bytes(&fs::read(&cached.path)?).1,
+    };
+    if args.sha1.is_none() && args.sha512.is_none() {
+        println!("{}", style(format!("Pinned to sha512 {sha512}")).dim());
+    }
+
+    let metadata = JarMetadata::read(&cached.path)?;
+    if let Some(metadata) = &metadata {
+        let (game_versions, loaders) = get_version_filters(&modpack);
+        if metadata.is_compatible(&game_versions, &loaders) == Some(false) {
+            println!("{}", style(format!("{} {} says it doesnt support this modpack's minecraft version or loader", metadata.id, metadata.version)).color256(166));
+        }
+    }
+
+    let name = match args.name {
+        Some(name) => name,
+        None => {
+            let filename = url_filename(&args.url);
+            match filename.rsplit_once('.') {
+                Some((stem, _)) => stem.to_owned(),
+                None => filename,
+            }
+        },
+    };
+
+    let addon = Addon {
+        name,
+        project_type: detect_project_type(&cached.path)?,
+        side: metadata.map(|m| m.side).unwrap_or(Side::Unknown),
+        source: AddonSource::Url(UrlSource {
+            url: args.url,
+            sha1: cached.sha1,
+            sha512,
+        }),
+        options: Some(AddonOptions::default()),
+        filename: None
+    };
+
+    add_to_index(vec![addon], &Index::read().await?).await?;
+    Ok(())
+}
\ No newline at end of file
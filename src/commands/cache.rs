@@ -0,0 +1,28 @@
+use std::time::Duration;
+
+use console::style;
+
+use crate::{cli::{CacheArgs, CacheCommands}, error::Result, util::cache};
+
+pub async fn cache(args: CacheArgs) -> Result<()> {
+    match args.subcommand {
+        CacheCommands::Info => {
+            let (count, size) = cache::info()?;
+            println!("{}\n{count} {} {}",
+                style(cache::cache_dir()?.to_string_lossy()).bold(),
+                if count == 1 { "file" } else { "files" },
+                style(format!("({:.1} MiB)", size as f64 / 1024.0 / 1024.0)).dim()
+            );
+        },
+        CacheCommands::Prune { days } => {
+            let removed = cache::prune(Duration::from_secs(days * 24 * 60 * 60))?;
+            println!("Removed {removed} unused {}", if removed == 1 { "file" } else { "files" });
+        },
+        CacheCommands::Clear => {
+            cache::clear()?;
+            println!("Cleared the download and response cache");
+        },
+    }
+
+    Ok(())
+}
\ No newline at end of file
@@ -0,0 +1,456 @@
+use std::{collections::HashMap, env, fmt::Write, fs, io::Read, path::{Path, PathBuf}, process::Command};
+
+use console::style;
+use serde::Serialize;
+use zip::ZipArchive;
+
+use crate::{
+    api::{curseforge::{CurseAPI, File as CurseFile}, modrinth::Version},
+    cli::DiffArgs, commands::import::packwiz::read_packwiz, error::{Error, Result},
+    structs::{
+        index::{Addon, AddonSource, CurseforgeSource, Index, LocalSource, ModrinthSource, ProjectType, UrlSource},
+        lock::Lockfile, mrpack::{Metadata, PackDependency}, pack::{Modpack, Versions}
+    },
+    util::{files::hash_bytes, output, to_hyperlink}, CURSEFORGE, MODRINTH
+};
+
+// the parts of a modpack that are compared
+struct PackState {
+    versions: Versions,
+    addons: Vec<Addon>
+}
+
+#[derive(Serialize)]
+struct PackDiff {
+    from: String,
+    to: String,
+    minecraft: Option<Change>,
+    loader: Option<Change>,
+    added: Vec<AddonChange>,
+    removed: Vec<AddonChange>,
+    updated: Vec<AddonChange>
+}
+
+#[derive(Serialize)]
+struct Change {
+    from: String,
+    to: String
+}
+
+#[derive(Serialize)]
+struct AddonChange {
+    name: String,
+    id: String,
+    source: &'static str,
+    url: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    from: Option<AddonVersion>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    to: Option<AddonVersion>
+}
+
+#[derive(Serialize)]
+struct AddonVersion {
+    id: String,
+    // the version name people know
+    name: String,
+    url: Option<String>
+}
+
+pub async fn diff(args: DiffArgs) -> Result<()> {
+    let from = read_state(&args.from).await?;
+    let to = match &args.to {
+        Some(reference) => read_state(reference).await?,
+        None => read_dir(&env::current_dir()?).await?,
+    };
+
+    let loader = |v: &Versions| format!("{} {}", v.loader.to_string().to_lowercase(), v.loader_version);
+    let minecraft = (from.versions.minecraft != to.versions.minecraft).then(|| Change {
+        from: from.versions.minecraft.clone(),
+        to: to.versions.minecraft.clone(),
+    });
+    let loader = (loader(&from.versions) != loader(&to.versions)).then(|| Change {
+        from: loader(&from.versions),
+        to: loader(&to.versions),
+    });
+
+    let mut added = Vec::new();
+    let mut updated = Vec::new();
+    for addon in &to.addons {
+        match from.addons.iter().find(|a| addon_key(a) == addon_key(addon)) {
+            None => added.push(addon),
+            Some(old) if old.generic_version() != addon.generic_version() => updated.push((old, addon)),
+            Some(_) => (),
+        }
+    }
+    let removed: Vec<&Addon> = from.addons.iter().filter(|a| !to.addons.iter().any(|b| addon_key(a) == addon_key(b))).collect();
+
+    // version names and links are only looked up for what changed
+    let changed: Vec<&Addon> = added.iter().chain(&removed).copied().chain(updated.iter().flat_map(|u| [u.0, u.1])).collect();
+    let lookup = Lookup::fetch(&changed).await?;
+
+    let mut diff = PackDiff {
+        from: args.from.clone(),
+        to: args.to.clone().unwrap_or("working tree".into()),
+        minecraft,
+        loader,
+        added: added.into_iter().map(|a| lookup.change(a, None, Some(a))).collect(),
+        removed: removed.into_iter().map(|a| lookup.change(a, Some(a), None)).collect(),
+        updated: updated.into_iter().map(|(old, new)| lookup.change(new, Some(old), Some(new))).collect(),
+    };
+    for changes in [&mut diff.added, &mut diff.removed, &mut diff.updated] {
+        changes.sort_by_key(|c| c.name.to_lowercase());
+    }
+
+    if output::json() {
+        output::print_json(&diff)
+    } else if args.markdown {
+        print!("{}", markdown(&diff));
+        Ok(())
+    } else {
+        print!("{}", text(&diff));
+        Ok(())
+    }
+}
+
+// a reference is an mrpack file, a modpack or packwiz folder, a pack.toml, or a git revision
+async fn read_state(reference: &str) -> Result<PackState> {
+    let path = Path::new(reference);
+    if path.is_dir() {
+        return read_dir(path).await;
+    }
+
+    if path.is_file() {
+        return match path.extension().unwrap_or_default().to_str() {
+            Some("mrpack") => read_mrpack(path).await,
+            _ if path.ends_with("pack.toml") => read_dir(path.parent().unwrap()).await,
+            _ => Err(Error::Other(format!("{reference} is not an mrpack or pack.toml file"))),
+        };
+    }
+
+    read_git(reference).await
+}
+
+// emm and packwiz both have a pack.toml, packwiz's just doesnt parse as an emm modpack
+async fn read_dir(dir: &Path) -> Result<PackState> {
+    let pack_path = dir.join("pack.toml");
+    let pack_str = fs::read_to_string(&pack_path)
+        .map_err(|_| Error::Other(format!("{} does not have a pack.toml", dir.to_string_lossy())))?;
+
+    match toml::from_str::<Modpack>(&pack_str) {
+        Ok(modpack) => Ok(PackState {
+            addons: Index::read_dir(dir.join(&modpack.index_path)).await?.addons,
+            versions: locked_versions(modpack.versions, fs::read_to_string(dir.join("emm.lock")).ok()),
+        }),
+        Err(_) => {
+            let (modpack, addons) = read_packwiz(&pack_path.to_string_lossy()).await?;
+            Ok(PackState { versions: modpack.versions, addons })
+        },
+    }
+}
+
+async fn read_git(rev: &str) -> Result<PackState> {
+    let pack_str = git(&["show", &format!("{rev}:./pack.toml")])
+        .map_err(|err| Error::Other(format!("{rev} is not an mrpack, a modpack folder or a git revision of this modpack ({err})")))?;
+    let modpack: Modpack = toml::from_str(&pack_str)
+        .map_err(|err| Error::Other(format!("pack.toml at {rev} is not a valid modpack: {err}")))?;
+
+    // paths are relative to the current folder, like the ones git show gets
+    let index_path = modpack.index_path.join("").to_string_lossy().to_string();
+    let mut addons = Vec::new();
+    for path in git(&["ls-tree", "--name-only", rev, "--", &index_path])?.lines().filter(|p| p.ends_with(".toml")) {
+        let addon_str = git(&["show", &format!("{rev}:./{path}")])?;
+        let mut addon: Addon = toml::from_str(&addon_str)
+            .map_err(|err| Error::Other(format!("{path} at {rev} is not a valid index file: {err}")))?;
+        addon.filename = Path::new(path).file_name().map(|f| f.to_string_lossy().to_string());
+        addons.push(addon);
+    }
+
+    let lock_str = git(&["show", &format!("{rev}:./emm.lock")]).ok();
+    Ok(PackState { versions: locked_versions(modpack.versions, lock_str), addons })
+}
+
+// "latest" is compared as the loader version the lockfile resolved it to, like exports use
+fn locked_versions(versions: Versions, lock_str: Option<String>) -> Versions {
+    match lock_str.and_then(|s| toml::from_str::<Lockfile>(&s).ok()) {
+        Some(lock) if versions.loader_version == "latest" && lock.minecraft == versions.minecraft && lock.loader == versions.loader => {
+            Versions { loader_version: lock.loader_version, ..versions }
+        },
+        _ => versions,
+    }
+}
+
+fn git(args: &[&str]) -> Result<String> {
+    let output = Command::new("git").args(args).output()?;
+    match output.status.success() {
+        true => Ok(String::from_utf8_lossy(&output.stdout).into_owned()),
+        false => Err(Error::Other(String::from_utf8_lossy(&output.stderr).trim().to_owned())),
+    }
+}
+
+// files are matched to modrinth by hash, and jars in the overrides to curseforge by fingerprint like the import does
+async fn read_mrpack(path: &Path) -> Result<PackState> {
+    let mut zip = ZipArchive::new(fs::File::open(path)?)?;
+    let mut mrpack_string = String::new();
+    zip.by_name("modrinth.index.json")?.read_to_string(&mut mrpack_string)?;
+    let mrpack: Metadata = serde_json::from_str(&mrpack_string)?;
+
+    let minecraft = mrpack.dependencies.get(&PackDependency::Minecraft).cloned()
+        .ok_or(Error::BadImport("modpack does not have a minecraft version".into()))?;
+    let (loader, loader_version) = mrpack.dependencies.iter().find(|d| *d.0 != PackDependency::Minecraft)
+        .ok_or(Error::BadImport("modpack does not have a mod loader".into()))?;
+    let versions = Versions { minecraft, loader: loader.clone().try_into()?, loader_version: loader_version.clone() };
+
+    let file_hashes: Vec<&str> = mrpack.files.iter().map(|f| f.hashes.sha1.as_str()).collect();
+    let mr_versions = match file_hashes.is_empty() {
+        true => HashMap::new(),
+        false => MODRINTH.versions_from_hashes(&file_hashes).await?,
+    };
+    let project_ids: Vec<&str> = mr_versions.values().map(|v| v.project_id.as_str()).collect();
+    let projects = match project_ids.is_empty() {
+        true => Vec::new(),
+        false => MODRINTH.get_multiple_projects(&project_ids).await?,
+    };
+
+    let mut addons = Vec::new();
+    for file in mrpack.files {
+        let project = mr_versions.get(&file.hashes.sha1).and_then(|v| projects.iter().find(|p| p.id == v.project_id).map(|p| (v, p)));
+        addons.push(match project {
+            Some((version, project)) => file_addon(&project.title, project.project_type.clone(), AddonSource::Modrinth(ModrinthSource {
+                id: project.id.clone(),
+                version: version.id.clone(),
+            })),
+            // files that arent on modrinth are direct downloads
+            None => file_addon(&file_name(&file.path), ProjectType::Mod, AddonSource::Url(UrlSource {
+                url: file.downloads.first().cloned().unwrap_or_default(),
+                sha1: file.hashes.sha1,
+                sha512: file.hashes.sha512,
+            })),
+        });
+    }
+
+    let mut jars: Vec<(PathBuf, Vec<u8>)> = Vec::new();
+    for i in 0..zip.len() {
+        let mut entry = zip.by_index(i)?;
+        let Some(path) = entry.name().strip_prefix("overrides/").map(PathBuf::from) else { continue };
+        if !entry.is_file() || path.extension().unwrap_or_default() != "jar" { continue }
+
+        let mut bytes = Vec::new();
+        entry.read_to_end(&mut bytes)?;
+        jars.push((path, bytes));
+    }
+
+    let fingerprints: Vec<u32> = jars.iter().map(|j| CurseAPI::hash_curse(&j.1)).collect();
+    let cf_files: Vec<CurseFile> = match fingerprints.is_empty() {
+        true => Vec::new(),
+        false => CURSEFORGE.get_fingerprint_matches(&fingerprints).await?.exact_matches.into_iter().map(|m| m.file).collect(),
+    };
+    let cf_mods = match cf_files.is_empty() {
+        true => Vec::new(),
+        false => CURSEFORGE.get_mods(cf_files.iter().map(|f| f.mod_id).collect()).await?,
+    };
+
+    for (path, bytes) in jars {
+        let (sha1, sha512) = hash_bytes(&bytes);
+        let cf_file = cf_files.iter().find(|f| f.hashes.iter().any(|h| h.value == sha1));
+        addons.push(match cf_file.and_then(|f| cf_mods.iter().find(|m| m.id == f.mod_id).map(|m| (f, m))) {
+            Some((file, cf_mod)) => file_addon(
+                &cf_mod.name,
+                cf_mod.class_id.and_then(|c| c.try_into().ok()).unwrap_or(ProjectType::Mod),
+                AddonSource::Curseforge(CurseforgeSource { id: cf_mod.id, version: file.id })
+            ),
+            None => file_addon(&file_name(&path), ProjectType::Mod, AddonSource::Local(LocalSource { path, sha1, sha512 })),
+        });
+    }
+
+    Ok(PackState { versions, addons })
+}
+
+fn file_addon(name: &str, project_type: ProjectType, source: AddonSource) -> Addon {
+    Addon {
+        name: name.into(),
+        project_type,
+        side: Default::default(),
+        source,
+        options: None,
+        filename: None,
+    }
+}
+
+fn file_name(path: &Path) -> String {
+    path.file_name().unwrap_or_default().to_string_lossy().to_string()
+}
+
+// local files are the same addon wherever they are in the modpack
+fn addon_key(addon: &Addon) -> String {
+    match &addon.source {
+        AddonSource::Local(source) => format!("local:{}", file_name(&source.path)),
+        source => format!("{}:{}", source_name(source), addon.generic_id()),
+    }
+}
+
+fn source_name(source: &AddonSource) -> &'static str {
+    match source {
+        AddonSource::Modrinth(_) => "modrinth",
+        AddonSource::Curseforge(_) => "curseforge",
+        AddonSource::Github(_) => "github",
+        AddonSource::Local(_) => "local",
+        AddonSource::Url(_) => "url",
+    }
+}
+
+// what the platforms know about the changed addons
+struct Lookup {
+    mr_versions: Vec<Version>,
+    cf_files: Vec<CurseFile>,
+    // (mod_id, website_url)
+    cf_links: Vec<(i32, String)>
+}
+
+impl Lookup {
+    async fn fetch(addons: &[&Addon]) -> Result<Self> {
+        let mut mr_ids = Vec::new();
+        let mut cf_pairs = Vec::new();
+        for addon in addons {
+            match &addon.source {
+                AddonSource::Modrinth(source) => mr_ids.push(source.version.as_str()),
+                AddonSource::Curseforge(source) => cf_pairs.push((source.id, source.version)),
+                _ => (),
+            }
+        }
+
+        let mr_versions = match mr_ids.is_empty() {
+            true => Vec::new(),
+            false => MODRINTH.get_versions(&mr_ids).await?,
+        };
+        let cf_links = match cf_pairs.is_empty() {
+            true => Vec::new(),
+            false => CURSEFORGE.get_mods(cf_pairs.iter().map(|p| p.0).collect()).await.unwrap_or_default()
+                .into_iter().map(|m| (m.id, m.links.website_url)).collect(),
+        };
+        let cf_files = CURSEFORGE.get_files(cf_pairs).await?;
+
+        Ok(Lookup { mr_versions, cf_files, cf_links })
+    }
+
+    fn change(&self, addon: &Addon, from: Option<&Addon>, to: Option<&Addon>) -> AddonChange {
+        AddonChange {
+            name: addon.name.clone(),
+            id: addon.generic_id(),
+            source: source_name(&addon.source),
+            url: self.project_url(addon),
+            from: from.map(|a| self.version(a)),
+            to: to.map(|a| self.version(a)),
+        }
+    }
+
+    fn project_url(&self, addon: &Addon) -> Option<String> {
+        match &addon.source {
+            AddonSource::Modrinth(source) => Some(format!("https://modrinth.com/project/{}", source.id)),
+            AddonSource::Curseforge(source) => self.cf_links.iter().find(|l| l.0 == source.id).map(|l| l.1.clone()),
+            AddonSource::Github(source) => Some(format!("https://github.com/{}", source.repo)),
+            AddonSource::Url(source) => Some(source.url.clone()),
+            AddonSource::Local(_) => None,
+        }
+    }
+
+    fn version(&self, addon: &Addon) -> AddonVersion {
+        let (name, url) = match &addon.source {
+            AddonSource::Modrinth(source) => (
+                self.mr_versions.iter().find(|v| v.id == source.version).map_or(source.version.clone(), |v| v.version_number.clone()),
+                Some(format!("https://modrinth.com/project/{}/version/{}", source.id, source.version)),
+            ),
+            AddonSource::Curseforge(source) => match self.cf_files.iter().find(|f| f.id == source.version) {
+                Some(file) => (file.file_name.clone(), self.project_url(addon).map(|url| format!("{url}/files/{}", file.id))),
+                // files of old packs can be deleted by now
+                None => (source.version.to_string(), None),
+            },
+            AddonSource::Github(source) => (
+                source.tag.clone(),
+                Some(format!("https://github.com/{}/releases/tag/{}", source.repo, source.tag)),
+            ),
+            // files only have their hash to tell versions apart
+            AddonSource::Local(source) => (source.sha1.chars().take(8).collect(), None),
+            AddonSource::Url(source) => (source.sha1.chars().take(8).collect(), Some(source.url.clone())),
+        };
+
+        AddonVersion { id: addon.generic_version(), name, url }
+    }
+}
+
+fn text(diff: &PackDiff) -> String {
+    let mut out = String::new();
+    if let Some(change) = &diff.minecraft {
+        writeln!(&mut out, "{} {} {} {}", style("Minecraft").bold(), change.from, style("→").dim(), change.to).unwrap();
+    }
+    if let Some(change) = &diff.loader {
+        writeln!(&mut out, "{} {} {} {}", style("Loader").bold(), change.from, style("→").dim(), change.to).unwrap();
+    }
+
+    let link = |text: &str, url: &Option<String>| match url {
+        Some(url) => to_hyperlink(url, text),
+        None => text.to_owned(),
+    };
+
+    for (title, changes, sign) in [
+        ("Added", &diff.added, style("+").green()),
+        ("Removed", &diff.removed, style("-").red()),
+        ("Updated", &diff.updated, style("~").color256(166)),
+    ] {
+        if changes.is_empty() { continue }
+        if !out.is_empty() { out.push('\n') }
+
+        writeln!(&mut out, "{}", style(format!("{title} ({})", changes.len())).bold()).unwrap();
+        for change in changes {
+            let name = style(link(&change.name, &change.url)).bold();
+            let versions = match (&change.from, &change.to) {
+                (Some(from), Some(to)) => format!("{} {} {}", style(&from.name).dim(), style("→").dim(), link(&to.name, &to.url)),
+                (Some(version), None) | (None, Some(version)) => style(link(&version.name, &version.url)).dim().to_string(),
+                (None, None) => String::new(),
+            };
+            writeln!(&mut out, "  {sign} {name} {versions}").unwrap();
+        }
+    }
+
+    if out.is_empty() {
+        writeln!(&mut out, "No changes between {} and {}", diff.from, diff.to).unwrap();
+    }
+
+    out
+}
+
+fn markdown(diff: &PackDiff) -> String {
+    let mut out = String::new();
+    if let Some(change) = &diff.minecraft {
+        writeln!(&mut out, "**Minecraft:** {} → {}\n", change.from, change.to).unwrap();
+    }
+    if let Some(change) = &diff.loader {
+        writeln!(&mut out, "**Loader:** {} → {}\n", change.from, change.to).unwrap();
+    }
+
+    let link = |text: &str, url: &Option<String>| match url {
+        Some(url) => format!("[{text}]({url})"),
+        None => text.to_owned(),
+    };
+
+    for (title, changes) in [("Added", &diff.added), ("Removed", &diff.removed), ("Updated", &diff.updated)] {
+        if changes.is_empty() { continue }
+
+        writeln!(&mut out, "### {title}\n").unwrap();
+        for change in changes {
+            let versions = match (&change.from, &change.to) {
+                (Some(from), Some(to)) => format!("{} → {}", from.name, link(&to.name, &to.url)),
+                (Some(version), None) | (None, Some(version)) => link(&version.name, &version.url),
+                (None, None) => String::new(),
+            };
+            writeln!(&mut out, "- {} {versions}", link(&change.name, &change.url)).unwrap();
+        }
+        out.push('\n');
+    }
+
+    if out.is_empty() {
+        writeln!(&mut out, "No changes.").unwrap();
+    }
+
+    out
+}
\ No newline at end of file
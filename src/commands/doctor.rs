@@ -0,0 +1,148 @@
+use std::time::Duration;
+
+use console::style;
+use indicatif::ProgressBar;
+use tokio::try_join;
+
+use crate::{api::{curseforge::FileRelationType, modrinth::DependencyType}, error::{Error, Result}, structs::{index::{Addon, AddonSource, Index}, jar::JarMetadata, lock::Lockfile, pack::Modpack}, util::{cache, get_version_filters, FilterVersions}, CURSEFORGE, MODRINTH};
+
+// checks the dependencies and compatibility of every addon at the version in the index
+pub async fn doctor() -> Result<()> {
+    let modpack = Modpack::read()?;
+    let index = Index::read().await?;
+
+    let progress = ProgressBar::new_spinner().with_message("Checking addons");
+    progress.enable_steady_tick(Duration::from_millis(100));
+
+    let mut mr_versions = Vec::new();
+    let mut cf_files = Vec::new();
+    let mut has_jar_addons = false;
+    index.addons.iter().for_each(|a| match &a.source {
+        AddonSource::Modrinth(source) => mr_versions.push(source.version.as_str()),
+        AddonSource::Curseforge(source) => cf_files.push((source.id, source.version)),
+        AddonSource::Github(_) | AddonSource::Url(_) | AddonSource::Local(_) => has_jar_addons = true,
+    });
+
+    let (mr_versions, cf_files) = try_join!(
+        async {
+            if mr_versions.is_empty() { return Ok(Vec::new()); }
+            MODRINTH.get_versions(&mr_versions).await
+        },
+        async {
+            if cf_files.is_empty() { return Ok(Vec::new()); }
+            CURSEFORGE.get_files(cf_files).await
+        }
+    )?;
+
+    let mut problems = Vec::new();
+    let mut warnings = Vec::new();
+    // (addon name, dependency id) for dependencies that arent in the index by id
+    let mut mr_missing: Vec<(String, String)> = Vec::new();
+    let mut cf_missing: Vec<(String, i32)> = Vec::new();
+
+    for version in mr_versions {
+        let addon = match find_by_source(&index, |s| matches!(s, AddonSource::Modrinth(s) if s.version == version.id)) {
+            Some(addon) => addon,
+            None => continue,
+        };
+
+        if vec![version.clone()].filter_compatible(&modpack, &addon.project_type).is_empty() {
+            problems.push(format!("{} {} is not compatible with this modpack's minecraft version or loader", addon.name, version.version_number));
+        }
+
+        for dep in version.dependencies {
+            match (dep.dependency_type, dep.project_id) {
+                (DependencyType::Required, None) => warnings.push(format!("{} has a required dependency without a project id, it can not be checked", addon.name)),
+                (DependencyType::Required, Some(id)) if find_addon(&index, &id).is_none() => mr_missing.push((addon.name.clone(), id)),
+                (DependencyType::Incompatible, Some(id)) => if let Some(other) = find_addon(&index, &id) {
+                    problems.push(format!("{} is incompatible with {}", addon.name, other.name));
+                },
+                _ => ()
+            }
+        }
+    }
+
+    for file in cf_files {
+        let addon = match find_by_source(&index, |s| matches!(s, AddonSource::Curseforge(s) if s.version == file.id)) {
+            Some(addon) => addon,
+            None => continue,
+        };
+
+        if vec![file.clone()].filter_compatible(&modpack, &addon.project_type).is_empty() {
+            problems.push(format!("{} {} is not compatible with this modpack's minecraft version or loader", addon.name, file.file_name));
+        }
+
+        for dep in file.dependencies {
+            let other = find_addon(&index, &dep.mod_id.to_string());
+            match dep.relation_type {
+                FileRelationType::RequiredDependency if other.is_none() => cf_missing.push((addon.name.clone(), dep.mod_id)),
+                FileRelationType::Incompatible => if let Some(other) = other {
+                    problems.push(format!("{} is incompatible with {}", addon.name, other.name));
+                },
+                _ => ()
+            }
+        }
+    }
+
+    // a dependency can be in the index from another platform, so names are compared as well
+    let mut dependency_names: Vec<(String, String)> = Vec::new();
+    if !mr_missing.is_empty() {
+        let ids: Vec<&str> = mr_missing.iter().map(|m| m.1.as_str()).collect();
+        dependency_names.extend(MODRINTH.get_multiple_projects(&ids).await?.into_iter().map(|p| (p.id, p.title)));
+    }
+    if !cf_missing.is_empty() {
+        let cf_mods = CURSEFORGE.get_mods(cf_missing.iter().map(|m| m.1).collect()).await?;
+        dependency_names.extend(cf_mods.into_iter().map(|m| (m.id.to_string(), m.name)));
+    }
+
+    let missing = mr_missing.into_iter().chain(cf_missing.into_iter().map(|(name, id)| (name, id.to_string())));
+    for (addon_name, id) in missing {
+        let dependency_name = dependency_names.iter().find(|d| d.0 == id).map(|d| d.1.clone()).unwrap_or(id);
+        if find_addon(&index, &dependency_name).is_none() {
+            problems.push(format!("{addon_name} requires {dependency_name}, which is not in the modpack"));
+        }
+    }
+
+    // addons without platform metadata can only be checked by reading their files
+    if has_jar_addons {
+        progress.set_message("Reading addon files");
+        // a check shouldnt change the working tree, so the lock is only resolved in memory
+        let lock = Lockfile::resolve(false).await?;
+        let (game_versions, loaders) = get_version_filters(&modpack);
+        for addon in index.addons.iter().filter(|a| matches!(a.source, AddonSource::Github(_) | AddonSource::Url(_) | AddonSource::Local(_))) {
+            let file = match lock.get(addon) {
+                Some(locked) => cache::fetch_locked_file(locked).await?,
+                None => continue,
+            };
+
+            if JarMetadata::read(&file.path)?.and_then(|m| m.is_compatible(&game_versions, &loaders)) == Some(false) {
+                problems.push(format!("{} says it doesnt support this modpack's minecraft version or loader", addon.name));
+            }
+        }
+    }
+
+    progress.finish_and_clear();
+
+    for warning in &warnings {
+        println!("{}", style(warning).color256(166));
+    }
+    for problem in &problems {
+        println!("{}", style(problem).red());
+    }
+
+    if !problems.is_empty() {
+        return Err(Error::Other(format!("Found {} {}", problems.len(), if problems.len() == 1 { "problem" } else { "problems" })));
+    }
+
+    println!("No problems found!");
+    Ok(())
+}
+
+fn find_by_source(index: &Index, matches: impl Fn(&AddonSource) -> bool) -> Option<&Addon> {
+    index.addons.iter().find(|a| matches(&a.source))
+}
+
+// select_addon would prompt for similar names, a doctor only wants exact matches
+fn find_addon<'a>(index: &'a Index, str: &str) -> Option<&'a Addon> {
+    index.addons.iter().find(|a| a.matches_str(str))
+}
\ No newline at end of file
@@ -0,0 +1,126 @@
+use std::{env, fmt::Write as _, fs, io::Write, path::{Path, PathBuf}, time::Duration};
+
+use indicatif::ProgressBar;
+use zip::{write::SimpleFileOptions, ZipWriter};
+
+use crate::{
+    cli::ExportCurseforgeArgs, error::{Error, Result}, structs::{
+        cfpack::{Manifest, ManifestFile, ManifestMinecraft, ManifestModLoader}, index::{AddonSource, Index}, lock::{LockedAddon, Lockfile}, pack::Modpack
+    }, util::{cache::{self, CachedFile}, escape_html, files::{add_file, add_recursively}}, CURSEFORGE
+};
+
+use super::print_artifacts;
+
+pub async fn export_curseforge(args: ExportCurseforgeArgs) -> Result<()> {
+    let modpack = Modpack::read()?;
+    let index = Index::read().await?;
+
+    let overrides_path = args.overrides_path.or(modpack.options.overrides_path.clone());
+    if overrides_path.as_ref().is_some_and(|path| !path.exists()) {
+        return Err(Error::Other("The overrides path provided does not exist".into()));
+    }
+
+    let progress = ProgressBar::new_spinner().with_message("Exporting to curseforge pack");
+    progress.enable_steady_tick(Duration::from_millis(100));
+
+    progress.set_message("Resolving files");
+    let lock = Lockfile::for_export(args.locked).await?;
+
+    // (Addon name, Source)
+    let mut cf_addons = Vec::new();
+    // (file_path, locked file) for addons that have to go in overrides
+    let mut to_download: Vec<(PathBuf, LockedAddon)> = Vec::new();
+
+    for addon in index.addons {
+        let locked = lock.require(&addon)?;
+
+        match addon.source {
+            AddonSource::Curseforge(source) => cf_addons.push((addon.name, source)),
+            AddonSource::Modrinth(_) | AddonSource::Github(_) | AddonSource::Url(_) | AddonSource::Local(_) => {
+                to_download.push((addon.project_type.export_folder(modpack.options.clone()).join(&locked.filename), locked.clone()))
+            },
+        }
+    }
+
+    progress.set_message("Exporting curseforge mods");
+
+    let files: Vec<ManifestFile> = cf_addons.iter().map(|a| ManifestFile {
+        project_id: a.1.id,
+        file_id: a.1.version,
+        required: true,
+    }).collect();
+
+    // (name, website_url)
+    let cf_links: Vec<(String, String)> = if cf_addons.is_empty() { Vec::new() } else {
+        let cf_mods = CURSEFORGE.get_mods(cf_addons.iter().map(|a| a.1.id).collect()).await?;
+        cf_addons.iter().map(|a| {
+            let url = cf_mods.iter().find(|m| m.id == a.1.id).map(|m| m.links.website_url.clone()).unwrap_or_default();
+            (a.0.clone(), url)
+        }).collect()
+    };
+
+    let mod_overrides = if !to_download.is_empty() {
+        progress.set_message("Downloading override files");
+        cache::fetch_locked(to_download).await?
+    } else { Vec::new() };
+
+    let manifest = Manifest {
+        minecraft: ManifestMinecraft {
+            version: modpack.versions.minecraft.clone(),
+            mod_loaders: vec![ManifestModLoader {
+                id: format!("{}-{}", modpack.versions.loader.to_string().to_lowercase(), lock.loader_version),
+                primary: true,
+            }],
+        },
+        manifest_type: "minecraftModpack".into(),
+        manifest_version: 1,
+        name: modpack.name.clone(),
+        version: modpack.version.clone(),
+        author: modpack.authors.join(", "),
+        files,
+        overrides: "overrides".into(),
+    };
+
+    progress.set_message("Creating pack zip file");
+    create_cfpack(&env::current_dir()?, &manifest, &modlist_html(&cf_links), overrides_path.as_ref(), &mod_overrides)?;
+
+    let output_file = env::current_dir()?.join(format!("{}-{}.zip", manifest.name, manifest.version)).to_string_lossy().to_string();
+    progress.finish_with_message(format!("Exported to {}", output_file));
+    print_artifacts("curseforge", Path::new(&output_file))
+}
+
+// the same list curseforge puts in its own pack exports
+// names and links come from curseforge, they shouldnt be able to break out of the list
+fn modlist_html(links: &[(String, String)]) -> String {
+    let mut out = String::from("<ul>\n");
+    for (name, url) in links {
+        writeln!(&mut out, "<li><a href=\"{}\">{}</a></li>", escape_html(url), escape_html(name)).unwrap();
+    }
+    out.push_str("</ul>\n");
+    out
+}
+
+fn create_cfpack(path: &Path, manifest: &Manifest, modlist: &str, overrides: Option<&PathBuf>, mod_overrides: &[(PathBuf, CachedFile)]) -> zip::result::ZipResult<()> {
+    let zip_path = path.join(format!("{}-{}.zip", manifest.name, manifest.version));
+    let mut zip = ZipWriter::new(fs::File::create(zip_path)?);
+    let options = SimpleFileOptions::default().compression_method(zip::CompressionMethod::Deflated);
+
+    zip.start_file("manifest.json", options)?;
+    zip.write_all(serde_json::to_string_pretty(&manifest).unwrap().as_bytes())?;
+
+    zip.start_file("modlist.html", options)?;
+    zip.write_all(modlist.as_bytes())?;
+
+    zip.add_directory("overrides", options)?;
+
+    if let Some(overrides) = overrides {
+        add_recursively(overrides, &PathBuf::from("overrides"), &mut zip, options)?;
+    }
+
+    for (file_path, cached) in mod_overrides {
+        add_file(&cached.path, &PathBuf::from("overrides").join(file_path), &mut zip, options)?;
+    }
+
+    zip.finish()?;
+    Ok(())
+}
\ No newline at end of file
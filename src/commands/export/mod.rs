@@ -1,17 +1,56 @@
-use std::path::PathBuf;
+use std::{fs, path::{Path, PathBuf}};
 
-use crate::{cli::{ExportArgs, ExportCommands}, error::Result, structs::{index::ProjectType, pack::{Modpack, PackOptions}}};
+use serde::Serialize;
+use serde_json::json;
+use walkdir::WalkDir;
+
+use crate::{cli::{ExportArgs, ExportCommands}, error::Result, structs::{index::ProjectType, pack::PackOptions}, util::{files::hash_bytes, output}};
 
 pub mod modrinth;
 pub mod curseforge;
 pub mod packwiz;
+pub mod server;
 
 pub async fn run(args: ExportArgs) -> Result<()> {
     match args.subcommand {
         ExportCommands::Modrinth(args) => modrinth::export_modrinth(args).await,
-        ExportCommands::Curseforge(args) => todo!(),
-        ExportCommands::Packwiz(args) => packwiz::export_packwiz(args).await
+        ExportCommands::Curseforge(args) => curseforge::export_curseforge(args).await,
+        ExportCommands::Packwiz(args) => packwiz::export_packwiz(args).await,
+        ExportCommands::Server(args) => server::export_server(args).await
+    }
+}
+
+// a file written by an export
+#[derive(Serialize)]
+struct Artifact {
+    path: PathBuf,
+    sha1: String,
+    sha512: String,
+    size: usize
+}
+
+// with --output json, prints the files an export wrote to path, every file in it if it's a directory
+pub fn print_artifacts(format: &str, path: &Path) -> Result<()> {
+    if !output::json() {
+        return Ok(());
     }
+
+    let path = std::path::absolute(path)?;
+    let mut files: Vec<PathBuf> = WalkDir::new(&path).into_iter()
+        .filter_map(|e| e.ok())
+        .filter(|e| e.file_type().is_file())
+        .map(|e| e.into_path())
+        .collect();
+    files.sort();
+
+    let mut artifacts = Vec::new();
+    for file in files {
+        let bytes = fs::read(&file)?;
+        let (sha1, sha512) = hash_bytes(&bytes);
+        artifacts.push(Artifact { path: file, sha1, sha512, size: bytes.len() });
+    }
+
+    output::print_json(&json!({ "format": format, "path": path, "artifacts": artifacts }))
 }
 
 impl ProjectType {
@@ -3,9 +3,10 @@ use std::{collections::HashMap, fs, path::PathBuf, time::Duration};
 use indicatif::ProgressBar;
 use serde::{Deserialize, Serialize};
 use sha2::{Sha256, Digest};
-use tokio::try_join;
 
-use crate::{cli::ExportPackwizArgs, error::{Error, Result}, structs::{index::{AddonSource, Index, ProjectType}, pack::Modpack, packwiz::{CurseforgeModUpdate, DownloadMode, HashFormat, IndexFile, ModDownload, ModUpdate, ModrinthModUpdate, PwIndex, PwIndexInfo, PwMod, PwPack}}, util::{files::download_file, modrinth::primary_file}, CURSEFORGE, GITHUB, MODRINTH};
+use crate::{cli::ExportPackwizArgs, error::{Error, Result}, structs::{index::{AddonSource, Index, ProjectType, Side}, lock::Lockfile, pack::Modpack, packwiz::{CurseforgeModUpdate, DownloadMode, HashFormat, IndexFile, ModDownload, ModUpdate, ModrinthModUpdate, PwIndex, PwIndexInfo, PwMod, PwPack}}, util::cache};
+
+use super::print_artifacts;
 
 pub async fn export_packwiz(args: ExportPackwizArgs) -> Result<()> {
     if !args.export_path.exists() || args.export_path.read_dir()?.count() != 0 {
@@ -18,117 +19,97 @@ pub async fn export_packwiz(args: ExportPackwizArgs) -> Result<()> {
     let progress = ProgressBar::new_spinner().with_message("Exporting to packwiz pack");
     progress.enable_steady_tick(Duration::from_millis(100));
 
-    let mut mr_sources = Vec::new();
-    let mut cf_sources = Vec::new();
-    let mut gh_sources = Vec::new();
-
-    index.addons.into_iter().for_each(|a| match a.source.clone() {
-        AddonSource::Modrinth(source) => mr_sources.push((a, source)),
-        AddonSource::Curseforge(source) => cf_sources.push((a, source)),
-        AddonSource::Github(source) => gh_sources.push((a, source)),
-    });
-
-    progress.set_message("Gathering info");
-    let mr_version_ids = mr_sources.iter().map(|a| a.1.version.as_str()).collect::<Vec<&str>>();
-    let cf_version_ids = cf_sources.iter().map(|a| (a.1.id, a.1.version)).collect::<Vec<(i32, i32)>>();
-    let (mr_versions, cf_files) = try_join!(
-        MODRINTH.get_versions(mr_version_ids.as_slice()),
-        CURSEFORGE.get_files(cf_version_ids)
-    )?;
+    progress.set_message("Resolving files");
+    let lock = Lockfile::for_export(args.locked).await?;
 
     let mut pwmods = Vec::new();
+    // (file path in the pack, locked file) local files are copied into the pack as is
+    let mut local_files = Vec::new();
 
-    mr_sources.into_iter().for_each(|a| {
-        let primary_file = primary_file(mr_versions.iter().find(|v| v.project_id == a.1.id).unwrap().files.clone());
-        let pwmod = PwMod {
-            name: a.0.name,
-            filename: primary_file.filename,
-            download: ModDownload {
-                url: Some(primary_file.url),
-                hash_format: HashFormat::Sha1,
-                hash: primary_file.hashes.get("sha1").unwrap().clone(),
-                mode: None,
-            },
-            option: None,
-            side: Some(a.0.side),
-            update: Some(ModUpdate {
-                modrinth: Some(ModrinthModUpdate {
-                    mod_id: a.1.id,
-                    version: a.1.version,
-                }),
-                curseforge: None,
-            }),
-        };
-        let pwmod_str = toml::to_string_pretty(&pwmod).unwrap();
-
-        pwmods.push(ExportHelper {
-            file_path: a.0.project_type.folder().join(format!("{}.pw.toml", pwmod.name.to_lowercase().replace(' ', "-"))),
-            hash: format!("{:x}", Sha256::digest(pwmod_str.as_bytes())),
-            pwmod_str,
-        });
-    });
+    for addon in index.addons {
+        let locked = lock.require(&addon)?;
 
-    cf_sources.into_iter().for_each(|a| {
-        let file = cf_files.iter().find(|f| f.mod_id == a.1.id).unwrap().clone();
-        let pwmod = PwMod {
-            name: a.0.name,
-            filename: file.file_name,
-            download: ModDownload {
-                url: None,
-                hash_format: HashFormat::Sha1,
-                hash: file.hashes.into_iter().find(|h| matches!(h.algo.into(), HashFormat::Sha1)).unwrap().value,
-                mode: Some(DownloadMode::Curseforge),
-            },
-            option: None,
-            side: Some(a.0.side),
-            update: Some(ModUpdate {
-                modrinth: None,
-                curseforge: Some(CurseforgeModUpdate {
-                    project_id: a.1.id,
-                    file_id: a.1.version,
-                }),
-            }),
-        };
-        let pwmod_str = toml::to_string_pretty(&pwmod).unwrap();
+        if let AddonSource::Local(_) = addon.source {
+            local_files.push((addon.project_type.folder().join(&locked.filename), locked.clone()));
+            continue;
+        }
 
-        pwmods.push(ExportHelper {
-            file_path: a.0.project_type.folder().join(format!("{}.pw.toml", pwmod.name.to_lowercase().replace(' ', "-"))),
-            hash: format!("{:x}", Sha256::digest(pwmod_str.as_bytes())),
-            pwmod_str,
-        });
-    });
-
-    for addon in gh_sources {
-        let repo_split: Vec<&str> = addon.1.repo.split('/').collect();
-        let release = GITHUB.get_release_by_tag(repo_split[0], repo_split[1], &addon.1.tag).await?;
-        let asset = match release.assets.get(addon.1.asset_index) {
-            Some(asset) => asset,
-            None => return Err(Error::Other(format!("Cant import {} because its release format has changed (asset index out of bounds)", addon.0.name))),
+        let (download, update) = match addon.source {
+            AddonSource::Modrinth(source) => (
+                ModDownload {
+                    url: locked.url.clone(),
+                    hash_format: HashFormat::Sha1,
+                    hash: locked.sha1.clone(),
+                    mode: None,
+                },
+                Some(ModUpdate {
+                    modrinth: Some(ModrinthModUpdate {
+                        mod_id: source.id,
+                        version: source.version,
+                    }),
+                    curseforge: None,
+                })
+            ),
+            AddonSource::Curseforge(source) => (
+                ModDownload {
+                    url: None,
+                    hash_format: HashFormat::Sha1,
+                    hash: locked.sha1.clone(),
+                    mode: Some(DownloadMode::Curseforge),
+                },
+                Some(ModUpdate {
+                    modrinth: None,
+                    curseforge: Some(CurseforgeModUpdate {
+                        project_id: source.id,
+                        file_id: source.version,
+                    }),
+                })
+            ),
+            AddonSource::Github(_) => (
+                ModDownload {
+                    url: locked.url.clone(),
+                    hash_format: HashFormat::Sha1,
+                    hash: locked.sha1.clone(),
+                    mode: None,
+                },
+                None
+            ),
+            AddonSource::Url(_) => (
+                ModDownload {
+                    url: locked.url.clone(),
+                    hash_format: HashFormat::Sha512,
+                    hash: locked.sha512.clone().unwrap_or_default(),
+                    mode: Some(DownloadMode::Url),
+                },
+                None
+            ),
+            AddonSource::Local(_) => unreachable!(),
         };
 
         let pwmod = PwMod {
-            name: addon.0.name,
-            filename: asset.name.clone(),
-            download: ModDownload {
-                url: Some(asset.browser_download_url.clone()),
-                hash_format: HashFormat::Sha256,
-                hash: format!("{:x}", Sha256::digest(reqwest::get(&asset.browser_download_url).await?.bytes().await?)),
-                mode: None,
-            },
+            name: addon.name,
+            filename: locked.filename.clone(),
+            download,
             option: None,
-            side: Some(addon.0.side),
-            update: None,
+            // packwiz doesnt have an unknown side
+            side: Some(match addon.side {
+                Side::Unknown => Side::Both,
+                side => side
+            }),
+            update,
         };
         let pwmod_str = toml::to_string_pretty(&pwmod).unwrap();
 
         pwmods.push(ExportHelper {
-            file_path: addon.0.project_type.folder().join(format!("{}.pw.toml", pwmod.name.to_lowercase().replace(' ', "-"))),
+            file_path: addon.project_type.folder().join(format!("{}.pw.toml", pwmod.name.to_lowercase().replace(' ', "-"))),
             hash: format!("{:x}", Sha256::digest(pwmod_str.as_bytes())),
             pwmod_str,
         });
-    };
+    }
     
-    let pwindex = PwIndex {
+    let local_files = cache::fetch_locked(local_files).await?;
+
+    let mut pwindex = PwIndex {
         hash_format: HashFormat::Sha256,
         files: pwmods.iter().map(|m| IndexFile {
             file: m.file_path.to_string_lossy().to_string(),
@@ -137,11 +118,19 @@ pub async fn export_packwiz(args: ExportPackwizArgs) -> Result<()> {
             metafile: Some(true),
         }).collect(),
     };
+    for (file_path, local) in &local_files {
+        pwindex.files.push(IndexFile {
+            file: file_path.to_string_lossy().to_string(),
+            hash: format!("{:x}", Sha256::digest(fs::read(&local.path)?)),
+            hash_format: None,
+            metafile: None,
+        });
+    }
     let pwindex_str = toml::to_string_pretty(&pwindex).unwrap();
 
     let mut pack_versions: HashMap<String, String> = HashMap::new();
     pack_versions.insert("minecraft".into(), modpack.versions.minecraft.clone());
-    pack_versions.insert(modpack.versions.loader.to_string().to_lowercase(), modpack.get_loader_version().await?);
+    pack_versions.insert(modpack.versions.loader.to_string().to_lowercase(), lock.loader_version.clone());
 
     let pwpack = PwPack {
         name: modpack.name,
@@ -171,9 +160,15 @@ pub async fn export_packwiz(args: ExportPackwizArgs) -> Result<()> {
         }
         fs::write(full_path, file.pwmod_str)?;
     }
+
+    for (file_path, local) in local_files {
+        let full_path = args.export_path.join(file_path);
+        fs::create_dir_all(full_path.parent().unwrap())?;
+        fs::copy(local.path, full_path)?;
+    }
     
     progress.finish_with_message(format!("Exported to {}", args.export_path.to_string_lossy()));
-    Ok(())
+    print_artifacts("packwiz", &args.export_path)
 }
 
 #[derive(Debug, Deserialize, Serialize)]
@@ -193,14 +188,4 @@ impl ProjectType {
             _ => "overrides/unknown"
         }.into()
     }
-}
-
-impl From<i32> for HashFormat {
-    fn from(value: i32) -> Self {
-        match value {
-            1 => Self::Sha1,
-            2 => Self::Md5,
-            _ => unreachable!()
-        }
-    }
 }
\ No newline at end of file
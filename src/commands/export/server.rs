@@ -0,0 +1,151 @@
+use std::{env, fmt::Write as _, fs, io::Write, path::{Path, PathBuf}, time::Duration};
+
+use console::style;
+use indicatif::ProgressBar;
+use serde::Serialize;
+use walkdir::WalkDir;
+use zip::{write::SimpleFileOptions, ZipWriter};
+
+use crate::{cli::ExportServerArgs, error::{Error, Result}, structs::{index::{Index, Side}, lock::Lockfile, pack::{ModLoader, Modpack}}, util::{cache, files::add_file, output}};
+
+use super::print_artifacts;
+
+// override files & folders that only matter on the client
+const CLIENT_ONLY_OVERRIDES: [&str; 7] = [
+    "resourcepacks",
+    "shaderpacks",
+    "screenshots",
+    "options.txt",
+    "optionsof.txt",
+    "optionsshaders.txt",
+    "servers.dat"
+];
+
+#[derive(Debug, Serialize)]
+struct ServerInfo {
+    name: String,
+    version: String,
+    minecraft: String,
+    loader: ModLoader,
+    loader_version: String
+}
+
+pub async fn export_server(args: ExportServerArgs) -> Result<()> {
+    let modpack = Modpack::read()?;
+    let index = Index::read().await?;
+
+    let overrides_path = args.overrides_path.or(modpack.options.overrides_path.clone());
+    if overrides_path.as_ref().is_some_and(|path| !path.exists()) {
+        return Err(Error::Other("The overrides path provided does not exist".into()));
+    }
+
+    if args.dir.as_ref().is_some_and(|dir| dir.exists() && (!dir.is_dir() || dir.read_dir().is_ok_and(|mut d| d.next().is_some()))) {
+        return Err(Error::Other("Please provide an empty or new folder path to export to".into()));
+    }
+
+    let progress = ProgressBar::new_spinner().with_message("Exporting server pack");
+    progress.enable_steady_tick(Duration::from_millis(100));
+
+    progress.set_message("Resolving files");
+    let lock = Lockfile::for_export(args.locked).await?;
+
+    let mut unknown_addons = Vec::new();
+    let mut to_download = Vec::new();
+    for addon in index.addons {
+        match addon.side {
+            Side::Server | Side::Both => (),
+            Side::Unknown => {
+                unknown_addons.push(addon.name.clone());
+                if !args.include_unknown { continue; }
+            },
+            Side::Client => continue,
+        }
+
+        let locked = lock.require(&addon)?;
+        to_download.push((addon.project_type.export_folder(modpack.options.clone()).join(&locked.filename), locked.clone()));
+    }
+
+    progress.set_message("Downloading files");
+    // (path in the server pack, source file)
+    let mut files: Vec<(PathBuf, PathBuf)> = cache::fetch_locked(to_download).await?
+        .into_iter()
+        .map(|(path, cached)| (path, cached.path))
+        .collect();
+
+    if let Some(overrides_path) = &overrides_path {
+        for entry in WalkDir::new(overrides_path).into_iter().filter_map(|e| e.ok()) {
+            let path = entry.path();
+            if !path.is_file() { continue; }
+
+            let relative_path = path.strip_prefix(overrides_path).unwrap();
+            let is_client_only = relative_path.components().next()
+                .is_some_and(|c| CLIENT_ONLY_OVERRIDES.contains(&c.as_os_str().to_string_lossy().as_ref()));
+
+            if !is_client_only {
+                files.push((relative_path.to_path_buf(), path.to_path_buf()));
+            }
+        }
+    }
+
+    let info = ServerInfo {
+        name: modpack.name.clone(),
+        version: modpack.version.clone(),
+        minecraft: modpack.versions.minecraft.clone(),
+        loader: modpack.versions.loader.clone(),
+        loader_version: lock.loader_version.clone(),
+    };
+    let info_str = serde_json::to_string_pretty(&info)?;
+
+    progress.set_message("Writing files");
+    let output = match &args.dir {
+        Some(dir) => {
+            for (path, source) in &files {
+                let target = dir.join(path);
+                fs::create_dir_all(target.parent().unwrap())?;
+                fs::copy(source, target)?;
+            }
+            fs::write(dir.join("server-info.json"), info_str)?;
+            dir.clone()
+        },
+        None => {
+            let zip_path = env::current_dir()?.join(format!("{}-{}-server.zip", modpack.name, modpack.version));
+            create_server_zip(&zip_path, &files, &info_str)?;
+            zip_path
+        },
+    };
+
+    progress.finish_with_message(format!("Exported to {}", output.to_string_lossy()));
+    print_artifacts("server", &output)?;
+
+    if !unknown_addons.is_empty() {
+        let mut out = format!("{}\n", style(format!(
+            "{} {} an unknown side and {}:",
+            unknown_addons.len(),
+            if unknown_addons.len() == 1 { "addon has" } else { "addons have" },
+            if args.include_unknown { "were included" } else { "were left out, use --include-unknown to include them" }
+        )).color256(166));
+
+        unknown_addons.sort();
+        for name in unknown_addons {
+            writeln!(&mut out, "  {name}").unwrap();
+        }
+        output::note(out.trim_end());
+    }
+
+    Ok(())
+}
+
+fn create_server_zip(zip_path: &Path, files: &[(PathBuf, PathBuf)], info: &str) -> zip::result::ZipResult<()> {
+    let mut zip = ZipWriter::new(fs::File::create(zip_path)?);
+    let options = SimpleFileOptions::default().compression_method(zip::CompressionMethod::Deflated);
+
+    zip.start_file("server-info.json", options)?;
+    zip.write_all(info.as_bytes())?;
+
+    for (path, source) in files {
+        add_file(source, path, &mut zip, options)?;
+    }
+
+    zip.finish()?;
+    Ok(())
+}
\ No newline at end of file
@@ -0,0 +1,170 @@
+use std::{env, fs::{self, File}, io::{self, Read}, path::{Path, PathBuf}, sync::Arc, time::Duration};
+
+use console::style;
+use indicatif::ProgressBar;
+use tokio::{sync::Semaphore, task::JoinSet};
+use zip::ZipArchive;
+
+use crate::{cli::ImportCurseforgeArgs, error::{Error, Result}, structs::{cfpack::Manifest, index::{Addon, AddonOptions, AddonSource, CurseforgeSource, Index, ProjectType, Side}, pack::{ModLoader, Modpack, PackOptions, Versions}}, util::curseforge::{detect_side, project_type_side}, CURSEFORGE};
+
+pub async fn import_curseforge(args: ImportCurseforgeArgs) -> Result<()> {
+    if !args.path.is_file() || args.path.extension().unwrap_or_default() != "zip" {
+        return Err(Error::Other("The path you provided is not a curseforge pack zip file".into()));
+    }
+
+    let progress = ProgressBar::new_spinner().with_message("Reading pack zip file");
+    progress.enable_steady_tick(Duration::from_millis(100));
+
+    let mut zip = ZipArchive::new(File::open(args.path)?)?;
+
+    let mut manifest_string = String::new();
+    zip.by_name("manifest.json")
+        .map_err(|_| Error::BadImport("the zip file does not have a manifest.json".into()))?
+        .read_to_string(&mut manifest_string)?;
+    let manifest: Manifest = serde_json::from_str(&manifest_string)?;
+
+    let mod_loaders = &manifest.minecraft.mod_loaders;
+    let (loader, loader_version) = match mod_loaders.iter().find(|l| l.primary).or(mod_loaders.first()) {
+        Some(loader) => parse_loader_id(&loader.id)?,
+        None => return Err(Error::BadImport("modpack does not have a mod loader".into())),
+    };
+
+    progress.set_message("Adding mods");
+
+    let mut addons = Vec::new();
+    // (addon index, file) for mods that need their side read from the jar
+    let mut to_inspect = Vec::new();
+    if !manifest.files.is_empty() {
+        let cf_mods = CURSEFORGE.get_mods(manifest.files.iter().map(|f| f.project_id).collect()).await?;
+        // files that dont exist anymore are left out, they are warned about below
+        let cf_files = CURSEFORGE.get_files(manifest.files.iter().map(|f| (f.project_id, f.file_id)).collect()).await?;
+
+        for manifest_file in &manifest.files {
+            let found = cf_mods.iter().find(|m| m.id == manifest_file.project_id)
+                .zip(cf_files.iter().find(|f| f.id == manifest_file.file_id));
+            let (cf_mod, file) = match found {
+                Some((cf_mod, file)) => (cf_mod.clone(), file),
+                None => {
+                    let warning = format!("Skipping projectID {} fileID {}, it could not be found on curseforge", manifest_file.project_id, manifest_file.file_id);
+                    progress.suspend(|| println!("{}", style(warning).color256(166)));
+                    continue;
+                }
+            };
+
+            let project_type = match cf_mod.class_id.map(ProjectType::try_from) {
+                Some(Ok(project_type)) => project_type,
+                _ => {
+                    progress.suspend(|| println!("{}", style(Error::UnsupportedProjectType(cf_mod.name.clone()).to_string()).color256(166)));
+                    continue;
+                }
+            };
+
+            if project_type == ProjectType::Mod {
+                to_inspect.push((addons.len(), file.clone()));
+            }
+
+            addons.push(Addon {
+                name: cf_mod.name,
+                side: project_type_side(&project_type),
+                project_type,
+                source: AddonSource::Curseforge(CurseforgeSource {
+                    id: cf_mod.id,
+                    version: file.id,
+                }),
+                options: Some(AddonOptions::default()),
+                filename: Some(format!("{}.toml", cf_mod.slug))
+            });
+        }
+    }
+
+    progress.set_message("Reading mod sides");
+    let permits = Arc::new(Semaphore::new(10)); // limit file downloads to 10 at a time
+    let mut tasks: JoinSet<Result<(usize, Side)>> = JoinSet::new();
+    for (idx, file) in to_inspect {
+        let permits = permits.clone();
+        tasks.spawn(async move {
+            let _permit = permits.acquire().await.unwrap();
+            Ok((idx, detect_side(&file).await?))
+        });
+    }
+    while let Some(res) = tasks.join_next().await {
+        let (idx, side) = res??;
+        addons[idx].side = side;
+    }
+
+    progress.set_message("Extracting overrides");
+    let overrides_path = PathBuf::from("./overrides");
+    let has_overrides = extract_overrides(&mut zip, &manifest.overrides, &env::current_dir()?.join(&overrides_path))?;
+
+    progress.set_message("Importing pack");
+
+    let modpack = Modpack {
+        name: manifest.name,
+        version: if manifest.version.is_empty() { "0.1.0".into() } else { manifest.version },
+        authors: if manifest.author.is_empty() { vec![] } else { vec![manifest.author] },
+        description: None,
+        index_path: "./index".into(),
+        options: PackOptions {
+            overrides_path: has_overrides.then_some(overrides_path),
+            ..Default::default()
+        },
+        versions: Versions {
+            minecraft: manifest.minecraft.version,
+            loader,
+            loader_version,
+        },
+    };
+
+    Modpack::write(&modpack)?;
+    Index::write_addons(addons).await?;
+    progress.finish_with_message(format!("Imported {}", modpack.name));
+    Ok(())
+}
+
+// curseforge stores loaders as "loader-version", for example "forge-47.2.0"
+fn parse_loader_id(id: &str) -> Result<(ModLoader, String)> {
+    let (loader, version) = match id.split_once('-') {
+        Some(split) => split,
+        None => return Err(Error::BadImport(format!("unable to read mod loader '{id}'"))),
+    };
+
+    let loader = match loader {
+        "fabric" => ModLoader::Fabric,
+        "quilt" => ModLoader::Quilt,
+        "forge" => ModLoader::Forge,
+        "neoforge" => ModLoader::NeoForge,
+        _ => return Err(Error::BadImport(format!("unsupported mod loader '{loader}'"))),
+    };
+
+    Ok((loader, version.to_owned()))
+}
+
+// returns whether any override files were extracted
+fn extract_overrides(zip: &mut ZipArchive<File>, overrides_dir: &str, output: &Path) -> Result<bool> {
+    let mut extracted = false;
+    for i in 0..zip.len() {
+        let mut file = zip.by_index(i)?;
+        let path = match file.enclosed_name() {
+            Some(path) => path,
+            None => continue,
+        };
+
+        let relative_path = match path.strip_prefix(overrides_dir) {
+            Ok(path) if !path.as_os_str().is_empty() => output.join(path),
+            _ => continue,
+        };
+
+        if file.is_dir() {
+            fs::create_dir_all(&relative_path)?;
+            continue;
+        }
+
+        if let Some(parent) = relative_path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        io::copy(&mut file, &mut File::create(&relative_path)?)?;
+        extracted = true;
+    }
+
+    Ok(extracted)
+}
\ No newline at end of file
@@ -1,9 +1,9 @@
-use std::{env, fs::{self, File}, io::Read, path::PathBuf, time::Duration};
+use std::{env, fs::{self, File}, io::Read, time::Duration};
 
 use indicatif::ProgressBar;
 use zip::ZipArchive;
 
-use crate::{api::curseforge::{CurseAPI, File as CurseFile}, cli::ImportModrinthArgs, error::{Error, Result}, structs::{index::{Addon, AddonOptions, AddonSource, CurseforgeSource, Index, ModrinthSource, Side}, mrpack::{Metadata, PackDependency}, pack::{ModLoader, Modpack, PackOptions, Versions}}, util::modrinth::get_side, CURSEFORGE, MODRINTH};
+use crate::{api::curseforge::{CurseAPI, File as CurseFile}, cli::ImportModrinthArgs, error::{Error, Result}, structs::{index::{Addon, AddonOptions, AddonSource, CurseforgeSource, Index, ModrinthSource, Side}, jar::JarMetadata, mrpack::{Metadata, PackDependency}, pack::{ModLoader, Modpack, PackOptions, Versions}}, util::modrinth::get_side, CURSEFORGE, MODRINTH};
 
 pub async fn import_modrinth(args: ImportModrinthArgs) -> Result<()> {
     if !args.path.is_file() || args.path.extension().unwrap_or_default() != "mrpack" {
@@ -73,11 +73,18 @@ pub async fn import_modrinth(args: ImportModrinthArgs) -> Result<()> {
 
         for addon in cf_addons {
             let version_file = cf_files.iter().find(|f| f.mod_id == addon.id).unwrap();
+            let file_path = override_mods_dir.join(version_file.file_name.clone());
+
+            // the jar is right here, so its side can be read without downloading anything
+            let side = match JarMetadata::read(&file_path) {
+                Ok(Some(metadata)) => metadata.side,
+                _ => Side::Unknown,
+            };
 
             addons.push(Addon {
                 name: addon.name,
                 project_type: addon.class_id.unwrap().try_into()?,
-                side: Side::Both,
+                side,
                 source: AddonSource::Curseforge(CurseforgeSource {
                     id: addon.id,
                     version: version_file.id
@@ -86,7 +93,6 @@ pub async fn import_modrinth(args: ImportModrinthArgs) -> Result<()> {
                 filename: Some(format!("{}.toml", addon.slug))
             });
 
-            let file_path = override_mods_dir.join(version_file.file_name.clone());
             if file_path.is_file() {
                 fs::remove_file(file_path)?;
             }
@@ -4,7 +4,7 @@ use indicatif::ProgressBar;
 use serde::de::DeserializeOwned;
 use tokio::task::JoinSet;
 
-use crate::{cli::ImportPackwizArgs, error::{Error, Result}, structs::{index::{Addon, AddonOptions, AddonSource, CurseforgeSource, Index, ModrinthSource, ProjectType}, pack::{ModLoader, Modpack, PackOptions, Versions}, packwiz::{IndexFile, ModUpdate, PwIndex, PwMod, PwPack}}};
+use crate::{api::request, cli::ImportPackwizArgs, error::{Error, Result}, structs::{index::{Addon, AddonOptions, AddonSource, CurseforgeSource, Index, ModrinthSource, ProjectType}, pack::{ModLoader, Modpack, PackOptions, Versions}, packwiz::{IndexFile, ModUpdate, PwIndex, PwMod, PwPack}}};
 
 pub async fn import_packwiz(args: ImportPackwizArgs) -> Result<()> {
     if !args.source.ends_with("pack.toml") {
@@ -20,18 +20,30 @@ pub async fn import_packwiz(args: ImportPackwizArgs) -> Result<()> {
     let progress = ProgressBar::new_spinner().with_message("Importing packwiz pack");
     progress.enable_steady_tick(Duration::from_millis(100));
 
-    let base_path = args.source.strip_suffix("/pack.toml").unwrap().to_string();
-
     progress.set_message("Reading pack files");
+    let (modpack, addons) = read_packwiz(&args.source).await?;
+
+    progress.set_message("Importing pack");
 
-    let source_pack: PwPack = PwFile::from(&args.source).get_content().await?;
-    let file_index: PwIndex = PwFile::from(format!("{}/{}", base_path, source_pack.index.file)).get_content().await?;
+    Modpack::write(&modpack)?;
+    Index::write_addons(addons).await?;
+    progress.finish_with_message(format!("Imported {}", modpack.name));
+    Ok(())
+}
+
+// reads a packwiz pack from the path or url of its pack.toml
+pub async fn read_packwiz(source: &str) -> Result<(Modpack, Vec<Addon>)> {
+    // keeps the trailing slash, so a pack.toml in the current folder has an empty base path
+    let base_path = source.strip_suffix("pack.toml").unwrap().to_string();
+    let source_pack: PwPack = PwFile::from(source).get_content().await?;
+    let file_index: PwIndex = PwFile::from(format!("{}{}", base_path, source_pack.index.file)).get_content().await?;
 
     let mut tasks: JoinSet<Result<Addon>> = JoinSet::new();
-    for file in file_index.files {
+    // only metafiles describe addons, the rest are files like configs
+    for file in file_index.files.into_iter().filter(|f| f.metafile == Some(true)) {
         let base_path = base_path.clone();
         let task = async move {
-            let pw_mod: PwMod = PwFile::from(format!("{}/{}", base_path, file.file)).get_content().await?;
+            let pw_mod: PwMod = PwFile::from(format!("{}{}", base_path, file.file)).get_content().await?;
             Ok(Addon {
                 name: pw_mod.name.clone(),
                 project_type: file.get_project_type(),
@@ -58,8 +70,6 @@ pub async fn import_packwiz(args: ImportPackwizArgs) -> Result<()> {
         }
     }?;
 
-    progress.set_message("Importing pack");
-
     let modpack = Modpack {
         name: source_pack.name,
         version: source_pack.version.unwrap_or("0.1.0".into()),
@@ -74,10 +84,7 @@ pub async fn import_packwiz(args: ImportPackwizArgs) -> Result<()> {
         },
     };
 
-    Modpack::write(&modpack)?;
-    Index::write_addons(addons).await?;
-    progress.finish_with_message(format!("Imported {}", modpack.name));
-    Ok(())
+    Ok((modpack, addons))
 }
 
 enum PwFile {
@@ -92,11 +99,7 @@ impl PwFile {
                 fs::read_to_string(path)?
             },
             PwFile::Url(url) => {
-                reqwest::get(url)
-                    .await?
-                    .error_for_status()?
-                    .text()
-                    .await?
+                request::get(url).await?.text().await?
             },
         };
         Ok(toml::from_str(&str).unwrap())
@@ -126,8 +129,8 @@ impl TryFrom<Option<ModUpdate>> for AddonSource {
                     }))
                 } else if let Some(source) = value.curseforge {
                     Ok(Self::Curseforge(CurseforgeSource {
-                        id: source.file_id,
-                        version: source.project_id,
+                        id: source.project_id,
+                        version: source.file_id,
                     }))
                 } else {
                     Err(Error::Other("unable to import mod as it does not have update information".into()))
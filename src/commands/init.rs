@@ -1,7 +1,7 @@
 use console::style;
 use dialoguer::{Input, Select};
 
-use crate::{cli::InitArgs, error::Result, structs::pack::{ModLoader, Modpack, PackOptions, Versions}, util::versions::{get_latest_loader_version, minecraft::{get_latest_release, get_latest_snapshot, list_mc_versions, VersionType}}};
+use crate::{cli::InitArgs, error::Result, structs::pack::{ModLoader, Modpack, PackOptions, Versions}, util::{prompt, versions::{minecraft::{get_latest_release, get_latest_snapshot, list_mc_versions, VersionType}}}};
 
 pub async fn init(args: InitArgs) -> Result<()> {
     if !args.reinit && Modpack::path().is_file() {
@@ -12,6 +12,7 @@ pub async fn init(args: InitArgs) -> Result<()> {
     let name: String = match args.name {
         Some(name) => name,
         None => {
+            prompt::require_input("Naming the modpack", "use --name/-n")?;
             Input::new()
             .with_prompt("Name your modpack")
             .interact_text()
@@ -24,12 +25,16 @@ pub async fn init(args: InitArgs) -> Result<()> {
     } else if args.latest_snapshot {
         get_latest_snapshot().await?
     } else {
+        prompt::require_input("Choosing a minecraft version", "use --latest/-l or --latest-snapshot")?;
         pick_game_version(args.show_snapshots).await?
     };
 
     let loader = match args.loader {
         Some(loader) => loader,
-        None => pick_loader().await?,
+        None => {
+            prompt::require_input("Choosing the modloader", "use --loader/-L")?;
+            pick_loader().await?
+        },
     };
 
     let mut options = PackOptions::default();
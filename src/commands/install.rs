@@ -0,0 +1,150 @@
+use std::{collections::BTreeMap, fs, path::{Path, PathBuf}, sync::Arc, time::Duration};
+
+use console::style;
+use indicatif::ProgressBar;
+use sha1::{Digest, Sha1};
+use tokio::{sync::Semaphore, task::JoinSet};
+use walkdir::WalkDir;
+
+use crate::{cli::InstallArgs, error::{Error, Result}, structs::{index::{Addon, Index, Side}, install::InstallManifest, lock::{LockedAddon, Lockfile}, pack::Modpack}, util::{cache, files::{download_file, is_inside}}};
+
+const MANIFEST_FILE: &str = ".emm-install.json";
+
+enum InstallSource {
+    Addon(LockedAddon),
+    Override(PathBuf)
+}
+
+pub async fn install(args: InstallArgs) -> Result<()> {
+    let modpack = Modpack::read()?;
+    let index = Index::read().await?;
+
+    if args.dir.is_file() {
+        return Err(Error::Other("The install path must be a folder".into()));
+    }
+    fs::create_dir_all(&args.dir)?;
+
+    let progress = ProgressBar::new_spinner().with_message("Resolving files");
+    progress.enable_steady_tick(Duration::from_millis(100));
+
+    let lock = Lockfile::for_export(args.locked).await?;
+
+    // relative path => (sha1, source)
+    let mut files: BTreeMap<String, (String, InstallSource)> = BTreeMap::new();
+
+    for addon in index.addons.iter().filter(|a| matches_side(a, args.side.as_ref())) {
+        let locked = lock.require(addon)?;
+        check_filename(&locked.filename)?;
+        let path = addon.project_type.export_folder(modpack.options.clone()).join(&locked.filename);
+        files.insert(relative_str(&path), (locked.sha1.clone(), InstallSource::Addon(locked.clone())));
+    }
+
+    if let Some(overrides_path) = &modpack.options.overrides_path {
+        for entry in WalkDir::new(overrides_path).into_iter().filter_map(|e| e.ok()) {
+            let path = entry.path();
+            if !path.is_file() { continue; }
+
+            let sha1 = format!("{:x}", Sha1::digest(fs::read(path)?));
+            let relative_path = path.strip_prefix(overrides_path).unwrap();
+            files.insert(relative_str(relative_path), (sha1, InstallSource::Override(path.to_path_buf())));
+        }
+    }
+
+    let manifest_path = args.dir.join(MANIFEST_FILE);
+    let previous: InstallManifest = match fs::read_to_string(&manifest_path) {
+        Ok(manifest_str) => serde_json::from_str(&manifest_str)?,
+        Err(_) => InstallManifest::default(),
+    };
+
+    progress.set_message("Installing files");
+
+    let mut unchanged = 0;
+    let mut installed = 0;
+    let permits = Arc::new(Semaphore::new(10)); // limit file downloads to 10 at a time
+    let mut tasks: JoinSet<Result<()>> = JoinSet::new();
+
+    for (relative_path, (sha1, source)) in &files {
+        let target = args.dir.join(relative_path);
+        if target.is_file() && previous.files.get(relative_path) == Some(sha1) {
+            unchanged += 1;
+            continue;
+        }
+
+        installed += 1;
+        match source {
+            InstallSource::Override(path) => {
+                fs::create_dir_all(target.parent().unwrap())?;
+                fs::copy(path, &target)?;
+            },
+            InstallSource::Addon(locked) => {
+                let permits = permits.clone();
+                let locked = locked.clone();
+
+                let task = async move {
+                    let _permit = permits.acquire().await.unwrap();
+                    if locked.path.is_some() {
+                        let local = cache::fetch_locked_file(&locked).await?;
+                        fs::create_dir_all(target.parent().unwrap())?;
+                        fs::copy(local.path, &target)?;
+                        return Ok(());
+                    }
+
+                    let url = match &locked.url {
+                        Some(url) => url,
+                        None => return Err(Error::Other(format!("Cant install {} because it does not have a download url", locked.filename))),
+                    };
+
+                    download_file(&target, url, Some(&locked.sha1), locked.sha512.as_deref()).await
+                };
+
+                tasks.spawn(task);
+            },
+        }
+    }
+
+    while let Some(res) = tasks.join_next().await { res?? }
+
+    // remove files emm installed before that arent part of the pack anymore,
+    // the manifest can be edited so paths outside of the install folder are left alone
+    let mut removed = 0;
+    for relative_path in previous.files.keys().filter(|p| !files.contains_key(*p) && is_inside(&args.dir, Path::new(p))) {
+        let target = args.dir.join(relative_path);
+        if target.is_file() {
+            fs::remove_file(target)?;
+            removed += 1;
+        }
+    }
+
+    let manifest = InstallManifest {
+        files: files.into_iter().map(|(path, (sha1, _))| (path, sha1)).collect(),
+    };
+    fs::write(manifest_path, serde_json::to_string_pretty(&manifest)?)?;
+
+    progress.finish_and_clear();
+    println!("Installed {} to {} {}",
+        modpack.name,
+        args.dir.to_string_lossy(),
+        style(format!("({installed} installed, {removed} removed, {unchanged} unchanged)")).dim()
+    );
+    Ok(())
+}
+
+fn matches_side(addon: &Addon, side: Option<&Side>) -> bool {
+    match side {
+        None | Some(Side::Both) => true,
+        Some(side) => matches!(addon.side, Side::Both | Side::Unknown) || &addon.side == side,
+    }
+}
+
+// filenames come from the platforms and emm.lock, they shouldnt be able to point outside of their folder
+fn check_filename(filename: &str) -> Result<()> {
+    if filename.is_empty() || filename == "." || filename == ".." || filename.contains(['/', '\\', ':']) {
+        return Err(Error::Other(format!("Refusing to install '{filename}', it is not a plain file name")));
+    }
+    Ok(())
+}
+
+// always use forward slashes so the manifest is the same on every os
+fn relative_str(path: &Path) -> String {
+    path.components().map(|c| c.as_os_str().to_string_lossy()).collect::<Vec<_>>().join("/")
+}
\ No newline at end of file
@@ -1,8 +1,23 @@
 use std::fmt::Write;
 
 use console::style;
+use serde::Serialize;
+use serde_json::json;
 
-use crate::{cli::ListArgs, error::Result, structs::index::{Addon, AddonSource, Index}, util::to_hyperlink, CURSEFORGE, GITHUB, MODRINTH};
+use crate::{cli::ListArgs, error::Result, structs::{index::{Addon, AddonSource, Index}, lock::{LockedAddon, Lockfile}}, util::{output, to_hyperlink}, CURSEFORGE, GITHUB, MODRINTH};
+
+// an index entry with what's known about it beyond the index
+#[derive(Serialize)]
+struct ListedAddon<'a> {
+    #[serde(flatten)]
+    addon: &'a Addon,
+    id: String,
+    version: String,
+    url: Option<String>,
+    description: Option<&'a str>,
+    // the file the lockfile resolved the addon to
+    file: Option<&'a LockedAddon>
+}
 
 pub async fn list(args: ListArgs) -> Result<()> {
     let mut index = Index::read().await?;
@@ -17,32 +32,43 @@ pub async fn list(args: ListArgs) -> Result<()> {
 
     let cf_links: Vec<(i32, String)> = CURSEFORGE.get_mods(cf_addon_ids).await.unwrap_or_default().into_iter().map(|a| (a.id, a.links.website_url)).collect();
 
+    if output::json() {
+        let descriptions = get_descriptions(&index.addons).await?;
+        // listing shouldnt change the working tree, so the lock is only resolved in memory
+        let lock = Lockfile::resolve(false).await?;
+
+        let addons: Vec<ListedAddon> = index.addons.iter().map(|addon| ListedAddon {
+            addon,
+            id: addon.generic_id(),
+            version: addon.generic_version(),
+            url: get_url(addon, &cf_links),
+            description: descriptions.iter().find(|d| d.0 == addon.generic_id()).map(|d| d.1.as_str()),
+            file: lock.get(addon),
+        }).collect();
+
+        return output::print_json(&json!({ "addons": addons }));
+    }
+
     if args.markdown {
-        let mut mr_ids = Vec::new();
-        let mut cf_ids = Vec::new();
-        let mut gh_ids = Vec::new();
-        index.addons.iter().for_each(|a| match &a.source {
-            AddonSource::Modrinth(source) => mr_ids.push(source.id.as_str()),
-            AddonSource::Curseforge(source) => cf_ids.push(source.id),
-            AddonSource::Github(source) => gh_ids.push({
-                let repo_split: Vec<&str> = source.repo.split('/').collect();
-                (repo_split[0].to_owned(), repo_split[1].to_owned())
-            }),
-        });
-
-        // (project_id, description)
-        let mut descriptions: Vec<(String, String)> = Vec::new();
-        descriptions.extend(MODRINTH.get_multiple_projects(&mr_ids).await?.into_iter().map(|a| (a.id, a.description)));
-        descriptions.extend(CURSEFORGE.get_mods(cf_ids).await?.into_iter().map(|a| (a.id.to_string(), a.summary)));
-        descriptions.extend(GITHUB.get_repos(gh_ids).await?.into_iter().map(|a| (a.full_name.to_lowercase(), a.description)));
-        
+        let descriptions = get_descriptions(&index.addons).await?;
 
         for addon in index.addons {
-            let url = get_url(&addon, &cf_links);
+            // local addons dont have a page to link to
+            let Some(url) = get_url(&addon, &cf_links) else {
+                writeln!(&mut out, "**{}** (local file)\n", addon.name).unwrap();
+                continue;
+            };
+
             let version_url = match &addon.source {
                 AddonSource::Modrinth(source) => format!("{url}/version/{}", source.version),
                 AddonSource::Curseforge(source) => format!("{url}/files/{}", source.version),
                 AddonSource::Github(source) => format!("{url}/releases/tag/{}", source.tag),
+                // direct downloads dont have a project page or a description
+                AddonSource::Url(_) => {
+                    writeln!(&mut out, "**[{}]({url})** (direct download)\n", addon.name).unwrap();
+                    continue;
+                },
+                AddonSource::Local(_) => unreachable!(),
             };
 
             writeln!(&mut out, "**[{name}]({url})** ([version]({version_url}))\n{description}\n",
@@ -58,14 +84,19 @@ pub async fn list(args: ListArgs) -> Result<()> {
                 AddonSource::Modrinth(_) => style("MR").green().dim(),
                 AddonSource::Curseforge(_) => style("CF").color256(166).dim(),
                 AddonSource::Github(_) => style("GH").magenta().dim(),
+                AddonSource::Local(_) => style("LF").blue().dim(),
+                AddonSource::Url(_) => style("DL").yellow().dim(),
             };
 
-            let url = get_url(&addon, &cf_links);
+            let id_link = match get_url(&addon, &cf_links) {
+                Some(url) => to_hyperlink(&url, &addon.generic_id()),
+                None => addon.generic_id(),
+            };
 
             writeln!(&mut out,
                 "{name:max_name_width$}  {id_prefix} {id_link}",
                 name = style(&addon.name).bold(),
-                id_link = style(to_hyperlink(&url, &addon.generic_id())).dim()
+                id_link = style(id_link).dim()
             ).unwrap();
         }
     }
@@ -74,10 +105,34 @@ pub async fn list(args: ListArgs) -> Result<()> {
     Ok(())
 }
 
-fn get_url(addon: &Addon, cf_links: &[(i32, String)]) -> String {
+// (project_id, description)
+async fn get_descriptions(addons: &[Addon]) -> Result<Vec<(String, String)>> {
+    let mut mr_ids = Vec::new();
+    let mut cf_ids = Vec::new();
+    let mut gh_ids = Vec::new();
+    addons.iter().for_each(|a| match &a.source {
+        AddonSource::Modrinth(source) => mr_ids.push(source.id.as_str()),
+        AddonSource::Curseforge(source) => cf_ids.push(source.id),
+        AddonSource::Github(source) => gh_ids.push({
+            let repo_split: Vec<&str> = source.repo.split('/').collect();
+            (repo_split[0].to_owned(), repo_split[1].to_owned())
+        }),
+        AddonSource::Local(_) | AddonSource::Url(_) => (),
+    });
+
+    let mut descriptions: Vec<(String, String)> = Vec::new();
+    descriptions.extend(MODRINTH.get_multiple_projects(&mr_ids).await?.into_iter().map(|a| (a.id, a.description)));
+    descriptions.extend(CURSEFORGE.get_mods(cf_ids).await?.into_iter().map(|a| (a.id.to_string(), a.summary)));
+    descriptions.extend(GITHUB.get_repos(gh_ids).await?.into_iter().map(|a| (a.full_name.to_lowercase(), a.description)));
+    Ok(descriptions)
+}
+
+fn get_url(addon: &Addon, cf_links: &[(i32, String)]) -> Option<String> {
     match &addon.source {
-        AddonSource::Modrinth(source) => format!("https://modrinth.com/project/{}", source.id),
-        AddonSource::Curseforge(source) => cf_links.iter().find(|l| l.0 == source.id).unwrap().1.clone(),
-        AddonSource::Github(source) => format!("https://github.com/{}", source.repo),
+        AddonSource::Modrinth(source) => Some(format!("https://modrinth.com/project/{}", source.id)),
+        AddonSource::Curseforge(source) => Some(cf_links.iter().find(|l| l.0 == source.id).unwrap().1.clone()),
+        AddonSource::Github(source) => Some(format!("https://github.com/{}", source.repo)),
+        AddonSource::Url(source) => Some(source.url.clone()),
+        AddonSource::Local(_) => None,
     }
 }
\ No newline at end of file
@@ -1,12 +1,15 @@
-use std::{fmt::Write, ops::Deref, sync::Arc, time::Duration};
+use std::{fmt::Write, sync::Arc, time::Duration};
 
 use console::style;
-use dialoguer::Confirm;
 use indicatif::ProgressBar;
+use serde::Serialize;
+use serde_json::{json, Value};
 use tokio::task::JoinSet;
 
-use crate::{cli::MigrateArgs, commands::init::pick_game_version, error::Result, structs::{index::{Addon, AddonSource, CurseforgeSource, GithubSource, Index, ModrinthSource, ProjectType}, pack::{ModLoader, Modpack}}, util::versions::get_latest_loader_version, CURSEFORGE, MODRINTH};
+use crate::{cli::MigrateArgs, commands::init::pick_game_version, error::Result, structs::{index::{Addon, AddonSource, CurseforgeSource, GithubSource, Index, ModrinthSource, ProjectType, ReleaseChannel}, jar::JarMetadata, lock::Lockfile, pack::{ModLoader, Modpack}}, util::{get_version_filters, output, prompt, github::find_asset, jar::inspect, min_release_channel, versions::get_latest_loader_version, FilterVersions}, CURSEFORGE, GITHUB, MODRINTH};
 
+#[derive(Clone, Copy, Serialize)]
+#[serde(rename_all = "lowercase")]
 enum Compatibility {
     Compatible,
     Partial, // compatible through acceptable_versions option
@@ -19,9 +22,15 @@ type AddonCompat = (Addon, Option<String>, Compatibility);
 
 pub async fn migrate(args: MigrateArgs) -> Result<()> {
     let modpack = Arc::new(Modpack::read()?);
-    let index = Index::read().await?;
+    let index = Index::read_migrated().await?;
 
-    let new_version = Arc::new(pick_game_version(args.show_snapshots).await?);
+    let new_version = Arc::new(match args.game_version {
+        Some(version) => version,
+        None => {
+            prompt::require_input("Choosing a minecraft version", "pass the version to migrate to")?;
+            pick_game_version(args.show_snapshots).await?
+        },
+    });
 
     let progress = ProgressBar::new_spinner().with_message("Finding compatible versions");
     progress.enable_steady_tick(Duration::from_millis(100));
@@ -29,20 +38,37 @@ pub async fn migrate(args: MigrateArgs) -> Result<()> {
     let mut mr_addons = Vec::new();
     let mut cf_addons = Vec::new();
     let mut gh_addons = Vec::new();
+    let mut local_addons = Vec::new();
+    let mut url_addons = Vec::new();
     index.addons.into_iter().for_each(|a| match a.source.clone() {
         AddonSource::Modrinth(source) => mr_addons.push((a, source.id)),
         AddonSource::Curseforge(source) => cf_addons.push((a, source.id)),
-        AddonSource::Github(_) => gh_addons.push(a)
+        AddonSource::Github(source) => gh_addons.push((a, source)),
+        AddonSource::Local(source) => local_addons.push((a, source.path, source.sha1)),
+        AddonSource::Url(source) => url_addons.push((a, source))
     });
 
     let mut to_migrate: Vec<AddonCompat> = Vec::new();
+
+    // local files and direct downloads stay the same, only their metadata can say if they still work
+    let (_, loaders) = get_version_filters(&modpack);
+    for (addon, path, sha1) in local_addons {
+        let (compatibility, version) = jar_compatibility(JarMetadata::read(&path)?, &new_version, &loaders, sha1);
+        to_migrate.push((addon, version, compatibility));
+    }
+
+    for (addon, source) in url_addons {
+        let (compatibility, version) = jar_compatibility(inspect(&source.url, Some(&source.sha1)).await?, &new_version, &loaders, source.sha1);
+        to_migrate.push((addon, version, compatibility));
+    }
     let mut tasks: JoinSet<Result<AddonCompat>> = JoinSet::new();
 
     for addon in mr_addons {
         let modpack = modpack.clone();
         let new_version = new_version.clone();
         let task = async move {
-            let versions = MODRINTH.get_project_versions(&addon.1).await?;
+            let channel = min_release_channel(&modpack, addon.0.options.as_ref());
+            let versions = MODRINTH.get_project_versions(&addon.1).await?.filter_release_channel(channel);
     
             let (compatibility, version) = 'compat: {
                 if !versions.iter().any(|v| match addon.0.project_type {
@@ -75,7 +101,8 @@ pub async fn migrate(args: MigrateArgs) -> Result<()> {
         let modpack = modpack.clone();
         let new_version = new_version.clone();
         let task = async move {
-            let files = CURSEFORGE.get_mod_files(&addon.1).await?;
+            let channel = min_release_channel(&modpack, addon.0.options.as_ref());
+            let files = CURSEFORGE.get_mod_files(&addon.1).await?.filter_release_channel(channel);
     
             let (compatibility, version) = 'compat: {
                 if !files.iter().any(|f| match addon.0.project_type {
@@ -104,9 +131,19 @@ pub async fn migrate(args: MigrateArgs) -> Result<()> {
         tasks.spawn(task);
     }
 
-    while let Some(res) = tasks.join_next().await { to_migrate.push(res??) }
+    for addon in gh_addons {
+        let modpack = modpack.clone();
+        let new_version = new_version.clone();
+        let task = async move {
+            let channel = min_release_channel(&modpack, addon.0.options.as_ref());
+            let (compatibility, version) = check_github(&modpack, &addon.1, &new_version, channel).await?;
+            Ok((addon.0, version, compatibility))
+        };
 
-    to_migrate.extend(gh_addons.into_iter().map(|addon| (addon, None, Compatibility::Unknown)));
+        tasks.spawn(task);
+    }
+
+    while let Some(res) = tasks.join_next().await { to_migrate.push(res??) }
 
     progress.finish_and_clear();
 
@@ -138,23 +175,28 @@ pub async fn migrate(args: MigrateArgs) -> Result<()> {
         unknown = style("unknown").blue()
     ).unwrap();
 
-    print!("{}", out);
+    // the report is part of the json instead
+    if !output::json() {
+        print!("{}", out);
+    }
+
+    let report: Vec<_> = to_migrate.iter().map(|(addon, version, compatibility)| json!({
+        "name": addon.name,
+        "id": addon.generic_id(),
+        "from": addon.generic_version(),
+        "to": version,
+        "compatibility": compatibility,
+    })).collect();
+    let old_version = modpack.versions.minecraft.clone();
 
-    if !Confirm::new()
-        .with_prompt("Migrate to new version?")
-        .interact()
-        .unwrap()
-    {
-        return Ok(());
+    if !prompt::confirm("Migrate to new version?", None) {
+        return print_json_report(&old_version, &new_version, false, false, report);
     }
 
-    if incompatible_count > 0 && Confirm::new()
-        .with_prompt("Remove incompatible mods from index?")
-        .default(false)
-        .interact()
-        .unwrap()
-    {
-        println!("Removing {} incompatible {}", incompatible_count, if incompatible_count == 1 { "addon" } else { "addons" });
+    // without input incompatible addons are kept in the index
+    let remove_incompatible = incompatible_count > 0 && prompt::confirm("Remove incompatible mods from index?", Some(false));
+    if remove_incompatible {
+        output::note(format!("Removing {} incompatible {}", incompatible_count, if incompatible_count == 1 { "addon" } else { "addons" }));
         Index::remove_addons(&to_migrate.iter().filter_map(|(addon, _, compat)| match compat {
             Compatibility::Incompatible => Some(addon),
             _ => None
@@ -175,11 +217,82 @@ pub async fn migrate(args: MigrateArgs) -> Result<()> {
             AddonSource::Modrinth(source) => AddonSource::Modrinth(ModrinthSource { version, ..source }),
             AddonSource::Curseforge(source) => AddonSource::Curseforge(CurseforgeSource { version: version.parse::<i32>().unwrap(), ..source }),
             AddonSource::Github(source) => AddonSource::Github(GithubSource { tag: version, ..source }),
+            AddonSource::Local(source) => AddonSource::Local(source),
+            AddonSource::Url(source) => AddonSource::Url(source),
         },
         ..addon
     })).collect();
 
     Index::write_addons(migrated_addons).await?;
-    println!("Migrated to {}", new_version);
-    Ok(())
+    Lockfile::update(false).await?;
+    output::note(format!("Migrated to {}", new_version));
+    print_json_report(&old_version, &new_version, true, remove_incompatible, report)
+}
+
+fn print_json_report(from: &str, to: &str, migrated: bool, removed_incompatible: bool, addons: Vec<Value>) -> Result<()> {
+    match output::json() {
+        true => output::print_json(&json!({
+            "from": from,
+            "to": to,
+            "migrated": migrated,
+            "removed_incompatible": removed_incompatible,
+            "addons": addons,
+        })),
+        false => Ok(()),
+    }
+}
+
+// the version stays the same, unless the file is incompatible
+fn jar_compatibility(metadata: Option<JarMetadata>, new_version: &String, loaders: &[&ModLoader], version: String) -> (Compatibility, Option<String>) {
+    match metadata.and_then(|m| m.is_compatible(&[new_version], loaders)) {
+        Some(true) => (Compatibility::Compatible, Some(version)),
+        Some(false) => (Compatibility::Incompatible, None),
+        None => (Compatibility::Unknown, Some(version)),
+    }
+}
+
+// github releases dont have version info, so the jars of recent releases are read instead
+async fn check_github(modpack: &Modpack, source: &GithubSource, new_version: &String, channel: Option<&ReleaseChannel>) -> Result<(Compatibility, Option<String>)> {
+    let repo_split: Vec<&str> = source.repo.split('/').collect();
+    let mut pages = GITHUB.release_pages(repo_split[0], repo_split[1]);
+    let (_, loaders) = get_version_filters(modpack);
+
+    // only the most recent releases, to avoid downloading every asset a repo ever had
+    let mut releases = Vec::new();
+    while releases.len() < 5 {
+        match pages.next().await? {
+            Some(page) => releases.extend(page.into_iter().filter(|r| r.release_channel().allowed_by(channel))),
+            None => break,
+        }
+    }
+
+    let mut partial = None;
+    let mut checked = false;
+    for release in releases.iter().take(5) {
+        let metadata = match find_asset(release, &source.asset) {
+            Some(asset) => match inspect(&asset.browser_download_url, None).await? {
+                Some(metadata) => metadata,
+                None => continue,
+            },
+            None => continue,
+        };
+
+        match metadata.is_compatible(&[new_version], &loaders) {
+            Some(true) => return Ok((Compatibility::Compatible, Some(release.tag_name.clone()))),
+            Some(false) => checked = true,
+            None => continue,
+        }
+
+        if let Some(acceptable_versions) = &modpack.options.acceptable_versions {
+            if partial.is_none() && metadata.is_compatible(&acceptable_versions.iter().collect::<Vec<&String>>(), &loaders) == Some(true) {
+                partial = Some(release.tag_name.clone());
+            }
+        }
+    }
+
+    Ok(match partial {
+        Some(tag) => (Compatibility::Partial, Some(tag)),
+        None if checked => (Compatibility::Incompatible, None),
+        None => (Compatibility::Unknown, None),
+    })
 }
\ No newline at end of file
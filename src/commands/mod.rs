@@ -2,9 +2,16 @@ pub mod init;
 pub mod add;
 pub mod remove;
 pub mod update;
+pub mod outdated;
 pub mod import;
 pub mod export;
 pub mod migrate;
 pub mod pin;
 pub mod unpin;
-pub mod list;
\ No newline at end of file
+pub mod list;
+pub mod install;
+pub mod cache;
+pub mod doctor;
+pub mod why;
+pub mod diff;
+pub mod search;
\ No newline at end of file
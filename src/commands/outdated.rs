@@ -0,0 +1,90 @@
+use std::{fmt::Write, sync::Arc};
+
+use console::style;
+use serde_json::json;
+
+use crate::{
+    cli::OutdatedArgs, commands::update::{find_updates, Update}, error::{Error, Result},
+    structs::{index::{Addon, Index}, pack::Modpack}, util::{escape_html, output, to_hyperlink}
+};
+
+pub async fn outdated(args: OutdatedArgs) -> Result<()> {
+    let modpack = Arc::new(Modpack::read()?);
+    let mut index = Index::read().await?;
+
+    if let Some(addons) = &args.addons {
+        let selected_addons: Vec<Addon> = addons.iter().filter_map(|str| index.select_addon(str)).cloned().collect();
+        index.addons = selected_addons
+    }
+    index.addons.sort_by_key(|a| a.name.to_lowercase());
+
+    // pinned addons are checked too, they are only marked as pinned
+    let outdated = find_updates(&modpack, index.addons, false).await?;
+
+    if output::json() {
+        output::print_json(&json!({ "outdated": outdated }))?;
+    } else if args.markdown {
+        print!("{}", markdown(&outdated));
+    } else if outdated.is_empty() {
+        println!("All addons are up to date!");
+    } else {
+        print!("{}", text(&outdated));
+    }
+
+    let available = outdated.iter().filter(|u| !u.pinned).count();
+    if args.exit_code && available > 0 {
+        return Err(Error::Other(format!("{available} {} available", if available == 1 { "update" } else { "updates" })));
+    }
+
+    Ok(())
+}
+
+fn text(outdated: &[Update]) -> String {
+    let name_width = outdated.iter().map(|u| u.name.chars().count()).max().unwrap_or_default();
+    let current_width = outdated.iter().map(|u| u.current.chars().count()).max().unwrap_or_default();
+
+    let mut out = String::new();
+    for update in outdated {
+        let version = match &update.url {
+            Some(url) => to_hyperlink(url, &update.version),
+            None => update.version.clone(),
+        };
+
+        writeln!(&mut out, "{name}  {current}  {arrow} {version}{pinned}",
+            name = if update.pinned { style(format!("{:name_width$}", update.name)).bold().dim() } else { style(format!("{:name_width$}", update.name)).bold() },
+            current = style(format!("{:current_width$}", update.current)).dim(),
+            arrow = style("→").dim(),
+            version = style(version).green(),
+            pinned = if update.pinned { style(" (pinned)").color256(166) } else { style("") }
+        ).unwrap();
+    }
+
+    out
+}
+
+fn markdown(outdated: &[Update]) -> String {
+    if outdated.is_empty() {
+        return "All addons are up to date.\n".into();
+    }
+
+    let mut out = String::from("| Addon | Current | Latest |\n| --- | --- | --- |\n");
+    for update in outdated {
+        let version = match &update.url {
+            Some(url) => format!("[{}]({url})", escape_cell(&update.version)),
+            None => escape_cell(&update.version),
+        };
+
+        writeln!(&mut out, "| {}{} | {} | {version} |",
+            escape_cell(&update.name),
+            if update.pinned { " (pinned)" } else { "" },
+            escape_cell(&update.current)
+        ).unwrap();
+    }
+
+    out
+}
+
+// a | in a name would end the table cell early
+fn escape_cell(str: &str) -> String {
+    escape_html(str).replace('|', "\\|")
+}
\ No newline at end of file
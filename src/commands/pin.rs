@@ -1,9 +1,9 @@
 use console::style;
 
-use crate::{cli::PinArgs, error::{Error, Result}, structs::index::{Addon, AddonOptions, AddonSource, CurseforgeSource, GithubSource, Index, ModrinthSource}, CURSEFORGE, GITHUB, MODRINTH};
+use crate::{cli::PinArgs, error::{Error, Result}, structs::{index::{Addon, AddonOptions, AddonSource, CurseforgeSource, GithubSource, Index, ModrinthSource}, lock::Lockfile}, util::github::find_asset, CURSEFORGE, GITHUB, MODRINTH};
 
 pub async fn pin(args: PinArgs) -> Result<()> {
-    let index = Index::read().await?;
+    let index = Index::read_migrated().await?;
 
     if let Some(addon) = index.select_addon(&args.addon).cloned() {
         if !addon.options.clone().unwrap_or_default().pinned {
@@ -26,13 +26,16 @@ pub async fn pin(args: PinArgs) -> Result<()> {
                         CurseforgeSource { version: args.version.map(|v| v.parse::<i32>().unwrap()).unwrap_or(source.version), ..source }
                     ),
                     AddonSource::Github(source) => AddonSource::Github(
-                        GithubSource { repo: args.version.unwrap_or(source.repo), ..source }
+                        GithubSource { tag: args.version.unwrap_or(source.tag), ..source }
                     ),
+                    AddonSource::Local(source) => AddonSource::Local(source),
+                    AddonSource::Url(source) => AddonSource::Url(source),
                 },
                 ..addon
             };
 
             Index::write_addons(vec![addon]).await?;
+            Lockfile::update(false).await?;
         } else {
             println!("{}", style(format!("{} is already pinned", addon.name)).color256(166))
         }
@@ -57,7 +60,15 @@ async fn check_version(addon: &Addon, version: &str) -> Result<()> {
         },
         AddonSource::Github(source) => {
             let repo_split: Vec<&str> = source.repo.split('/').collect();
-            GITHUB.get_release_by_tag(repo_split[0], repo_split[1], version).await.is_ok()
+            // the release also needs an asset the addon can use
+            GITHUB.get_release_by_tag(repo_split[0], repo_split[1], version).await
+                .is_ok_and(|release| find_asset(&release, &source.asset).is_some())
+        },
+        AddonSource::Local(_) => {
+            return Err(Error::Other(format!("{} is a local file and does not have versions", addon.name)));
+        },
+        AddonSource::Url(_) => {
+            return Err(Error::Other(format!("{} is a direct download and does not have versions", addon.name)));
         },
     };
 
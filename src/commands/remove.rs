@@ -1,6 +1,6 @@
 use console::style;
 
-use crate::{cli::RemoveArgs, error::Result, structs::index::Index};
+use crate::{cli::RemoveArgs, error::Result, structs::{index::{Addon, AddonOptions, Index}, lock::Lockfile}, util::prompt};
 
 pub async fn remove(args: RemoveArgs) -> Result<()> {
     let index = Index::read().await?;
@@ -16,6 +16,61 @@ pub async fn remove(args: RemoveArgs) -> Result<()> {
         }
     }
 
+    let orphans = find_orphans(&index, &to_remove);
+    if !orphans.is_empty() {
+        println!("{}", style(format!("No longer needed: {}", orphans.iter().map(|a| a.name.as_str()).collect::<Vec<&str>>().join(", "))).dim());
+
+        if args.dependencies || prompt::confirm(&format!("Remove {} unused {}?", orphans.len(), if orphans.len() == 1 { "dependency" } else { "dependencies" }), Some(true)) {
+            for orphan in orphans {
+                println!("Removing {}", orphan.name);
+                to_remove.push(orphan);
+            }
+        }
+    }
+
     Index::remove_addons(to_remove.as_slice()).await?;
+
+    // the removed addons dont depend on anything anymore
+    let removed_ids: Vec<String> = to_remove.iter().map(|a| a.generic_id()).collect();
+    let updated: Vec<Addon> = index.addons.iter()
+        .filter(|a| !removed_ids.contains(&a.generic_id()))
+        .filter_map(|a| {
+            let options = a.options.clone()?;
+            if !options.dependency_of.iter().any(|id| removed_ids.contains(id)) {
+                return None;
+            }
+
+            let dependency_of = options.dependency_of.iter().filter(|id| !removed_ids.contains(id)).cloned().collect();
+            Some(Addon { options: Some(AddonOptions { dependency_of, ..options }), ..a.clone() })
+        })
+        .collect();
+    Index::write_addons(updated).await?;
+
+    Lockfile::update(false).await?;
     Ok(())
+}
+
+// addons that were only added as a dependency of removed addons,
+// explicitly added addons are never included
+fn find_orphans<'a>(index: &'a Index, removed: &[&Addon]) -> Vec<&'a Addon> {
+    let mut removed_ids: Vec<String> = removed.iter().map(|a| a.generic_id()).collect();
+    let mut orphans = Vec::new();
+
+    // removing an orphan can make its own dependencies orphans
+    loop {
+        let is_needed = |id: &String| !removed_ids.contains(id) && index.addons.iter().any(|a| a.generic_id() == *id);
+        let new_orphans: Vec<&Addon> = index.addons.iter()
+            .filter(|a| !removed_ids.contains(&a.generic_id()))
+            .filter(|a| a.options.as_ref().is_some_and(|o| o.added_as_dependency && !o.dependency_of.iter().any(is_needed)))
+            .collect();
+
+        if new_orphans.is_empty() {
+            break;
+        }
+
+        removed_ids.extend(new_orphans.iter().map(|a| a.generic_id()));
+        orphans.extend(new_orphans);
+    }
+
+    orphans
 }
\ No newline at end of file
@@ -0,0 +1,213 @@
+use std::{cmp::Reverse, fmt::Write};
+
+use chrono::{DateTime, Utc};
+use clap::ValueEnum;
+use console::style;
+use dialoguer::MultiSelect;
+use serde::Serialize;
+use tokio::try_join;
+
+use crate::{
+    api::{curseforge::mod_loader_from_type, SearchSort},
+    cli::{AddCurseforgeArgs, AddModrinthArgs, SearchArgs, SearchSource},
+    commands::add::{curseforge::add_curseforge, modrinth::add_modrinth},
+    error::Result,
+    structs::{index::{Index, ProjectType}, pack::{ModLoader, Modpack}},
+    util::{get_version_filters, modrinth::search_facets, output, prompt},
+    CURSEFORGE, MODRINTH
+};
+
+// a search result from either platform
+#[derive(Debug, Serialize)]
+struct Project {
+    source: &'static str,
+    id: String,
+    slug: String,
+    title: String,
+    author: String,
+    downloads: u64,
+    updated: Option<DateTime<Utc>>,
+    loaders: Vec<ModLoader>,
+    in_index: bool
+}
+
+pub async fn search(args: SearchArgs) -> Result<()> {
+    let modpack = Modpack::read()?;
+    let index = Index::read().await?;
+    let query = args.query.join(" ");
+    let project_type = args.project_type.unwrap_or(ProjectType::Mod);
+
+    let (mr_projects, cf_projects) = try_join!(
+        async { match args.source {
+            SearchSource::Mr | SearchSource::All => search_modrinth(&modpack, &query, &project_type, args.sort).await,
+            SearchSource::Cf => Ok(Vec::new()),
+        }},
+        async { match args.source {
+            SearchSource::Cf | SearchSource::All => search_curseforge(&modpack, &query, &project_type, args.sort).await,
+            SearchSource::Mr => Ok(Vec::new()),
+        }}
+    )?;
+
+    let mut projects: Vec<Project> = mr_projects.into_iter().chain(cf_projects).collect();
+    for project in projects.iter_mut() {
+        project.in_index = index.addons.iter().any(|a| a.generic_id() == project.id);
+    }
+
+    // relevance cant be compared across platforms, so those stay grouped by platform
+    match args.sort {
+        SearchSort::Relevance => (),
+        SearchSort::Downloads => projects.sort_by_key(|p| Reverse(p.downloads)),
+        SearchSort::Updated => projects.sort_by_key(|p| Reverse(p.updated)),
+    }
+
+    if args.json || output::json() {
+        return output::print_json(&projects);
+    }
+
+    if projects.is_empty() {
+        println!("{}", style(format!("Searching for {query} returned no results")).color256(166));
+        return Ok(());
+    }
+
+    print!("{}", table(&projects));
+
+    if args.add {
+        add_selected(projects, project_type).await?;
+    }
+
+    Ok(())
+}
+
+async fn search_modrinth(modpack: &Modpack, query: &str, project_type: &ProjectType, sort: SearchSort) -> Result<Vec<Project>> {
+    let hits = MODRINTH.search(query, search_facets(modpack, project_type), sort, &20).await?.hits;
+    Ok(hits.into_iter().map(|hit| Project {
+        source: "modrinth",
+        id: hit.project_id,
+        slug: hit.slug,
+        title: hit.title,
+        author: hit.author,
+        downloads: hit.downloads,
+        updated: Some(hit.date_modified),
+        loaders: hit.categories.iter().filter_map(|c| ModLoader::from_str(c, true).ok()).collect(),
+        in_index: false,
+    }).collect())
+}
+
+async fn search_curseforge(modpack: &Modpack, query: &str, project_type: &ProjectType, sort: SearchSort) -> Result<Vec<Project>> {
+    let (game_versions, loaders) = get_version_filters(modpack);
+    // only mods are made for a loader
+    let loaders = match project_type {
+        ProjectType::Mod => loaders,
+        _ => Vec::new(),
+    };
+
+    let mods = CURSEFORGE.search(query, &game_versions, &loaders, project_type.curseforge_class_id().unwrap(), sort, &20).await?;
+    Ok(mods.into_iter().map(|cf_mod| {
+        let mut loaders = Vec::new();
+        for loader in cf_mod.latest_files_indexes.iter().filter_map(|i| i.mod_loader.and_then(mod_loader_from_type)) {
+            if !loaders.contains(&loader) { loaders.push(loader) }
+        }
+
+        Project {
+            source: "curseforge",
+            id: cf_mod.id.to_string(),
+            slug: cf_mod.slug,
+            title: cf_mod.name,
+            author: cf_mod.authors.into_iter().next().map(|a| a.name).unwrap_or_default(),
+            downloads: cf_mod.download_count as u64,
+            updated: cf_mod.date_modified,
+            loaders,
+            in_index: false,
+        }
+    }).collect())
+}
+
+fn table(projects: &[Project]) -> String {
+    let rows: Vec<[String; 5]> = projects.iter().map(|p| [
+        p.title.clone(),
+        p.author.clone(),
+        short_count(p.downloads),
+        p.updated.map(|u| u.format("%Y-%m-%d").to_string()).unwrap_or_default(),
+        p.loaders.iter().map(|l| l.to_string().to_lowercase()).collect::<Vec<String>>().join(", "),
+    ]).collect();
+
+    let header = ["TITLE", "AUTHOR", "DOWNLOADS", "UPDATED", "LOADERS"];
+    let widths: Vec<usize> = (0..header.len())
+        .map(|i| rows.iter().map(|r| r[i].chars().count()).chain([header[i].len()]).max().unwrap())
+        .collect();
+
+    let mut out = String::new();
+    writeln!(&mut out, "{}", style(format!("   {:w0$}  {:w1$}  {:>w2$}  {:w3$}  {}",
+        header[0], header[1], header[2], header[3], header[4],
+        w0 = widths[0], w1 = widths[1], w2 = widths[2], w3 = widths[3]
+    )).dim()).unwrap();
+
+    for (project, row) in projects.iter().zip(rows) {
+        let prefix = match project.source {
+            "modrinth" => style("MR").green().dim(),
+            _ => style("CF").color256(166).dim(),
+        };
+
+        // projects that are already in the modpack are marked with a *
+        let title = format!("{:w$}", row[0], w = widths[0]);
+        writeln!(&mut out, "{prefix} {title}  {:w1$}  {:>w2$}  {:w3$}  {}{}",
+            row[1], row[2], row[3], row[4],
+            if project.in_index { style(" *").green() } else { style("") },
+            title = if project.in_index { style(title).bold().green() } else { style(title).bold() },
+            w1 = widths[1], w2 = widths[2], w3 = widths[3]
+        ).unwrap();
+    }
+
+    if projects.iter().any(|p| p.in_index) {
+        writeln!(&mut out, "{}", style("* already in the modpack").dim()).unwrap();
+    }
+
+    out
+}
+
+// 1234567 -> 1.2M
+fn short_count(count: u64) -> String {
+    match count {
+        1_000_000.. => format!("{:.1}M", count as f64 / 1_000_000.0),
+        1_000.. => format!("{:.1}k", count as f64 / 1_000.0),
+        _ => count.to_string(),
+    }
+}
+
+async fn add_selected(projects: Vec<Project>, project_type: ProjectType) -> Result<()> {
+    prompt::require_input("Choosing projects to add", "use `emm add` with the ids of the projects")?;
+    let items: Vec<String> = projects.iter().map(|p| format!("{} ({})", p.title, p.source)).collect();
+    let selected = MultiSelect::new()
+        .with_prompt("Select projects to add")
+        .items(&items)
+        .report(false)
+        .interact()
+        .unwrap();
+
+    let (mr_ids, cf_ids): (Vec<&Project>, Vec<&Project>) = selected.iter()
+        .map(|i| &projects[*i])
+        .filter(|p| !p.in_index)
+        .partition(|p| p.source == "modrinth");
+
+    if !mr_ids.is_empty() {
+        add_modrinth(AddModrinthArgs {
+            ids: mr_ids.iter().map(|p| p.id.clone()).collect(),
+            version: None,
+            with_optional: false,
+            prompt_optional: false,
+            project_type: Some(project_type.clone()),
+        }).await?;
+    }
+
+    if !cf_ids.is_empty() {
+        add_curseforge(AddCurseforgeArgs {
+            ids: cf_ids.iter().map(|p| p.id.clone()).collect(),
+            version: None,
+            with_optional: false,
+            prompt_optional: false,
+            project_type: Some(project_type),
+        }).await?;
+    }
+
+    Ok(())
+}
\ No newline at end of file
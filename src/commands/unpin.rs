@@ -3,7 +3,7 @@ use console::style;
 use crate::{cli::UnpinArgs, error::Result, structs::index::{Addon, AddonOptions, Index}};
 
 pub async fn unpin(args: UnpinArgs) -> Result<()> {
-    let index = Index::read().await?;
+    let index = Index::read_migrated().await?;
 
     if let Some(addon) = index.select_addon(&args.addon).cloned() {
         if addon.options.clone().unwrap_or_default().pinned {
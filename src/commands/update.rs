@@ -1,18 +1,41 @@
-use std::{collections::HashMap, fmt::Write, sync::Arc};
+use std::{collections::HashMap, fmt::Write, fs, sync::Arc};
 
 use console::style;
+use dialoguer::MultiSelect;
+use lazy_regex::regex_replace_all;
+use serde::Serialize;
+use serde_json::json;
 use tokio::{task::JoinSet, try_join};
 
 use crate::{
     api::{curseforge::File, github::GithubRelease, modrinth::Version}, cli::UpdateArgs, error::Result, structs::{
-        index::{Addon, AddonSource, CurseforgeSource, GithubSource, Index, ModrinthSource, ProjectType},
+        index::{Addon, AddonSource, CurseforgeSource, GithubSource, Index, LocalSource, ModrinthSource, ProjectType, ReleaseChannel},
+        lock::Lockfile,
         pack::Modpack,
-    }, util::{get_version_filters, modrinth::get_primary_hash, to_hyperlink, FilterVersions}, CURSEFORGE, GITHUB, MODRINTH
+    }, util::{files::hash_bytes, get_version_filters, github::find_asset, jar::inspect, min_release_channel, modrinth::get_primary_hash, output, prompt, to_hyperlink, FilterVersions}, CURSEFORGE, GITHUB, MODRINTH
 };
 
+// an update of an addon, the addon already points at the new version
+#[derive(Serialize)]
+pub struct Update {
+    pub name: String,
+    pub id: String,
+    pub from: String,
+    pub to: String,
+    // the version names people know, from and to are what's written to the index
+    pub current: String,
+    pub version: String,
+    pub url: Option<String>,
+    pub changelog: Option<String>,
+    pub pinned: bool,
+    pub applied: bool,
+    #[serde(skip)]
+    pub addon: Addon
+}
+
 pub async fn update(args: UpdateArgs) -> Result<()> {
     let modpack = Arc::new(Modpack::read()?);
-    let mut index = Index::read().await?;
+    let mut index = Index::read_migrated().await?;
 
     // only use the addons in args if there are any
     if let Some(addons) = &args.addons {
@@ -25,89 +48,279 @@ pub async fn update(args: UpdateArgs) -> Result<()> {
         index.addons.retain(|a| !a.options.as_ref().is_some_and(|a| a.pinned));
     }
 
+    // changed local files are only accepted when they are asked for by name
+    let mut to_update = find_updates(&modpack, index.addons, args.addons.is_some()).await?;
+    to_update.iter_mut().for_each(|u| u.applied = !args.dry_run);
+
+    if to_update.is_empty() {
+        if !output::json() {
+            println!("No new updates found!");
+        }
+    } else {
+        if args.changelog {
+            get_curseforge_changelogs(&mut to_update).await?;
+        }
+
+        if !output::json() {
+            let title = match (args.dry_run, args.interactive) {
+                (true, _) => "Would update:",
+                (_, true) => "Available updates:",
+                _ => "Updating:",
+            };
+            println!(
+                "{title}{}",
+                to_update.iter().fold(String::new(), |mut out, u| {
+                    let version = match &u.url {
+                        Some(url) => to_hyperlink(url, &u.version),
+                        None => u.version.clone(),
+                    };
+                    write!(out, "\n{} {}", style(&u.name).bold(), style(version).dim()).unwrap();
+                    if let Some(changelog) = u.changelog.as_ref().filter(|_| args.changelog) {
+                        changelog.trim().lines().for_each(|line| write!(out, "\n    {line}").unwrap());
+                    }
+                    out
+                })
+            );
+        }
+
+        // without input every update is applied
+        if !args.dry_run && args.interactive && !prompt::no_input() {
+            let names: Vec<&str> = to_update.iter().map(|u| u.name.as_str()).collect();
+            let selected = MultiSelect::new()
+                .with_prompt("Select updates to apply")
+                .items(&names)
+                .defaults(&vec![true; names.len()])
+                .report(false)
+                .interact()
+                .unwrap();
+
+            for (i, update) in to_update.iter_mut().enumerate() {
+                update.applied = selected.contains(&i);
+            }
+
+            let count = to_update.iter().filter(|u| u.applied).count();
+            output::note(format!("Updating {} {}", count, if count == 1 { "addon" } else { "addons" }));
+        }
+
+        if !args.dry_run {
+            Index::write_addons(to_update.iter().filter(|u| u.applied).map(|u| u.addon.clone()).collect()).await?;
+        }
+    }
+
+    if !args.dry_run {
+        Lockfile::update(true).await?;
+    }
+
+    if output::json() {
+        output::print_json(&json!({ "dry_run": args.dry_run, "updates": to_update }))?;
+    }
+
+    Ok(())
+}
+
+// finds the newest compatible version of each addon, addons that are up to date are left out
+// repin_local re-pins local files that changed to their new hashes instead of warning about them
+pub async fn find_updates(modpack: &Arc<Modpack>, addons: Vec<Addon>, repin_local: bool) -> Result<Vec<Update>> {
     let mut mr_addon_versions = Vec::new();
     let mut cf_addon_sources = Vec::new();
     let mut gh_addon_sources = Vec::new();
 
-    index.addons.iter().for_each(|a| match &a.source {
-        AddonSource::Modrinth(source) => mr_addon_versions.push(source.version.as_str()),
-        AddonSource::Curseforge(source) => cf_addon_sources.push((source.id, a.project_type.clone())),
-        AddonSource::Github(source) => gh_addon_sources.push(source.repo.clone())
+    addons.iter().for_each(|a| {
+        let channel = min_release_channel(modpack, a.options.as_ref()).cloned();
+        match &a.source {
+            AddonSource::Modrinth(source) => mr_addon_versions.push((source.version.as_str(), a.project_type.clone(), channel)),
+            AddonSource::Curseforge(source) => cf_addon_sources.push((source.id, source.version, a.project_type.clone(), channel)),
+            AddonSource::Github(source) => gh_addon_sources.push((source.clone(), channel)),
+            AddonSource::Local(_) | AddonSource::Url(_) => (),
+        }
     });
 
     let (
-        latest_mr_versions,
-        (latest_cf_versions, cf_links),
+        (latest_mr_versions, current_mr_versions),
+        (latest_cf_versions, current_cf_versions, cf_links),
         latest_gh_versions
     ) = try_join!(
-        update_modrinth(&modpack, mr_addon_versions),
-        update_curseforge(&modpack, cf_addon_sources),
-        update_github(gh_addon_sources)
+        update_modrinth(modpack, mr_addon_versions),
+        update_curseforge(modpack, cf_addon_sources),
+        update_github(modpack, gh_addon_sources)
     )?;
 
-    // Mods with updated version ids
-    // (Addon, new_version_url/version_name)
-    let to_update: Vec<(Addon, String)> = index.addons.into_iter().filter_map(|addon| {
-        match &addon.source {
+    // addons with updated version ids
+    let updates = addons.into_iter().filter_map(|addon| {
+        let from = addon.generic_version();
+        let (addon, current, version, url, changelog) = match &addon.source {
             AddonSource::Modrinth(source) => {
-                let latest_version = latest_mr_versions.values().find(|v| v.project_id == source.id).unwrap();
-                if latest_version.id != source.version {
-                    return Some((
-                        Addon { source: AddonSource::Modrinth(ModrinthSource { id: source.id.clone(), version: latest_version.id.clone() }), ..addon },
-                        to_hyperlink(&format!("https://modrinth.com/project/{}/version/{}", source.id, latest_version.id), &latest_version.version_number)
-                    ));
-                }
+                let latest_version = match latest_mr_versions.iter().find(|v| v.project_id == source.id) {
+                    Some(version) => version,
+                    // modrinth leaves out versions it has no compatible update for
+                    None if current_mr_versions.iter().any(|v| v.id == source.version) => {
+                        output::note(style(format!("Skipping {}, there is no compatible version of it for this modpack", addon.name)).color256(166));
+                        return None;
+                    },
+                    None => {
+                        output::note(style(format!("Skipping {}, modrinth did not return its version {}", addon.name, source.version)).color256(166));
+                        return None;
+                    }
+                };
+                if latest_version.id == source.version { return None; }
+
+                (
+                    Addon { source: AddonSource::Modrinth(ModrinthSource { id: source.id.clone(), version: latest_version.id.clone() }), ..addon.clone() },
+                    current_mr_versions.iter().find(|v| v.id == source.version).map_or(source.version.clone(), |v| v.version_number.clone()),
+                    latest_version.version_number.clone(),
+                    Some(format!("https://modrinth.com/project/{}/version/{}", source.id, latest_version.id)),
+                    latest_version.changelog.clone()
+                )
             },
             AddonSource::Curseforge(source) => {
-                let latest_version = latest_cf_versions.iter().find(|v| v.mod_id == source.id).unwrap();
-                if latest_version.id != source.version {
-                    return Some((
-                        Addon { source: AddonSource::Curseforge(CurseforgeSource { id: source.id, version: latest_version.id }), ..addon },
-                        to_hyperlink(
-                            &format!("{}/files/{}", cf_links.iter().find(|l| l.0 == source.id).unwrap().1, latest_version.id),
-                            &latest_version.file_name
-                        )
-                    ));
-                }
+                let latest_version = latest_cf_versions.iter().find(|v| v.mod_id == source.id)?;
+                if latest_version.id == source.version { return None; }
+
+                (
+                    Addon { source: AddonSource::Curseforge(CurseforgeSource { id: source.id, version: latest_version.id }), ..addon.clone() },
+                    current_cf_versions.iter().find(|f| f.id == source.version).map_or(source.version.to_string(), |f| f.file_name.clone()),
+                    latest_version.file_name.clone(),
+                    cf_links.iter().find(|l| l.0 == source.id).map(|l| format!("{}/files/{}", l.1, latest_version.id)),
+                    // fetched separately, only when changelogs are shown
+                    None
+                )
             },
             AddonSource::Github(source) => {
-                let latest_version = &latest_gh_versions.iter().find(|r| r.0 == source.repo).unwrap().1;
-                if latest_version.tag_name != source.tag {
-                    return Some((
-                        Addon { source: AddonSource::Github(GithubSource { tag: latest_version.tag_name.clone(), ..source.clone() }), ..addon},
-                        to_hyperlink(&format!("https://github.com/{}/releases/tag/{}", source.repo, latest_version.tag_name), &latest_version.tag_name)
-                    ));
+                let latest_version = &latest_gh_versions.iter().find(|r| r.0 == *source)?.1;
+                if latest_version.tag_name == source.tag { return None; }
+
+                (
+                    Addon { source: AddonSource::Github(GithubSource { tag: latest_version.tag_name.clone(), ..source.clone() }), ..addon.clone() },
+                    source.tag.clone(),
+                    latest_version.tag_name.clone(),
+                    Some(format!("https://github.com/{}/releases/tag/{}", source.repo, latest_version.tag_name)),
+                    latest_version.body.clone()
+                )
+            },
+            // local files cant be updated by emm, a changed file could also be a corrupted one
+            AddonSource::Local(source) => {
+                let (sha1, sha512) = hash_bytes(&fs::read(&source.path).ok()?);
+                if sha1 == source.sha1 { return None; }
+                if !repin_local {
+                    output::note(style(format!("{} does not match the hash in the index anymore, use `emm update {}` to accept the changed file", source.path.to_string_lossy(), addon.name)).color256(166));
+                    return None;
                 }
+
+                (
+                    Addon { source: AddonSource::Local(LocalSource { sha1, sha512, ..source.clone() }), ..addon.clone() },
+                    source.path.file_name().unwrap_or_default().to_string_lossy().to_string(),
+                    "file changed".into(),
+                    None,
+                    None
+                )
             },
-        }
+            // direct downloads are pinned to their hashes, there is nothing to update to
+            AddonSource::Url(_) => return None,
+        };
 
-        None
+        Some(Update {
+            name: addon.name.clone(),
+            id: addon.generic_id(),
+            from,
+            to: addon.generic_version(),
+            current,
+            version,
+            url,
+            changelog,
+            pinned: addon.options.as_ref().is_some_and(|o| o.pinned),
+            applied: false,
+            addon,
+        })
     }).collect();
 
-    if to_update.is_empty() {
-        println!("No new updates found!");
-    } else {
-        println!(
-            "Updating:{}",
-            to_update.iter().fold(String::new(), |mut out, a| {
-                write!(out, "\n{} {}", style(&a.0.name).bold(), style(&a.1).dim()).unwrap();
-                out
-            })
-        );
-        
-        Index::write_addons(to_update.into_iter().map(|a| a.0).collect()).await?;
+    Ok(updates)
+}
+
+async fn get_curseforge_changelogs(to_update: &mut [Update]) -> Result<()> {
+    let mut tasks: JoinSet<Result<(i32, String)>> = JoinSet::new();
+    for update in to_update.iter() {
+        if let AddonSource::Curseforge(source) = &update.addon.source {
+            let (mod_id, file_id) = (source.id, source.version);
+            tasks.spawn(async move { Ok((file_id, CURSEFORGE.get_file_changelog(&mod_id, &file_id).await?)) });
+        }
+    }
+
+    while let Some(res) = tasks.join_next().await {
+        let (file_id, changelog) = res??;
+        let update = to_update.iter_mut().find(|u| matches!(&u.addon.source, AddonSource::Curseforge(s) if s.version == file_id));
+        if let Some(update) = update {
+            update.changelog = Some(html_to_text(&changelog));
+        }
     }
 
     Ok(())
 }
 
-async fn update_modrinth(modpack: &Modpack, mr_addon_versions: Vec<&str>) -> Result<HashMap<String, Version>> {
+// curseforge changelogs are html, good enough for reading them in a terminal
+fn html_to_text(html: &str) -> String {
+    let text = regex_replace_all!(r"(?i)<br\s*/?>|</p>|</li>|</h\d>", html, "\n");
+    let text = regex_replace_all!(r"<[^>]*>", &text, "");
+    text.replace("&nbsp;", " ")
+        .replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&#39;", "'")
+        .replace("&amp;", "&")
+        .lines()
+        .map(str::trim)
+        .filter(|l| !l.is_empty())
+        .collect::<Vec<&str>>()
+        .join("\n")
+}
+
+// (latest versions, current versions)
+async fn update_modrinth(modpack: &Arc<Modpack>, mr_addon_versions: Vec<(&str, ProjectType, Option<ReleaseChannel>)>) -> Result<(Vec<Version>, Vec<Version>)> {
     if mr_addon_versions.is_empty() { return Ok(Default::default()); }
-    let mr_version_hashes: Vec<String> = MODRINTH
-        .get_versions(mr_addon_versions.as_slice())
-        .await?
+    let ids: Vec<&str> = mr_addon_versions.iter().map(|a| a.0).collect();
+    let current_versions = MODRINTH.get_versions(&ids).await?;
+    let (restricted, unrestricted): (Vec<_>, Vec<_>) = mr_addon_versions.into_iter().partition(|a| a.2.is_some());
+
+    let mut latest_versions = Vec::new();
+    if !unrestricted.is_empty() {
+        let versions: Vec<&Version> = current_versions.iter().filter(|v| unrestricted.iter().any(|a| a.0 == v.id)).collect();
+        latest_versions.extend(update_modrinth_by_hashes(modpack, versions).await?.into_values());
+    }
+
+    // modrinth cant filter the latest versions by release channel, so the versions are filtered here
+    if !restricted.is_empty() {
+        let mut tasks: JoinSet<Result<Version>> = JoinSet::new();
+        for (id, project_type, channel) in restricted {
+            let modpack = modpack.clone();
+            // left out versions are reported when the updates are collected
+            let current = match current_versions.iter().find(|v| v.id == id) {
+                Some(version) => version.clone(),
+                None => continue,
+            };
+
+            let task = async move {
+                let versions = MODRINTH.get_project_versions(&current.project_id).await?;
+                let latest = versions
+                    .filter_compatible(&modpack, &project_type)
+                    .filter_release_channel(channel.as_ref())
+                    .best_match(&modpack);
+
+                // stay on the current version if there is nothing to update to
+                Ok(latest.unwrap_or(current))
+            };
+
+            tasks.spawn(task);
+        }
+
+        while let Some(res) = tasks.join_next().await { latest_versions.push(res??) }
+    }
+
+    Ok((latest_versions, current_versions))
+}
+
+async fn update_modrinth_by_hashes(modpack: &Modpack, current_versions: Vec<&Version>) -> Result<HashMap<String, Version>> {
+    let mr_version_hashes: Vec<String> = current_versions
         .into_iter()
-        .map(|v| get_primary_hash(v.files).expect("couldnt find hash"))
+        .map(|v| get_primary_hash(v.files.clone()).expect("couldnt find hash"))
         .collect();
 
     let (acceptable_versions, acceptable_loaders) = get_version_filters(modpack);
@@ -132,28 +345,33 @@ async fn update_modrinth(modpack: &Modpack, mr_addon_versions: Vec<&str>) -> Res
         .await
 }
 
-async fn update_curseforge(modpack: &Modpack, cf_addon_ids: Vec<(i32, ProjectType)>) -> Result<(Vec<File>, Vec<(i32, String)>)> {
+// (latest files, current files, links)
+async fn update_curseforge(modpack: &Modpack, cf_addon_ids: Vec<(i32, i32, ProjectType, Option<ReleaseChannel>)>) -> Result<(Vec<File>, Vec<File>, Vec<(i32, String)>)> {
     if cf_addon_ids.is_empty() { return Ok(Default::default()); }
-    let mut tasks: JoinSet<Result<Option<File>>> = JoinSet::new();
-    for (id, project_type) in cf_addon_ids.clone() {
+    let mut tasks: JoinSet<Result<(Option<File>, Option<File>)>> = JoinSet::new();
+    for (id, file_id, project_type, channel) in cf_addon_ids.clone() {
         let modpack = modpack.clone();
 
         let task = async move {
             let files = CURSEFORGE.get_mod_files(&id).await?;
+            let current = files.iter().find(|f| f.id == file_id).cloned();
 
-            let compatibles = files.filter_compatible(&modpack, &project_type);
-            Ok(compatibles.best_match(&modpack))
+            let compatibles = files
+                .filter_compatible(&modpack, &project_type)
+                .filter_release_channel(channel.as_ref());
+            Ok((compatibles.best_match(&modpack), current))
         };
 
         tasks.spawn(task);
     }
 
     let mut latest_cf_versions = Vec::new();
+    let mut current_cf_versions = Vec::new();
     while let Some(res) = tasks.join_next().await {
+        let (latest, current) = res??;
         // only push to latest versions if there are compatible versions
-        if let Some(file) = res?? {
-            latest_cf_versions.push(file);
-        }
+        latest_cf_versions.extend(latest);
+        current_cf_versions.extend(current);
     }
 
     // (mod_id, website_url) for displaying links later
@@ -164,31 +382,57 @@ async fn update_curseforge(modpack: &Modpack, cf_addon_ids: Vec<(i32, ProjectTyp
         .map(|a| (a.id, a.links.website_url))
         .collect();
 
-    Ok((latest_cf_versions, cf_links))
+    Ok((latest_cf_versions, current_cf_versions, cf_links))
 }
 
-async fn update_github(gh_addon_sources: Vec<String>) -> Result<Vec<(String, GithubRelease)>> {
+// github releases dont say what they are for, so only releases with a matching asset
+// that doesnt say it is incompatible with the modpack are used
+async fn update_github(modpack: &Arc<Modpack>, gh_addon_sources: Vec<(GithubSource, Option<ReleaseChannel>)>) -> Result<Vec<(GithubSource, GithubRelease)>> {
     if gh_addon_sources.is_empty() { return Ok(Default::default()); }
-    // pair of repo & latest compatible release
-    let mut tasks: JoinSet<Result<(String, Option<GithubRelease>)>> = JoinSet::new();
-    for repo in gh_addon_sources {
+    // pair of source & latest compatible release
+    let mut tasks: JoinSet<Result<(GithubSource, Option<GithubRelease>)>> = JoinSet::new();
+    for (source, channel) in gh_addon_sources {
+        let modpack = modpack.clone();
         let task = async move {
-            let repo_split: Vec<&str> = repo.split('/').collect();
-            let releases = GITHUB.list_releases(repo_split[0], repo_split[1]).await?;
-    
-            Ok((repo.to_owned(), releases.first().map(|r| r.to_owned())))
+            let repo_split: Vec<&str> = source.repo.split('/').collect();
+            let mut pages = GITHUB.release_pages(repo_split[0], repo_split[1]);
+            let (game_versions, loaders) = get_version_filters(&modpack);
+
+            let mut latest = None;
+            // later pages are only fetched while nothing was found
+            'pages: while let Some(releases) = pages.next().await? {
+                for release in releases.into_iter().filter(|r| r.release_channel().allowed_by(channel.as_ref())) {
+                    // releases are newest first, anything after the current one would be a downgrade
+                    if release.tag_name == source.tag {
+                        latest = Some(release);
+                        break 'pages;
+                    }
+
+                    let asset = match find_asset(&release, &source.asset) {
+                        Some(asset) => asset,
+                        None => continue,
+                    };
+
+                    if inspect(&asset.browser_download_url, None).await?.and_then(|m| m.is_compatible(&game_versions, &loaders)) != Some(false) {
+                        latest = Some(release);
+                        break 'pages;
+                    }
+                }
+            }
+
+            Ok((source, latest))
         };
 
         tasks.spawn(task);
     }
 
-    let mut latest_gh_versions: Vec<(String, GithubRelease)> = Vec::new();
+    let mut latest_gh_versions: Vec<(GithubSource, GithubRelease)> = Vec::new();
     while let Some(res) = tasks.join_next().await {
         let res = res??;
 
         // res.1 (GithubRelease) will be None if no compatible versions are available
-        if res.1.is_some() {
-            latest_gh_versions.push((res.0, res.1.unwrap()));
+        if let Some(release) = res.1 {
+            latest_gh_versions.push((res.0, release));
         }
     }
 
@@ -0,0 +1,31 @@
+use console::style;
+
+use crate::{cli::WhyArgs, error::Result, structs::index::Index};
+
+pub async fn why(args: WhyArgs) -> Result<()> {
+    let index = Index::read().await?;
+
+    if let Some(addon) = index.select_addon(&args.addon) {
+        let options = addon.options.clone().unwrap_or_default();
+        let added_as = match (options.added_as_dependency, options.optional) {
+            (true, true) => "as an optional dependency",
+            (true, false) => "as a dependency",
+            (false, _) => "explicitly",
+        };
+        println!("{} was added {added_as}", style(&addon.name).bold());
+
+        // dependents that were removed since are left out
+        let dependents: Vec<&str> = options.dependency_of.iter()
+            .filter_map(|id| index.addons.iter().find(|a| a.generic_id() == *id))
+            .map(|a| a.name.as_str())
+            .collect();
+
+        if dependents.is_empty() {
+            println!("{}", style("Nothing in the modpack depends on it").dim());
+        } else {
+            println!("Required by:\n{}", dependents.join("\n"));
+        }
+    }
+
+    Ok(())
+}
\ No newline at end of file
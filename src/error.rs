@@ -25,6 +25,12 @@ pub enum Error {
     #[error("Unable to import: {0}")]
     BadImport(String),
 
+    #[error("emm.lock is out of date: {0}, run without --locked to update it")]
+    StaleLockfile(String),
+
+    #[error("Hash mismatch for {0}, expected {1} but got {2}")]
+    HashMismatch(String, String, String),
+
     #[error("Deprecated api usage: {0}")]
     Deprecated(String),
 
@@ -1,8 +1,9 @@
-use std::env;
+use std::{env, sync::atomic::Ordering};
 use api::{curseforge::CurseAPI, github::GithubApi, modrinth::ModrinthAPI};
 use clap::{CommandFactory, Parser};
 use cli::{Args, Commands};
 use lazy_static::lazy_static;
+use structs::config::Config;
 
 mod api;
 mod cli;
@@ -12,28 +13,41 @@ mod structs;
 mod util;
 
 lazy_static! {
-    pub static ref GITHUB: GithubApi = GithubApi::default();
-    pub static ref MODRINTH: ModrinthAPI = ModrinthAPI::new(&format!("evergales/emm/{} (discord: evergales)", env!("CARGO_PKG_VERSION")));
+    pub static ref CONFIG: Config = Config::read();
+    pub static ref GITHUB: GithubApi = GithubApi::new(&CONFIG.urls.github, CONFIG.github_token.as_deref());
+    pub static ref MODRINTH: ModrinthAPI = ModrinthAPI::new(&format!("evergales/emm/{} (discord: evergales)", env!("CARGO_PKG_VERSION")), &CONFIG.urls.modrinth);
     pub static ref CURSEFORGE: CurseAPI = {
         let key = env::var("CURSEFORGE_API_KEY").unwrap_or("$2a$10$Grlqtes/CrLoTgnvg174H.BKRX8caplGh0o1dOwxhhMWAgv.2J9cC".into());
-        CurseAPI::new(&key)
+        CurseAPI::new(&key, &CONFIG.urls.curseforge)
     };
 }
 
 #[tokio::main]
 async fn main() {
     // hi there!
-    if let Err(err) = match Args::parse().subcommand {
+    let args = Args::parse();
+    api::request::REFRESH.store(args.refresh, Ordering::Relaxed);
+    util::prompt::set_no_input(args.yes);
+    util::output::set_format(args.output);
+
+    if let Err(err) = match args.subcommand {
         Commands::Init(args) => commands::init::init(args).await,
         Commands::Add(args) => commands::add::add(args).await,
+        Commands::Search(args) => commands::search::search(args).await,
         Commands::Remove(args) => commands::remove::remove(args).await,
         Commands::Update(args) => commands::update::update(args).await,
         Commands::Import(args) => commands::import::run(args).await,
         Commands::Export(args) => commands::export::run(args).await,
+        Commands::Outdated(args) => commands::outdated::outdated(args).await,
+        Commands::Diff(args) => commands::diff::diff(args).await,
         Commands::Pin(args) => commands::pin::pin(args).await,
         Commands::Unpin(args) => commands::unpin::unpin(args).await,
         Commands::List(args) => commands::list::list(args).await,
         Commands::Migrate(args) => commands::migrate::migrate(args).await,
+        Commands::Install(args) => commands::install::install(args).await,
+        Commands::Cache(args) => commands::cache::cache(args).await,
+        Commands::Doctor => commands::doctor::doctor().await,
+        Commands::Why(args) => commands::why::why(args).await,
         Commands::Completion { shell } => {
             clap_complete::generate(
                 shell,
@@ -45,6 +59,7 @@ async fn main() {
             Ok(())
         }
     } {
-        eprintln!("{err}")
+        eprintln!("{err}");
+        std::process::exit(1);
     }
 }
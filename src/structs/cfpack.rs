@@ -0,0 +1,44 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct Manifest {
+    pub minecraft: ManifestMinecraft,
+    pub manifest_type: String,
+    pub manifest_version: i32,
+    pub name: String,
+    #[serde(default)]
+    pub version: String,
+    #[serde(default)]
+    pub author: String,
+    pub files: Vec<ManifestFile>,
+    #[serde(default = "default_overrides")]
+    pub overrides: String
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ManifestMinecraft {
+    pub version: String,
+    pub mod_loaders: Vec<ManifestModLoader>
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ManifestModLoader {
+    // loader-version, for example "forge-47.2.0"
+    pub id: String,
+    pub primary: bool
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ManifestFile {
+    #[serde(rename = "projectID")]
+    pub project_id: i32,
+    #[serde(rename = "fileID")]
+    pub file_id: i32,
+    pub required: bool
+}
+
+fn default_overrides() -> String {
+    "overrides".into()
+}
\ No newline at end of file
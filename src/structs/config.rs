@@ -0,0 +1,45 @@
+use std::path::PathBuf;
+
+use serde::{Deserialize, Serialize};
+
+use crate::{api, util::versions};
+
+// global emm config, see util/config.rs for where it's read from
+#[derive(Debug, Default, Deserialize, Serialize, Clone)]
+#[serde(default)]
+pub struct Config {
+    pub cache_dir: Option<PathBuf>,
+    // raises github's ratelimit from 60 to 5000 requests an hour
+    pub github_token: Option<String>,
+    // seconds to reuse api responses for before asking again, unset turns the response cache off
+    pub response_cache_ttl: Option<u64>,
+    pub urls: ApiUrls
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone)]
+#[serde(default)]
+pub struct ApiUrls {
+    pub modrinth: String,
+    pub curseforge: String,
+    pub github: String,
+    pub fabric_meta: String,
+    pub quilt_meta: String,
+    pub forge_meta: String,
+    pub neoforge_maven: String,
+    pub minecraft_meta: String
+}
+
+impl Default for ApiUrls {
+    fn default() -> Self {
+        Self {
+            modrinth: api::modrinth::API_URL.into(),
+            curseforge: api::curseforge::API_URL.into(),
+            github: api::github::API_URL.into(),
+            fabric_meta: versions::fabric::FABRIC_META_URL.into(),
+            quilt_meta: versions::quilt::QUILT_META_URL.into(),
+            forge_meta: versions::forge::FORGE_METADATA_URL.into(),
+            neoforge_maven: versions::neoforge::NEOFORGE_MAVEN_URL.into(),
+            minecraft_meta: versions::minecraft::PISTON_META_URL.into(),
+        }
+    }
+}
\ No newline at end of file
@@ -1,9 +1,10 @@
+use std::path::PathBuf;
+
+use clap::ValueEnum;
 use serde::{Deserialize, Serialize};
 
 use crate::error::Error;
 
-use super::pack::ModLoader;
-
 #[derive(Debug, Deserialize, Serialize)]
 pub struct Index {
     pub addons: Vec<Addon>
@@ -28,6 +29,8 @@ pub enum AddonSource {
     Modrinth(ModrinthSource),
     Curseforge(CurseforgeSource),
     Github(GithubSource),
+    Local(LocalSource),
+    Url(UrlSource),
 }
 
 #[derive(Debug, Default, Deserialize, Serialize, Clone, PartialEq)]
@@ -35,6 +38,18 @@ pub enum AddonSource {
 pub struct AddonOptions {
     #[serde(skip_serializing_if = "std::ops::Not::not")]
     pub pinned: bool,
+    // added as an optional dependency of another addon
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub optional: bool,
+    // added because another addon needed it, so it can be removed once nothing needs it anymore
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub added_as_dependency: bool,
+    // ids of the addons that depend on this one
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub dependency_of: Vec<String>,
+    // overrides the modpack's min_release_channel
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub min_release_channel: Option<ReleaseChannel>,
 }
 
 #[derive(Debug, Deserialize, Serialize, Clone, PartialEq)]
@@ -53,29 +68,60 @@ pub struct CurseforgeSource {
 pub struct GithubSource {
     pub repo: String,
     pub tag: String,
-    pub asset_index: usize
+    // glob pattern for the name of the release asset
+    #[serde(default)]
+    pub asset: String,
+    // replaced by asset, only read to migrate older index files
+    #[serde(default, skip_serializing)]
+    pub asset_index: Option<usize>
 }
 
+// a direct download, the hashes pin the file so a changed download is noticed
 #[derive(Debug, Deserialize, Serialize, Clone, PartialEq)]
+pub struct UrlSource {
+    pub url: String,
+    pub sha1: String,
+    pub sha512: String
+}
+
+// a file inside the modpack folder, for addons that arent on any platform
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq)]
+pub struct LocalSource {
+    // relative to the modpack folder
+    pub path: PathBuf,
+    pub sha1: String,
+    pub sha512: String
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq, ValueEnum)]
 #[serde(rename_all = "lowercase")]
+#[clap(rename_all = "lowercase")]
 pub enum ProjectType {
+    #[value(skip)]
     Project,
     Mod,
     Shader,
+    #[value(skip)]
     Plugin,
+    #[value(skip)]
     Modpack,
     Datapack,
     Resourcepack,
+    #[value(skip)]
     Unknown
 }
 
-#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Default)]
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Default, ValueEnum)]
 #[serde(rename_all = "lowercase")]
+#[clap(rename_all = "lowercase")]
 pub enum Side {
     #[default]
     Both,
     Client,
     Server,
+    // the platform didnt say which side the addon is for, handled like both
+    #[value(skip)]
+    Unknown,
 }
 
 impl TryFrom<i32> for ProjectType {
@@ -92,10 +138,32 @@ impl TryFrom<i32> for ProjectType {
     }
 }
 
-#[derive(Debug, Deserialize, Serialize, Clone, PartialEq)]
+impl ProjectType {
+    // the curseforge class id, the reverse of try_from
+    pub fn curseforge_class_id(&self) -> Option<i32> {
+        match self {
+            Self::Mod => Some(6),
+            Self::Shader => Some(6552),
+            Self::Datapack => Some(6945),
+            Self::Resourcepack => Some(12),
+            _ => None,
+        }
+    }
+}
+
+// ordered from most to least stable
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq, PartialOrd)]
+#[serde(rename_all = "lowercase")]
 pub enum ReleaseChannel {
     Release,
     #[serde(alias = "prerelease")]
     Beta,
     Alpha
+}
+
+impl ReleaseChannel {
+    // whether a version on this channel is at least as stable as the minimum, no minimum allows everything
+    pub fn allowed_by(&self, min: Option<&ReleaseChannel>) -> bool {
+        min.is_none_or(|min| self <= min)
+    }
 }
\ No newline at end of file
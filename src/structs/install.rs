@@ -0,0 +1,10 @@
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+
+// .emm-install.json, tracks the files emm installed into an instance folder
+#[derive(Debug, Default, Deserialize, Serialize)]
+pub struct InstallManifest {
+    // relative path => sha1
+    pub files: BTreeMap<String, String>
+}
\ No newline at end of file
@@ -0,0 +1,131 @@
+use std::collections::HashMap;
+
+use serde::Deserialize;
+
+use super::{index::Side, pack::ModLoader};
+
+// what emm could read from a mod jar's loader metadata
+#[derive(Debug, Clone)]
+pub struct JarMetadata {
+    pub id: String,
+    pub version: String,
+    pub side: Side,
+    pub loaders: Vec<ModLoader>,
+    // supported minecraft versions, any range can match, empty if the jar doesnt say
+    pub minecraft: Vec<VersionRange>,
+    pub dependencies: Vec<JarDependency>
+}
+
+#[derive(Debug, Clone)]
+pub struct JarDependency {
+    pub id: String,
+    pub versions: Vec<VersionRange>,
+    pub required: bool
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum VersionRange {
+    // fabric & quilt style, ">=1.20 <1.21", "~1.20.1", "1.20.x"
+    Predicate(String),
+    // forge & neoforge style, "[1.20.1,1.21)"
+    Maven(String)
+}
+
+// fabric.mod.json
+#[derive(Debug, Deserialize)]
+pub struct FabricModJson {
+    pub id: String,
+    pub version: String,
+    #[serde(default)]
+    pub environment: Option<String>,
+    #[serde(default)]
+    pub depends: HashMap<String, OneOrMany>,
+    #[serde(default)]
+    pub recommends: HashMap<String, OneOrMany>
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+pub enum OneOrMany {
+    One(String),
+    Many(Vec<String>)
+}
+
+// quilt.mod.json
+#[derive(Debug, Deserialize)]
+pub struct QuiltModJson {
+    pub quilt_loader: QuiltLoader,
+    #[serde(default)]
+    pub minecraft: Option<QuiltMinecraft>
+}
+
+#[derive(Debug, Deserialize)]
+pub struct QuiltLoader {
+    pub id: String,
+    pub version: String,
+    #[serde(default)]
+    pub depends: Vec<QuiltDependency>
+}
+
+#[derive(Debug, Deserialize)]
+pub struct QuiltMinecraft {
+    #[serde(default)]
+    pub environment: Option<String>
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+pub enum QuiltDependency {
+    Id(String),
+    Object {
+        id: String,
+        #[serde(default)]
+        versions: Option<QuiltVersions>,
+        #[serde(default)]
+        optional: bool
+    },
+    // any one of these is enough
+    Any(Vec<QuiltDependency>)
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+pub enum QuiltVersions {
+    One(String),
+    Any { any: Vec<String> },
+    All { all: Vec<String> },
+    Many(Vec<String>)
+}
+
+// META-INF/mods.toml & META-INF/neoforge.mods.toml
+#[derive(Debug, Deserialize)]
+pub struct ModsToml {
+    #[serde(default)]
+    pub mods: Vec<ModsTomlMod>,
+    #[serde(default)]
+    pub dependencies: HashMap<String, Vec<ModsTomlDependency>>
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ModsTomlMod {
+    pub mod_id: String,
+    #[serde(default)]
+    pub version: Option<String>,
+    #[serde(default)]
+    pub display_test: Option<String>
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ModsTomlDependency {
+    pub mod_id: String,
+    // forge
+    #[serde(default)]
+    pub mandatory: Option<bool>,
+    // neoforge
+    #[serde(default, rename = "type")]
+    pub dependency_type: Option<String>,
+    #[serde(default)]
+    pub version_range: Option<String>
+}
\ No newline at end of file
@@ -0,0 +1,34 @@
+use std::path::PathBuf;
+
+use serde::{Deserialize, Serialize};
+
+use super::pack::ModLoader;
+
+// emm.lock, the resolved files of every addon in the index
+// so exports dont depend on what the platforms return at export time
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct Lockfile {
+    pub minecraft: String,
+    pub loader: ModLoader,
+    // always an actual version, even if pack.toml uses "latest"
+    pub loader_version: String,
+    #[serde(default, rename = "addon")]
+    pub addons: Vec<LockedAddon>
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq)]
+pub struct LockedAddon {
+    pub id: String,
+    pub version: String,
+    pub filename: String,
+    // curseforge files that disallow 3rd party distribution dont have a url
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub url: Option<String>,
+    pub sha1: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sha512: Option<String>,
+    pub size: usize,
+    // local addons are read from the modpack folder instead of downloaded
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub path: Option<PathBuf>
+}
\ No newline at end of file
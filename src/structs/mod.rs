@@ -1,4 +1,9 @@
 pub mod index;
 pub mod pack;
 pub mod mrpack;
-pub mod packwiz;
\ No newline at end of file
+pub mod packwiz;
+pub mod cfpack;
+pub mod lock;
+pub mod install;
+pub mod config;
+pub mod jar;
\ No newline at end of file
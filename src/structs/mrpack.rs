@@ -66,7 +66,7 @@ impl From<ModLoader> for PackDependency {
 impl From<Side> for FileEnv {
     fn from(value: Side) -> Self {
         let (client, server) = match value {
-            Side::Both => (SideSupportType::Required, SideSupportType::Required),
+            Side::Both | Side::Unknown => (SideSupportType::Required, SideSupportType::Required),
             Side::Client => (SideSupportType::Required, SideSupportType::Unsupported),
             Side::Server => (SideSupportType::Unsupported, SideSupportType::Required),
         };
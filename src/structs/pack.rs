@@ -3,6 +3,8 @@ use std::path::PathBuf;
 use clap::ValueEnum;
 use serde::{Deserialize, Serialize};
 
+use super::index::ReleaseChannel;
+
 #[derive(Debug, Deserialize, Serialize, Clone)]
 pub struct Modpack {
     pub name: String,
@@ -24,7 +26,8 @@ pub struct PackOptions {
     pub mods_output: Option<PathBuf>,
     pub resourcepacks_output: Option<PathBuf>,
     pub shaders_output: Option<PathBuf>,
-    pub datapacks_output: Option<PathBuf>
+    pub datapacks_output: Option<PathBuf>,
+    pub min_release_channel: Option<ReleaseChannel>
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -0,0 +1,206 @@
+use std::{fs::{self, File}, path::{Path, PathBuf}, sync::Arc, time::{Duration, SystemTime}};
+
+use tokio::{sync::Semaphore, task::JoinSet};
+use walkdir::WalkDir;
+
+use crate::{api::request, error::{Error, Result}, structs::lock::LockedAddon, CONFIG};
+
+use super::files::{hash_bytes, write_atomic};
+
+// a file stored in the download cache
+pub struct CachedFile {
+    pub path: PathBuf,
+    pub sha1: String,
+    // only known when the file was downloaded or looked up by it
+    pub sha512: Option<String>,
+    pub size: usize
+}
+
+// files are stored under their sha1 with sha512 hardlinks next to them
+// so they can be found with whichever hash a platform provides
+pub fn cache_dir() -> Result<PathBuf> {
+    if let Some(dir) = &CONFIG.cache_dir {
+        return Ok(dir.clone());
+    }
+
+    match dirs::cache_dir() {
+        Some(dir) => Ok(dir.join("emm")),
+        None => Err(Error::Other("Could not find a cache directory for this system".into())),
+    }
+}
+
+fn files_dir() -> Result<PathBuf> {
+    Ok(cache_dir()?.join("files"))
+}
+
+fn object_path(algo: &str, hash: &str) -> Result<PathBuf> {
+    Ok(files_dir()?.join(algo).join(hash))
+}
+
+// get a file from the cache or download it,
+// downloads are checked against the expected hashes when there are any
+pub async fn fetch(url: &str, sha1: Option<&str>, sha512: Option<&str>) -> Result<CachedFile> {
+    if let Some(cached) = lookup(sha1, sha512)? {
+        return Ok(cached);
+    }
+
+    let bytes = request::get(url).await?.bytes().await?;
+    let (actual_sha1, actual_sha512) = hash_bytes(&bytes);
+
+    if let Some(sha1) = sha1 {
+        if sha1 != actual_sha1 {
+            return Err(Error::HashMismatch(url.to_owned(), sha1.to_owned(), actual_sha1));
+        }
+    }
+
+    if let Some(sha512) = sha512 {
+        if sha512 != actual_sha512 {
+            return Err(Error::HashMismatch(url.to_owned(), sha512.to_owned(), actual_sha512));
+        }
+    }
+
+    let path = object_path("sha1", &actual_sha1)?;
+    fs::create_dir_all(path.parent().unwrap())?;
+
+    write_atomic(&path, &bytes)?;
+
+    let sha512_path = object_path("sha512", &actual_sha512)?;
+    fs::create_dir_all(sha512_path.parent().unwrap())?;
+    if !sha512_path.is_file() && fs::hard_link(&path, &sha512_path).is_err() {
+        fs::copy(&path, &sha512_path)?;
+    }
+
+    Ok(CachedFile {
+        path,
+        sha1: actual_sha1,
+        sha512: Some(actual_sha512),
+        size: bytes.len(),
+    })
+}
+
+// fetch the files of locked addons, returns (destination path, cached file) pairs
+pub async fn fetch_locked(files: Vec<(PathBuf, LockedAddon)>) -> Result<Vec<(PathBuf, CachedFile)>> {
+    let permits = Arc::new(Semaphore::new(10)); // limit file downloads to 10 at a time
+    let mut tasks: JoinSet<Result<(PathBuf, CachedFile)>> = JoinSet::new();
+    for (path, locked) in files {
+        let permits = permits.clone();
+        let task = async move {
+            let _permit = permits.acquire().await.unwrap();
+            Ok((path, fetch_locked_file(&locked).await?))
+        };
+
+        tasks.spawn(task);
+    }
+
+    let mut fetched = Vec::new();
+    while let Some(res) = tasks.join_next().await { fetched.push(res??) }
+    Ok(fetched)
+}
+
+// get the file of a locked addon, local addons are read from the modpack folder
+pub async fn fetch_locked_file(locked: &LockedAddon) -> Result<CachedFile> {
+    if let Some(path) = &locked.path {
+        return read_local(path, &locked.sha1, locked.sha512.as_deref());
+    }
+
+    match &locked.url {
+        Some(url) => fetch(url, Some(&locked.sha1), locked.sha512.as_deref()).await,
+        None => Err(Error::Other(format!("{} does not have a download url", locked.filename))),
+    }
+}
+
+// local files can change without emm knowing, so they are checked against the hashes in the index
+fn read_local(path: &Path, sha1: &str, sha512: Option<&str>) -> Result<CachedFile> {
+    let bytes = fs::read(path)?;
+    let (actual_sha1, actual_sha512) = hash_bytes(&bytes);
+    if sha1 != actual_sha1 {
+        return Err(Error::HashMismatch(path.to_string_lossy().to_string(), sha1.to_owned(), actual_sha1));
+    }
+
+    if let Some(sha512) = sha512 {
+        if sha512 != actual_sha512 {
+            return Err(Error::HashMismatch(path.to_string_lossy().to_string(), sha512.to_owned(), actual_sha512));
+        }
+    }
+
+    Ok(CachedFile {
+        path: path.to_path_buf(),
+        sha1: actual_sha1,
+        sha512: Some(actual_sha512),
+        size: bytes.len(),
+    })
+}
+
+fn lookup(sha1: Option<&str>, sha512: Option<&str>) -> Result<Option<CachedFile>> {
+    // cached files are stored under both hashes, so a hash without a file means it isnt cached
+    let sha1_path = sha1.map(|h| object_path("sha1", h)).transpose()?;
+    let sha512_path = sha512.map(|h| object_path("sha512", h)).transpose()?;
+    if sha1_path.iter().chain(&sha512_path).any(|p| !p.is_file()) {
+        return Ok(None);
+    }
+
+    let path = match sha1_path.or(sha512_path) {
+        Some(path) => path,
+        None => return Ok(None),
+    };
+
+    // files are stored under their hashes, so they are only read when the sha1 isnt known
+    let (sha1, sha512) = match sha1 {
+        Some(sha1) => (sha1.to_owned(), sha512.map(str::to_owned)),
+        None => {
+            let (sha1, sha512) = hash_bytes(&fs::read(&path)?);
+            (sha1, Some(sha512))
+        },
+    };
+
+    // used by prune to find files that havent been used in a while
+    // a read only cache can still be used, its files just arent marked as used
+    File::options().write(true).open(&path).and_then(|f| f.set_modified(SystemTime::now())).ok();
+    let size = fs::metadata(&path)?.len() as usize;
+
+    Ok(Some(CachedFile { path, sha1, sha512, size }))
+}
+
+// (file count, total size in bytes), hardlinks are only counted once
+pub fn info() -> Result<(usize, u64)> {
+    let dir = files_dir()?.join("sha1");
+    let mut count = 0;
+    let mut size = 0;
+    for entry in WalkDir::new(dir).into_iter().filter_map(|e| e.ok()) {
+        if entry.path().is_file() {
+            count += 1;
+            size += entry.metadata().map(|m| m.len()).unwrap_or_default();
+        }
+    }
+
+    Ok((count, size))
+}
+
+// remove files that havent been used for max_age, returns the amount of removed files
+pub fn prune(max_age: Duration) -> Result<usize> {
+    let now = SystemTime::now();
+    let mut removed = 0;
+    for algo in ["sha1", "sha512"] {
+        for entry in WalkDir::new(files_dir()?.join(algo)).into_iter().filter_map(|e| e.ok()) {
+            let path = entry.path();
+            if !path.is_file() { continue; }
+
+            let modified = entry.metadata().ok().and_then(|m| m.modified().ok()).unwrap_or(now);
+            if now.duration_since(modified).unwrap_or_default() > max_age {
+                fs::remove_file(path)?;
+                if algo == "sha1" { removed += 1; }
+            }
+        }
+    }
+
+    Ok(removed)
+}
+
+pub fn clear() -> Result<()> {
+    for dir in [files_dir()?, request::responses_dir()?] {
+        if dir.is_dir() {
+            fs::remove_dir_all(dir)?;
+        }
+    }
+    Ok(())
+}
\ No newline at end of file
@@ -0,0 +1,61 @@
+use std::{env, fs, path::PathBuf};
+
+use console::style;
+
+use crate::structs::config::Config;
+
+impl Config {
+    // EMM_CONFIG or <config dir>/emm/config.toml
+    pub fn path() -> Option<PathBuf> {
+        match env::var_os("EMM_CONFIG") {
+            Some(path) => Some(path.into()),
+            None => dirs::config_dir().map(|dir| dir.join("emm").join("config.toml")),
+        }
+    }
+
+    // a broken config file shouldnt stop emm from working, so this falls back to the defaults
+    pub fn read() -> Self {
+        let mut config: Config = match Self::path().and_then(|path| fs::read_to_string(path).ok()) {
+            Some(config_str) => toml::from_str(&config_str).unwrap_or_else(|err| {
+                eprintln!("{}", style(format!("Ignoring invalid config file: {err}")).color256(166));
+                Config::default()
+            }),
+            None => Config::default(),
+        };
+
+        config.apply_env();
+        config
+    }
+
+    // env vars take priority over the config file
+    fn apply_env(&mut self) {
+        if let Some(dir) = env::var_os("EMM_CACHE_DIR") {
+            self.cache_dir = Some(dir.into());
+        }
+
+        if let Some(token) = env::var("GITHUB_TOKEN").ok().filter(|t| !t.is_empty()) {
+            self.github_token = Some(token);
+        }
+
+        let urls = &mut self.urls;
+        for (var, url) in [
+            ("EMM_MODRINTH_URL", &mut urls.modrinth),
+            ("EMM_CURSEFORGE_URL", &mut urls.curseforge),
+            ("EMM_GITHUB_URL", &mut urls.github),
+            ("EMM_FABRIC_META_URL", &mut urls.fabric_meta),
+            ("EMM_QUILT_META_URL", &mut urls.quilt_meta),
+            ("EMM_FORGE_META_URL", &mut urls.forge_meta),
+            ("EMM_NEOFORGE_MAVEN_URL", &mut urls.neoforge_maven),
+            ("EMM_MINECRAFT_META_URL", &mut urls.minecraft_meta),
+        ] {
+            if let Ok(value) = env::var(var) {
+                *url = value;
+            }
+
+            // paths are appended to these, so avoid double slashes
+            while url.ends_with('/') {
+                url.pop();
+            }
+        }
+    }
+}
\ No newline at end of file
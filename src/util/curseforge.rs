@@ -1,8 +1,40 @@
 use std::cmp::Ordering;
 
-use crate::{api::curseforge::File, structs::{index::ProjectType, pack::Modpack}};
+use crate::{api::curseforge::{File, FileReleaseType}, error::Result, structs::{index::{ProjectType, ReleaseChannel, Side}, pack::Modpack}};
 
-use super::{get_version_filters, FilterVersions};
+use super::{get_version_filters, jar::inspect, FilterVersions};
+
+// curseforge doesnt say which side a mod is for, so it's read from the jar
+pub async fn detect_side(file: &File) -> Result<Side> {
+    let url = match &file.download_url {
+        Some(url) => url,
+        None => return Ok(Side::Unknown), // the author disabled third party downloads
+    };
+
+    // algo 1 == sha1
+    let sha1 = file.hashes.iter().find(|h| h.algo == 1).map(|h| h.value.as_str());
+    Ok(inspect(url, sha1).await?.map(|m| m.side).unwrap_or(Side::Unknown))
+}
+
+// the side of addons that arent mods follows from what they are
+pub fn project_type_side(project_type: &ProjectType) -> Side {
+    match project_type {
+        ProjectType::Shader | ProjectType::Resourcepack => Side::Client,
+        // datapacks are loaded by the server, and by the client in singleplayer
+        ProjectType::Datapack => Side::Both,
+        _ => Side::Unknown,
+    }
+}
+
+impl File {
+    pub fn release_channel(&self) -> ReleaseChannel {
+        match self.release_type {
+            FileReleaseType::Release => ReleaseChannel::Release,
+            FileReleaseType::Beta => ReleaseChannel::Beta,
+            FileReleaseType::Alpha => ReleaseChannel::Alpha,
+        }
+    }
+}
 
 impl FilterVersions<File> for Vec<File> {
     fn filter_compatible(self, modpack: &Modpack, project_type: &ProjectType) -> Self {
@@ -17,6 +49,10 @@ impl FilterVersions<File> for Vec<File> {
         ).collect()
     }
 
+    fn filter_release_channel(self, min: Option<&ReleaseChannel>) -> Self {
+        self.into_iter().filter(|file| file.release_channel().allowed_by(min)).collect()
+    }
+
     // should be used on versions that are known to be compatible
     // order: matches game version => more recent => matches primary loader
     fn best_match(mut self, modpack: &Modpack) -> Option<File> {
@@ -1,24 +1,50 @@
-use std::{env, fs::File, io::{Read, Write}, path::Path};
+use std::{env, fs::{self, File}, io::{Read, Write}, path::Path, sync::atomic::{AtomicUsize, Ordering}};
 
 use path_clean::clean;
+use sha1::{Digest, Sha1};
+use sha2::Sha512;
 use walkdir::WalkDir;
 use zip::{write::SimpleFileOptions, ZipWriter};
 
 use crate::error::Result;
 
+use super::cache;
+
 pub fn is_local_path(path: &Path) -> bool {
-    let current_dir = env::current_dir().unwrap();
-    path.is_relative() && clean(current_dir.join(path)).starts_with(current_dir)
+    is_inside(&env::current_dir().unwrap(), path)
+}
+
+// whether the relative path stays inside of base
+pub fn is_inside(base: &Path, path: &Path) -> bool {
+    let base = clean(base);
+    path.is_relative() && clean(base.join(path)).starts_with(base)
+}
+
+// downloads go through the cache, so a file with known hashes is only downloaded once
+pub async fn download_file(path: &Path, url: &str, sha1: Option<&str>, sha512: Option<&str>) -> Result<()> {
+    let cached = cache::fetch(url, sha1, sha512).await?;
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    fs::copy(cached.path, path)?;
+    Ok(())
 }
 
-pub async fn download_file(path: &Path, url: &str) -> Result<()> {
-    let res = reqwest::get(url).await?;
-    let data = &*res.bytes().await?;
-    let mut file = File::create(path)?;
-    file.write_all(data)?;
+// write to a temporary file next to path first, so concurrent writes of the same file dont clash
+pub fn write_atomic(path: &Path, bytes: &[u8]) -> Result<()> {
+    static COUNTER: AtomicUsize = AtomicUsize::new(0);
+    let tmp_name = format!("{}.tmp-{}-{}", path.file_name().unwrap_or_default().to_string_lossy(), std::process::id(), COUNTER.fetch_add(1, Ordering::Relaxed));
+    let tmp_path = path.with_file_name(tmp_name);
+    fs::write(&tmp_path, bytes)?;
+    fs::rename(&tmp_path, path)?;
     Ok(())
 }
 
+// (sha1, sha512)
+pub fn hash_bytes(bytes: &[u8]) -> (String, String) {
+    (format!("{:x}", Sha1::digest(bytes)), format!("{:x}", Sha512::digest(bytes)))
+}
+
 // https://github.com/zip-rs/zip2/blob/master/examples/write_dir.rs
 pub fn add_recursively(from_path: &Path, zip_path: &Path, zip: &mut ZipWriter<File>, options: SimpleFileOptions) -> zip::result::ZipResult<()> {
     let mut buffer = Vec::new();
@@ -37,5 +63,11 @@ pub fn add_recursively(from_path: &Path, zip_path: &Path, zip: &mut ZipWriter<Fi
         }
     }
 
+    Ok(())
+}
+
+pub fn add_file(from_path: &Path, zip_path: &Path, zip: &mut ZipWriter<File>, options: SimpleFileOptions) -> zip::result::ZipResult<()> {
+    zip.start_file_from_path(zip_path, options)?;
+    zip.write_all(&fs::read(from_path)?)?;
     Ok(())
 }
\ No newline at end of file
@@ -0,0 +1,33 @@
+use lazy_regex::{regex_replace_all, Regex};
+
+use crate::api::github::{GithubRelease, ReleaseAsset};
+
+// a glob pattern that matches the same asset in other releases,
+// by replacing the version numbers in its name: sodium-mc1.20.4-0.5.9.jar => sodium-mc*-*.jar
+pub fn asset_pattern(release: &GithubRelease, asset: &ReleaseAsset) -> String {
+    let pattern = regex_replace_all!(r"\d+(?:\.\d+)*", &asset.name, "*").into_owned();
+
+    // the exact name is used when the pattern would pick another asset of this release
+    match find_asset(release, &pattern) {
+        Some(found) if found.name == asset.name => pattern,
+        _ => asset.name.clone(),
+    }
+}
+
+// * matches anything, ? matches one character
+pub fn matches_pattern(pattern: &str, name: &str) -> bool {
+    let regex = pattern
+        .split('*')
+        .map(|part| part.split('?').map(lazy_regex::regex::escape).collect::<Vec<String>>().join("."))
+        .collect::<Vec<String>>()
+        .join(".*");
+
+    Regex::new(&format!("^{regex}$")).is_ok_and(|r| r.is_match(name))
+}
+
+// extra assets like -sources.jar often match the same pattern, but always have longer names
+pub fn find_asset<'a>(release: &'a GithubRelease, pattern: &str) -> Option<&'a ReleaseAsset> {
+    release.assets.iter()
+        .filter(|a| matches_pattern(pattern, &a.name))
+        .min_by_key(|a| a.name.len())
+}
\ No newline at end of file
@@ -1,11 +1,12 @@
 use std::{env, fs, path::PathBuf, sync::Arc};
 
+use console::style;
 use dialoguer::FuzzySelect;
 use tokio::{sync::Semaphore, task::JoinSet};
 
-use crate::{error::{Error, Result}, structs::{index::{Addon, AddonSource, CurseforgeSource, Index, ProjectType}, pack::Modpack}};
+use crate::{error::{Error, Result}, structs::{index::{Addon, AddonSource, Index}, pack::Modpack}, GITHUB};
 
-use super::files::is_local_path;
+use super::{files::is_local_path, github::asset_pattern, output, prompt};
 
 impl Addon {
     fn index_file_name(&self) -> String {
@@ -17,6 +18,8 @@ impl Addon {
             AddonSource::Modrinth(source) => source.id.clone(),
             AddonSource::Curseforge(source) => source.id.to_string(),
             AddonSource::Github(source) => source.repo.clone(),
+            AddonSource::Local(source) => source.path.to_string_lossy().replace('\\', "/"),
+            AddonSource::Url(source) => source.url.clone(),
         }
     }
 
@@ -25,6 +28,8 @@ impl Addon {
             AddonSource::Modrinth(source) => source.version.clone(),
             AddonSource::Curseforge(source) => source.version.to_string(),
             AddonSource::Github(source) => source.tag.clone(),
+            AddonSource::Local(source) => source.sha1.clone(),
+            AddonSource::Url(source) => source.sha1.clone(),
         }
     }
 
@@ -43,8 +48,20 @@ impl Index {
         Ok(env::current_dir()?.join(index_path))
     }
 
+    // only reads from disk, commands that change the index use read_migrated
     pub async fn read() -> Result<Self> {
-        let path = Self::path()?;
+        Self::read_dir(Self::path()?).await
+    }
+
+    // reads the index and rewrites index files left by older versions of emm
+    pub async fn read_migrated() -> Result<Self> {
+        let mut index = Self::read().await?;
+        migrate_github_assets(&mut index.addons).await?;
+        Ok(index)
+    }
+
+    // reads the index folder at path as it is, without migrating anything
+    pub async fn read_dir(path: PathBuf) -> Result<Self> {
         if !path.is_dir() {
             return Ok(Index { addons: vec![] });
         }
@@ -61,7 +78,8 @@ impl Index {
                 }
     
                 let content = fs::read_to_string(&path)?;
-                let mut addon: Addon = toml::from_str(&content).unwrap();
+                let mut addon: Addon = toml::from_str(&content)
+                    .map_err(|err| Error::Other(format!("{} is not a valid index file: {err}", path.to_string_lossy())))?;
                 addon.filename = Some(path.file_name().unwrap().to_string_lossy().to_string());
 
                 Ok(Some(addon))
@@ -126,6 +144,10 @@ impl Index {
     pub fn select_addon(&self, str: &str) -> Option<&Addon> {
         match self.addons.iter().find(|a| a.matches_str(str)) {
             Some(addon) => Some(addon),
+            None if prompt::no_input() => {
+                output::note(style(format!("'{str}' does not match any addon in this modpack")).color256(166));
+                None
+            },
             None => {
                 let idx = FuzzySelect::new()
                     .with_prompt("Similar to:")
@@ -139,4 +161,37 @@ impl Index {
             },
         }
     }
+}
+
+// older index files point at github release assets by their position,
+// which breaks as soon as a release has its assets added or reordered
+async fn migrate_github_assets(addons: &mut [Addon]) -> Result<()> {
+    let mut migrated = Vec::new();
+    for addon in addons.iter_mut() {
+        let source = match &mut addon.source {
+            AddonSource::Github(source) => source,
+            _ => continue,
+        };
+        let asset_index = match source.asset_index.take() {
+            Some(asset_index) => asset_index,
+            None => continue,
+        };
+
+        let repo_split: Vec<&str> = source.repo.split('/').collect();
+        let release = GITHUB.get_release_by_tag(repo_split[0], repo_split[1], &source.tag).await?;
+        let asset = match release.assets.get(asset_index) {
+            Some(asset) => asset,
+            None => return Err(Error::Other(format!("Cant migrate {} because its release format has changed (asset index out of bounds)", addon.name))),
+        };
+
+        source.asset = asset_pattern(&release, asset);
+        output::note(style(format!("Migrated {} to use assets matching '{}'", addon.name, source.asset)).dim());
+        migrated.push(addon.clone());
+    }
+
+    if !migrated.is_empty() {
+        Index::write_addons(migrated).await?;
+    }
+
+    Ok(())
 }
\ No newline at end of file
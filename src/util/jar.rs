@@ -0,0 +1,390 @@
+use std::{cmp::Ordering, fs::File, io::Read, path::Path};
+
+use zip::ZipArchive;
+
+use crate::{error::Result, structs::{index::{ProjectType, Side}, jar::{FabricModJson, JarDependency, JarMetadata, ModsToml, OneOrMany, QuiltDependency, QuiltModJson, QuiltVersions, VersionRange}, pack::ModLoader}};
+
+use super::cache;
+
+// dependencies that are the game/loader itself and not other mods
+const PLATFORM_IDS: [&str; 8] = ["minecraft", "java", "fabricloader", "fabric-loader", "quilt_loader", "quilt-loader", "forge", "neoforge"];
+
+impl JarMetadata {
+    // None if the file isnt a jar or doesnt have any loader metadata emm understands,
+    // multi loader jars are merged into one
+    pub fn read(path: &Path) -> Result<Option<Self>> {
+        let mut zip = match ZipArchive::new(File::open(path)?) {
+            Ok(zip) => zip,
+            Err(_) => return Ok(None),
+        };
+
+        let mut found: Vec<JarMetadata> = Vec::new();
+
+        if let Some(text) = read_entry(&mut zip, "fabric.mod.json") {
+            if let Ok(fabric) = serde_json::from_str::<FabricModJson>(&text) {
+                found.push(fabric.into());
+            }
+        }
+
+        if let Some(text) = read_entry(&mut zip, "quilt.mod.json") {
+            if let Ok(quilt) = serde_json::from_str::<QuiltModJson>(&text) {
+                found.push(quilt.into());
+            }
+        }
+
+        let jar_version = read_entry(&mut zip, "META-INF/MANIFEST.MF").and_then(|manifest| {
+            manifest.lines()
+                .find_map(|l| l.strip_prefix("Implementation-Version:"))
+                .map(|v| v.trim().to_owned())
+        });
+
+        for (file, loader) in [("META-INF/mods.toml", ModLoader::Forge), ("META-INF/neoforge.mods.toml", ModLoader::NeoForge)] {
+            if let Some(text) = read_entry(&mut zip, file) {
+                if let Some(metadata) = toml::from_str::<ModsToml>(&text).ok().and_then(|m| from_mods_toml(m, loader, jar_version.as_deref())) {
+                    found.push(metadata);
+                }
+            }
+        }
+
+        let mut found = found.into_iter();
+        let mut metadata = match found.next() {
+            Some(metadata) => metadata,
+            None => return Ok(None),
+        };
+
+        for other in found {
+            if metadata.side == Side::Unknown {
+                metadata.side = other.side;
+            }
+            for loader in other.loaders {
+                if !metadata.loaders.contains(&loader) {
+                    metadata.loaders.push(loader);
+                }
+            }
+            metadata.minecraft.extend(other.minecraft);
+        }
+
+        Ok(Some(metadata))
+    }
+
+    // Some(true/false) if the jar says whether it supports this minecraft version
+    pub fn supports_minecraft(&self, version: &str) -> Option<bool> {
+        if self.minecraft.is_empty() { return None; }
+
+        let results: Vec<Option<bool>> = self.minecraft.iter().map(|r| r.matches(version)).collect();
+        if results.contains(&Some(true)) {
+            Some(true)
+        } else if results.iter().all(|r| r == &Some(false)) {
+            Some(false)
+        } else {
+            None
+        }
+    }
+
+    // None if the jar doesnt say enough to check
+    pub fn is_compatible(&self, game_versions: &[&String], loaders: &[&ModLoader]) -> Option<bool> {
+        if !self.loaders.is_empty() && !loaders.iter().any(|l| self.loaders.contains(l)) {
+            return Some(false);
+        }
+
+        let results: Vec<Option<bool>> = game_versions.iter().map(|v| self.supports_minecraft(v)).collect();
+        if results.contains(&Some(true)) {
+            Some(true)
+        } else if results.iter().all(|r| r == &Some(false)) {
+            Some(false)
+        } else {
+            None
+        }
+    }
+}
+
+// download a file (through the cache) and read its metadata
+pub async fn inspect(url: &str, sha1: Option<&str>) -> Result<Option<JarMetadata>> {
+    let cached = cache::fetch(url, sha1, None).await?;
+    JarMetadata::read(&cached.path)
+}
+
+// guess what kind of addon a downloaded file is from its contents
+pub fn detect_project_type(path: &Path) -> Result<ProjectType> {
+    if JarMetadata::read(path)?.is_some() {
+        return Ok(ProjectType::Mod);
+    }
+
+    let zip = match ZipArchive::new(File::open(path)?) {
+        Ok(zip) => zip,
+        Err(_) => return Ok(ProjectType::Unknown),
+    };
+
+    let has_dir = |dir: &str| zip.file_names().any(|f| f.starts_with(dir));
+    let has_mcmeta = zip.file_names().any(|f| f == "pack.mcmeta");
+
+    Ok(if has_mcmeta && has_dir("data/") {
+        ProjectType::Datapack
+    } else if has_mcmeta {
+        ProjectType::Resourcepack
+    } else if has_dir("shaders/") {
+        ProjectType::Shader
+    } else {
+        ProjectType::Unknown
+    })
+}
+
+fn read_entry(zip: &mut ZipArchive<File>, name: &str) -> Option<String> {
+    let mut text = String::new();
+    zip.by_name(name).ok()?.read_to_string(&mut text).ok()?;
+    Some(text)
+}
+
+impl From<FabricModJson> for JarMetadata {
+    fn from(fabric: FabricModJson) -> Self {
+        let ranges = |versions: OneOrMany| match versions {
+            OneOrMany::One(v) => vec![VersionRange::Predicate(v)],
+            OneOrMany::Many(v) => v.into_iter().map(VersionRange::Predicate).collect(),
+        };
+
+        let mut minecraft = Vec::new();
+        let mut dependencies = Vec::new();
+        for (required, deps) in [(true, fabric.depends), (false, fabric.recommends)] {
+            for (id, versions) in deps {
+                if id == "minecraft" {
+                    minecraft = ranges(versions);
+                } else if !PLATFORM_IDS.contains(&id.as_str()) {
+                    dependencies.push(JarDependency { id, versions: ranges(versions), required });
+                }
+            }
+        }
+        dependencies.sort_by(|a, b| a.id.cmp(&b.id));
+
+        JarMetadata {
+            id: fabric.id,
+            version: fabric.version,
+            // fabric defaults to both sides
+            side: match fabric.environment.as_deref() {
+                Some("client") => Side::Client,
+                Some("server") => Side::Server,
+                _ => Side::Both,
+            },
+            loaders: vec![ModLoader::Fabric],
+            minecraft,
+            dependencies,
+        }
+    }
+}
+
+impl From<QuiltModJson> for JarMetadata {
+    fn from(quilt: QuiltModJson) -> Self {
+        let mut minecraft = Vec::new();
+        let mut dependencies = Vec::new();
+        for (id, versions, optional) in quilt.quilt_loader.depends.into_iter().flat_map(|d| flatten_quilt_dependency(d, false)) {
+
+            let versions = match versions {
+                None => Vec::new(),
+                Some(QuiltVersions::One(v)) => vec![VersionRange::Predicate(v)],
+                Some(QuiltVersions::Any { any: v } | QuiltVersions::Many(v)) => v.into_iter().map(VersionRange::Predicate).collect(),
+                Some(QuiltVersions::All { all }) => vec![VersionRange::Predicate(all.join(" "))],
+            };
+
+            if id == "minecraft" {
+                minecraft.extend(versions);
+            } else if !PLATFORM_IDS.contains(&id.as_str()) {
+                dependencies.push(JarDependency { id, versions, required: !optional });
+            }
+        }
+
+        JarMetadata {
+            id: quilt.quilt_loader.id,
+            version: quilt.quilt_loader.version,
+            side: match quilt.minecraft.and_then(|m| m.environment).as_deref() {
+                Some("client") => Side::Client,
+                Some("dedicated_server") => Side::Server,
+                _ => Side::Both,
+            },
+            loaders: vec![ModLoader::Quilt],
+            minecraft,
+            dependencies,
+        }
+    }
+}
+
+// (id, versions, optional), a mod in an array isnt required on its own since any of the others will do
+fn flatten_quilt_dependency(dep: QuiltDependency, in_any: bool) -> Vec<(String, Option<QuiltVersions>, bool)> {
+    match dep {
+        QuiltDependency::Id(id) => vec![(id, None, in_any)],
+        QuiltDependency::Object { id, versions, optional } => vec![(id, versions, optional || in_any)],
+        QuiltDependency::Any(deps) => deps.into_iter().flat_map(|d| flatten_quilt_dependency(d, true)).collect(),
+    }
+}
+
+fn from_mods_toml(mut mods_toml: ModsToml, loader: ModLoader, jar_version: Option<&str>) -> Option<JarMetadata> {
+    if mods_toml.mods.is_empty() { return None; }
+    let first = mods_toml.mods.remove(0);
+    let deps = mods_toml.dependencies.remove(&first.mod_id).unwrap_or_default();
+
+    // neoforge for 1.20.1 still used mods.toml
+    let loader = match loader {
+        ModLoader::Forge if deps.iter().any(|d| d.mod_id == "neoforge") => ModLoader::NeoForge,
+        loader => loader,
+    };
+
+    let mut minecraft = Vec::new();
+    let mut dependencies = Vec::new();
+    for dep in deps {
+        let versions: Vec<VersionRange> = dep.version_range.into_iter().map(VersionRange::Maven).collect();
+        if dep.mod_id == "minecraft" {
+            minecraft = versions;
+        } else if !PLATFORM_IDS.contains(&dep.mod_id.as_str()) {
+            let required = match dep.dependency_type.as_deref() {
+                Some(dependency_type) => dependency_type.eq_ignore_ascii_case("required"),
+                None => dep.mandatory.unwrap_or(true),
+            };
+            dependencies.push(JarDependency { id: dep.mod_id, versions, required });
+        }
+    }
+
+    let version = match first.version {
+        Some(version) if version == "${file.jarVersion}" => jar_version.unwrap_or(&version).to_owned(),
+        Some(version) => version,
+        None => jar_version.unwrap_or_default().to_owned(),
+    };
+
+    Some(JarMetadata {
+        id: first.mod_id,
+        version,
+        // mods tell forge whether the server needs them through displayTest
+        side: match first.display_test.as_deref() {
+            Some("IGNORE_SERVER_VERSION") => Side::Client,
+            Some("MATCH_VERSION") => Side::Both,
+            _ => Side::Unknown,
+        },
+        loaders: vec![loader],
+        minecraft,
+        dependencies,
+    })
+}
+
+impl VersionRange {
+    // None if the versions cant be compared, for example snapshots
+    pub fn matches(&self, version: &str) -> Option<bool> {
+        match self {
+            Self::Predicate(predicate) => {
+                let mut result = Some(true);
+                // space separated predicates all have to match
+                for term in predicate.split_whitespace() {
+                    match predicate_matches(term, version) {
+                        Some(true) => (),
+                        Some(false) => return Some(false),
+                        None => result = None,
+                    }
+                }
+                result
+            },
+            Self::Maven(range) => maven_matches(range, version),
+        }
+    }
+}
+
+impl std::fmt::Display for VersionRange {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Predicate(range) | Self::Maven(range) => write!(f, "{range}"),
+        }
+    }
+}
+
+// "1.20.4" => [1, 20, 4], release candidates & pre releases compare like their release
+fn parse_version(version: &str) -> Option<Vec<u32>> {
+    let version = version.split(['-', '+']).next()?;
+    version.split('.').map(|p| p.parse().ok()).collect()
+}
+
+fn compare_versions(a: &str, b: &str) -> Option<Ordering> {
+    if a == b { return Some(Ordering::Equal); }
+    let (mut a, mut b) = (parse_version(a)?, parse_version(b)?);
+    let len = a.len().max(b.len());
+    a.resize(len, 0);
+    b.resize(len, 0);
+    Some(a.cmp(&b))
+}
+
+fn predicate_matches(term: &str, version: &str) -> Option<bool> {
+    if term == "*" { return Some(true); }
+
+    let (op, target) = [">=", "<=", ">", "<", "=", "~", "^"].iter()
+        .find_map(|op| term.strip_prefix(op).map(|t| (*op, t)))
+        .unwrap_or(("=", term));
+
+    // "1.20.x" matches every version starting with 1.20
+    if target.split('.').any(|p| matches!(p, "x" | "X" | "*")) {
+        let prefix: Vec<&str> = target.split('.').take_while(|p| !matches!(*p, "x" | "X" | "*")).collect();
+        let parts: Vec<&str> = version.split('.').collect();
+        return Some(parts.len() >= prefix.len() && parts[..prefix.len()] == prefix[..]);
+    }
+
+    let ordering = compare_versions(version, target)?;
+    Some(match op {
+        ">=" => ordering != Ordering::Less,
+        "<=" => ordering != Ordering::Greater,
+        ">" => ordering == Ordering::Greater,
+        "<" => ordering == Ordering::Less,
+        // same minor version
+        "~" => ordering != Ordering::Less && parse_version(version)?.get(..2) == parse_version(target)?.get(..2),
+        // same major version
+        "^" => ordering != Ordering::Less && parse_version(version)?.first() == parse_version(target)?.first(),
+        _ => ordering == Ordering::Equal,
+    })
+}
+
+// "[1.20,1.21)", "[1.20.1]", "[1.20,)", multiple ranges separated by commas
+fn maven_matches(range: &str, version: &str) -> Option<bool> {
+    let range = range.trim();
+    // a plain version is only a recommendation in maven, forge accepts any version for it
+    if !range.starts_with(['[', '(']) { return Some(true); }
+
+    let mut result = Some(false);
+    let mut rest = range;
+    while let Some(start) = rest.find(['[', '(']) {
+        let end = match rest[start..].find([']', ')']) {
+            Some(end) => start + end,
+            None => return None,
+        };
+
+        let segment = &rest[start..=end];
+        rest = &rest[end + 1..];
+
+        match maven_segment_matches(segment, version) {
+            Some(true) => return Some(true),
+            Some(false) => (),
+            None => result = None,
+        }
+    }
+
+    result
+}
+
+fn maven_segment_matches(segment: &str, version: &str) -> Option<bool> {
+    let lower_inclusive = segment.starts_with('[');
+    let upper_inclusive = segment.ends_with(']');
+    let inner = &segment[1..segment.len() - 1];
+
+    let (lower, upper) = match inner.split_once(',') {
+        Some((lower, upper)) => (lower.trim(), upper.trim()),
+        // "[1.20.1]" is an exact version
+        None => return compare_versions(version, inner.trim()).map(|o| o == Ordering::Equal),
+    };
+
+    if !lower.is_empty() {
+        let ordering = compare_versions(version, lower)?;
+        if ordering == Ordering::Less || (ordering == Ordering::Equal && !lower_inclusive) {
+            return Some(false);
+        }
+    }
+
+    if !upper.is_empty() {
+        let ordering = compare_versions(version, upper)?;
+        if ordering == Ordering::Greater || (ordering == Ordering::Equal && !upper_inclusive) {
+            return Some(false);
+        }
+    }
+
+    Some(true)
+}
\ No newline at end of file
@@ -0,0 +1,260 @@
+use std::{env, fs, path::PathBuf};
+
+use tokio::{task::JoinSet, try_join};
+
+use crate::{error::{Error, Result}, structs::{index::{Addon, AddonSource, Index}, lock::{LockedAddon, Lockfile}, pack::Modpack}, util::{cache, github::find_asset, modrinth::primary_file}, CURSEFORGE, GITHUB, MODRINTH};
+
+impl Lockfile {
+    pub fn path() -> PathBuf {
+        env::current_dir().unwrap().join("emm.lock")
+    }
+
+    pub fn read() -> Result<Option<Self>> {
+        if !Self::path().is_file() { return Ok(None); }
+        let lock_str = fs::read_to_string(Self::path())?;
+        toml::from_str(&lock_str).map(Some).map_err(|err| Error::Other(format!("Unable to read emm.lock: {err}")))
+    }
+
+    pub fn write(&self) -> Result<()> {
+        fs::write(Self::path(), toml::to_string_pretty(self).unwrap())?;
+        Ok(())
+    }
+
+    // the locked file for an addon at its current version
+    pub fn get(&self, addon: &Addon) -> Option<&LockedAddon> {
+        self.addons.iter().find(|a| a.id == addon.generic_id() && a.version == addon.generic_version())
+    }
+
+    // like get, for commands that need every addon to be locked
+    pub fn require(&self, addon: &Addon) -> Result<&LockedAddon> {
+        self.get(addon).ok_or(Error::StaleLockfile(format!("{} is not locked at its current version", addon.name)))
+    }
+
+    // bring the lockfile in line with pack.toml & the index, only resolving addons that changed
+    // refresh_loader re-resolves the loader version if pack.toml uses "latest"
+    pub async fn update(refresh_loader: bool) -> Result<Self> {
+        let lock = Self::resolve(refresh_loader).await?;
+        lock.write()?;
+        Ok(lock)
+    }
+
+    // the lockfile update would write, without writing it
+    pub async fn resolve(refresh_loader: bool) -> Result<Self> {
+        let modpack = Modpack::read()?;
+        let index = Index::read().await?;
+        let current = Self::read()?;
+
+        let loader_version = match &current {
+            Some(lock) if !refresh_loader
+                && modpack.versions.loader_version == "latest"
+                && lock.minecraft == modpack.versions.minecraft
+                && lock.loader == modpack.versions.loader => lock.loader_version.clone(),
+            _ => modpack.get_loader_version().await?,
+        };
+
+        let mut addons = Vec::new();
+        let mut to_resolve = Vec::new();
+        for addon in &index.addons {
+            match current.as_ref().and_then(|lock| lock.get(addon)) {
+                Some(locked) => addons.push(locked.clone()),
+                None => to_resolve.push(addon.clone()),
+            }
+        }
+
+        addons.extend(resolve_addons(to_resolve).await?);
+        addons.sort_by(|a, b| a.id.cmp(&b.id));
+
+        let lock = Lockfile {
+            minecraft: modpack.versions.minecraft,
+            loader: modpack.versions.loader,
+            loader_version,
+            addons,
+        };
+
+        // the platforms leave deleted or hidden versions out of bulk requests instead of failing
+        let missing: Vec<&str> = index.addons.iter().filter(|a| lock.get(a).is_none()).map(|a| a.name.as_str()).collect();
+        if !missing.is_empty() {
+            return Err(Error::Other(format!("Unable to lock {}, the version in the index may have been deleted", missing.join(", "))));
+        }
+
+        Ok(lock)
+    }
+
+    // the lockfile exports should use
+    // with locked a missing or outdated lockfile is an error instead of being updated
+    pub async fn for_export(locked: bool) -> Result<Self> {
+        if !locked {
+            return Self::update(false).await;
+        }
+
+        let lock = match Self::read()? {
+            Some(lock) => lock,
+            None => return Err(Error::StaleLockfile("emm.lock does not exist".into())),
+        };
+
+        lock.check(&Modpack::read()?, &Index::read().await?)?;
+        Ok(lock)
+    }
+
+    fn check(&self, modpack: &Modpack, index: &Index) -> Result<()> {
+        if self.minecraft != modpack.versions.minecraft || self.loader != modpack.versions.loader {
+            return Err(Error::StaleLockfile("the minecraft version or loader changed".into()));
+        }
+
+        if modpack.versions.loader_version != "latest" && modpack.versions.loader_version != self.loader_version {
+            return Err(Error::StaleLockfile("the loader version changed".into()));
+        }
+
+        for addon in &index.addons {
+            self.require(addon)?;
+        }
+
+        if self.addons.len() != index.addons.len() {
+            return Err(Error::StaleLockfile("it has addons that are no longer in the index".into()));
+        }
+
+        Ok(())
+    }
+}
+
+async fn resolve_addons(addons: Vec<Addon>) -> Result<Vec<LockedAddon>> {
+    let mut mr_versions = Vec::new();
+    let mut cf_files = Vec::new();
+    let mut gh_sources = Vec::new();
+    let mut local_sources = Vec::new();
+    let mut url_sources = Vec::new();
+
+    addons.into_iter().for_each(|a| match a.source {
+        AddonSource::Modrinth(source) => mr_versions.push(source.version),
+        AddonSource::Curseforge(source) => cf_files.push((source.id, source.version)),
+        AddonSource::Github(source) => gh_sources.push((a.name, source)),
+        AddonSource::Local(source) => local_sources.push((a.name, source)),
+        AddonSource::Url(source) => url_sources.push(source),
+    });
+
+    let (mr_versions, cf_files) = try_join!(
+        async {
+            if mr_versions.is_empty() { return Ok(Vec::new()); }
+            MODRINTH.get_versions(&mr_versions.iter().map(AsRef::as_ref).collect::<Vec<&str>>()).await
+        },
+        async {
+            if cf_files.is_empty() { return Ok(Vec::new()); }
+            CURSEFORGE.get_files(cf_files).await
+        }
+    )?;
+
+    let mut locked = Vec::new();
+
+    for version in mr_versions {
+        let file = primary_file(version.files);
+        locked.push(LockedAddon {
+            id: version.project_id,
+            version: version.id,
+            filename: file.filename,
+            url: Some(file.url.clone()),
+            sha1: file.hashes.get("sha1").cloned().ok_or(Error::Other(format!("{} file does not have a sha1 hash", file.url)))?,
+            sha512: file.hashes.get("sha512").cloned(),
+            size: file.size,
+            path: None,
+        });
+    }
+
+    for file in cf_files {
+        // algo 1 == sha1
+        let sha1 = match file.hashes.iter().find(|h| h.algo == 1) {
+            Some(hash) => hash.value.clone(),
+            None => return Err(Error::Other(format!("{} does not have a sha1 hash", file.file_name))),
+        };
+
+        locked.push(LockedAddon {
+            id: file.mod_id.to_string(),
+            version: file.id.to_string(),
+            filename: file.file_name,
+            url: file.download_url,
+            sha1,
+            sha512: None,
+            size: file.file_length,
+            path: None,
+        });
+    }
+
+    // github doesnt provide file hashes, so assets have to be downloaded (into the cache) once here
+    let mut tasks: JoinSet<Result<LockedAddon>> = JoinSet::new();
+    for (name, source) in gh_sources {
+        let task = async move {
+            let repo_split: Vec<&str> = source.repo.split('/').collect();
+            let release = GITHUB.get_release_by_tag(repo_split[0], repo_split[1], &source.tag).await?;
+            // index files that arent migrated yet still point at their asset by position
+            let asset = match source.asset_index.filter(|_| source.asset.is_empty()) {
+                Some(asset_index) => release.assets.get(asset_index),
+                None => find_asset(&release, &source.asset),
+            };
+            let asset = match asset {
+                Some(asset) => asset,
+                None => return Err(Error::Other(format!("Cant lock {name} because no asset of {} matches '{}'", source.tag, source.asset))),
+            };
+
+            let cached = cache::fetch(&asset.browser_download_url, None, None).await?;
+
+            Ok(LockedAddon {
+                id: source.repo,
+                version: source.tag,
+                filename: asset.name.clone(),
+                url: Some(asset.browser_download_url.clone()),
+                sha1: cached.sha1,
+                sha512: cached.sha512,
+                size: cached.size,
+                path: None,
+            })
+        };
+
+        tasks.spawn(task);
+    }
+    // direct downloads dont have to match a platform, but they do have to match the hashes in the index
+    for source in url_sources {
+        let task = async move {
+            let cached = cache::fetch(&source.url, Some(&source.sha1), Some(&source.sha512)).await?;
+
+            Ok(LockedAddon {
+                id: source.url.clone(),
+                version: source.sha1,
+                filename: url_filename(&source.url),
+                url: Some(source.url),
+                sha1: cached.sha1,
+                sha512: cached.sha512,
+                size: cached.size,
+                path: None,
+            })
+        };
+
+        tasks.spawn(task);
+    }
+    while let Some(res) = tasks.join_next().await { locked.push(res??) }
+
+    // the hashes come from the index, the file itself is only checked when its used
+    for (name, source) in local_sources {
+        let size = match fs::metadata(&source.path) {
+            Ok(metadata) => metadata.len() as usize,
+            Err(_) => return Err(Error::Other(format!("Cant lock {name} because {} does not exist", source.path.to_string_lossy()))),
+        };
+
+        locked.push(LockedAddon {
+            id: source.path.to_string_lossy().replace('\\', "/"),
+            version: source.sha1.clone(),
+            filename: source.path.file_name().unwrap_or_default().to_string_lossy().to_string(),
+            url: None,
+            sha1: source.sha1,
+            sha512: Some(source.sha512),
+            size,
+            path: Some(source.path),
+        });
+    }
+
+    Ok(locked)
+}
+
+// the last path segment of a url, without the query
+pub fn url_filename(url: &str) -> String {
+    let path = url.split(['?', '#']).next().unwrap_or_default();
+    path.trim_end_matches('/').rsplit('/').next().unwrap_or_default().to_owned()
+}
\ No newline at end of file
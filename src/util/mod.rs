@@ -1,13 +1,20 @@
 use supports_hyperlinks::supports_hyperlinks;
 
-use crate::structs::{index::ProjectType, pack::{ModLoader, Modpack}};
+use crate::structs::{index::{AddonOptions, ProjectType, ReleaseChannel}, pack::{ModLoader, Modpack}};
 
 pub mod pack;
 pub mod index;
 pub mod versions;
 pub mod modrinth;
 pub mod curseforge;
+pub mod github;
 pub mod files;
+pub mod cache;
+pub mod lock;
+pub mod config;
+pub mod jar;
+pub mod prompt;
+pub mod output;
 
 // using https://crates.io/crates/supports-hyperlinks
 // to test if hyperlinks in terminal are supported and use a link if they are
@@ -22,6 +29,21 @@ pub fn to_hyperlink(link: &str, placeholder: &str) -> String {
 pub trait FilterVersions<T> {
     fn filter_compatible(self, modpack: &Modpack, project_type: &ProjectType) -> Self;
     fn best_match(self, modpack: &Modpack) -> Option<T>;
+    fn filter_release_channel(self, min: Option<&ReleaseChannel>) -> Self;
+}
+
+// an addon's own setting overrides the modpack's
+// for names and versions from the apis that end up in html or markdown
+pub fn escape_html(str: &str) -> String {
+    str.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&#39;")
+}
+
+pub fn min_release_channel<'a>(modpack: &'a Modpack, options: Option<&'a AddonOptions>) -> Option<&'a ReleaseChannel> {
+    options.and_then(|o| o.min_release_channel.as_ref()).or(modpack.options.min_release_channel.as_ref())
 }
 
 pub fn get_version_filters(modpack: &Modpack) -> (Vec<&String>, Vec<&ModLoader>) {
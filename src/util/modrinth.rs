@@ -1,6 +1,6 @@
 use std::cmp::Ordering;
 
-use crate::{api::modrinth::{SideSupportType, Version, VersionFile}, error::{Error, Result}, structs::{index::{ProjectType, Side}, pack::Modpack}};
+use crate::{api::modrinth::{SearchFacet, SideSupportType, Version, VersionFile}, error::{Error, Result}, structs::{index::{ProjectType, ReleaseChannel, Side}, pack::Modpack}};
 
 use super::{get_version_filters, FilterVersions};
 
@@ -17,6 +17,22 @@ pub fn get_primary_hash(files: Vec<VersionFile>) -> Result<String> {
     }
 }
 
+// facet groups are and-ed, facets in a group or-ed.
+// loaders are categories on modrinth, other project types use categories for shader loaders and such
+pub fn search_facets(modpack: &Modpack, project_type: &ProjectType) -> Vec<Vec<SearchFacet>> {
+    let (game_versions, loaders) = get_version_filters(modpack);
+    let mut facets = vec![
+        vec![SearchFacet::ProjectType(project_type.clone())],
+        game_versions.into_iter().map(|v| SearchFacet::Versions(v.clone())).collect(),
+    ];
+
+    if *project_type == ProjectType::Mod {
+        facets.push(loaders.into_iter().map(|l| SearchFacet::Categories(l.to_string().to_lowercase())).collect());
+    }
+
+    facets
+}
+
 pub fn get_side(client_side: &SideSupportType, server_side: &SideSupportType) -> Side {
     let use_client = should_use_side(client_side);
     let use_server = should_use_side(server_side);
@@ -25,7 +41,7 @@ pub fn get_side(client_side: &SideSupportType, server_side: &SideSupportType) ->
         (true, true) => Side::Both,
         (true, false) => Side::Client,
         (false, true) => Side::Server,
-        _ => Side::Both, // SideSupportType could be unknown, better to use both as fallback
+        _ => Side::Unknown, // SideSupportType could be unknown, exports use both as fallback
     }
 }
 
@@ -45,6 +61,10 @@ impl FilterVersions<Version> for Vec<Version> {
         ).collect()
     }
 
+    fn filter_release_channel(self, min: Option<&ReleaseChannel>) -> Self {
+        self.into_iter().filter(|version| version.version_type.allowed_by(min)).collect()
+    }
+
     // should be used on versions that are known to be compatible
     // order: matches game version => more recent => matches primary loader
     fn best_match(mut self, modpack: &Modpack) -> Option<Version> {
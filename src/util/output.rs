@@ -0,0 +1,29 @@
+use std::{fmt::Display, sync::atomic::{AtomicBool, Ordering}};
+
+use serde::Serialize;
+
+use crate::{cli::OutputFormat, error::Result};
+
+// set by --output json, stdout only gets the json then
+static JSON: AtomicBool = AtomicBool::new(false);
+
+pub fn set_format(format: OutputFormat) {
+    JSON.store(format == OutputFormat::Json, Ordering::Relaxed);
+}
+
+pub fn json() -> bool {
+    JSON.load(Ordering::Relaxed)
+}
+
+pub fn print_json(value: &impl Serialize) -> Result<()> {
+    println!("{}", serde_json::to_string_pretty(value)?);
+    Ok(())
+}
+
+// messages on the side of a command go to stderr with --output json, so stdout stays parseable
+pub fn note(message: impl Display) {
+    match json() {
+        true => eprintln!("{message}"),
+        false => println!("{message}"),
+    }
+}
\ No newline at end of file
@@ -0,0 +1,39 @@
+use std::{io::IsTerminal, sync::atomic::{AtomicBool, Ordering}};
+
+use console::Term;
+use dialoguer::Confirm;
+
+use crate::error::{Error, Result};
+
+// set by --yes/--no-input, or when there is no terminal to prompt in like in ci
+static NO_INPUT: AtomicBool = AtomicBool::new(false);
+
+pub fn set_no_input(no_input: bool) {
+    let attended = std::io::stdin().is_terminal() && Term::stderr().is_term();
+    NO_INPUT.store(no_input || !attended, Ordering::Relaxed);
+}
+
+pub fn no_input() -> bool {
+    NO_INPUT.load(Ordering::Relaxed)
+}
+
+// for prompts without a sensible default, the hint should say which flag to use instead
+pub fn require_input(what: &str, hint: &str) -> Result<()> {
+    match no_input() {
+        true => Err(Error::Other(format!("{what} needs input, {hint}"))),
+        false => Ok(()),
+    }
+}
+
+// without input questions are answered with their default, or yes if they dont have one
+pub fn confirm(prompt: &str, default: Option<bool>) -> bool {
+    if no_input() {
+        return default.unwrap_or(true);
+    }
+
+    let mut confirm = Confirm::new().with_prompt(prompt);
+    if let Some(default) = default {
+        confirm = confirm.default(default);
+    }
+    confirm.interact().unwrap()
+}
\ No newline at end of file
@@ -1,16 +1,16 @@
 use serde::{Deserialize, Serialize};
 
-use crate::error::Result;
+use crate::{api::request, error::Result, CONFIG};
 
-const FABRIC_META_URL: &str = "https://meta.fabricmc.net/";
+pub const FABRIC_META_URL: &str = "https://meta.fabricmc.net";
 
 pub async fn fetch_supported_mc_versions() -> Result<Vec<String>> {
-    let versions: Vec<GameVersion> = reqwest::get(FABRIC_META_URL.to_owned() + "/v2/versions/game").await?.json().await?;
+    let versions: Vec<GameVersion> = request::get(&format!("{}/v2/versions/game", CONFIG.urls.fabric_meta)).await?.json().await?;
     Ok(versions.into_iter().map(|v| v.version).collect())
 }
 
 pub async fn fetch_loader_versions() -> Result<Vec<String>> {
-    let loaders: Vec<LoaderVersion> = reqwest::get(FABRIC_META_URL.to_owned() + "/v2/versions/loader").await?.json().await?;
+    let loaders: Vec<LoaderVersion> = request::get(&format!("{}/v2/versions/loader", CONFIG.urls.fabric_meta)).await?.json().await?;
     Ok(loaders.into_iter().map(|l| l.version).collect())
 }
 
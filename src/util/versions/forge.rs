@@ -1,11 +1,11 @@
 use std::collections::HashMap;
 
-use crate::error::Result;
+use crate::{api::request, error::Result, CONFIG};
 
-const FORGE_METADATA_URL: &str = "https://files.minecraftforge.net/net/minecraftforge/forge/maven-metadata.json";
+pub const FORGE_METADATA_URL: &str = "https://files.minecraftforge.net/net/minecraftforge/forge/maven-metadata.json";
 
 async fn fetch_versions() -> Result<HashMap<String, Vec<String>>> {
-    let versions: HashMap<String, Vec<String>> = reqwest::get(FORGE_METADATA_URL).await?.json().await?;
+    let versions: HashMap<String, Vec<String>> = request::get(&CONFIG.urls.forge_meta).await?.json().await?;
     Ok(versions)
 }
 
@@ -1,11 +1,11 @@
 use serde::{Deserialize, Serialize};
 
-use crate::error::Result;
+use crate::{api::request, error::Result, CONFIG};
 
-const PISTOR_META_URL: &str = "https://piston-meta.mojang.com/mc/game/version_manifest_v2.json";
+pub const PISTON_META_URL: &str = "https://piston-meta.mojang.com/mc/game/version_manifest_v2.json";
 
 async fn fetch_version_manifest() -> Result<VersionManifest> {
-    let version_manifest: VersionManifest = reqwest::get(PISTOR_META_URL).await?.json().await?;
+    let version_manifest: VersionManifest = request::get_cached(&CONFIG.urls.minecraft_meta).await?.json().await?;
     Ok(version_manifest)
 }
 
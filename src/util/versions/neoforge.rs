@@ -1,16 +1,16 @@
 use serde::{Deserialize, Serialize};
 
-use crate::error::Result;
+use crate::{api::request, error::Result, CONFIG};
 
-const NEOFORGE_MAVEN_URL: &str = "https://maven.neoforged.net/api/maven";
+pub const NEOFORGE_MAVEN_URL: &str = "https://maven.neoforged.net/api/maven";
 
 pub async fn get_supported_versions(mc_version: &str) -> Result<Vec<String>> {
     let filtered_versions_url = match mc_version {
-        "1.20.1" => format!("{}/versions/releases/net/neoforged/forge?filter=1.20.1", NEOFORGE_MAVEN_URL),
-        _ => format!("{}/versions/releases/net/neoforged/neoforge?filter={}", NEOFORGE_MAVEN_URL, mc_version.strip_prefix("1.").unwrap_or("unsupported"))
+        "1.20.1" => format!("{}/versions/releases/net/neoforged/forge?filter=1.20.1", CONFIG.urls.neoforge_maven),
+        _ => format!("{}/versions/releases/net/neoforged/neoforge?filter={}", CONFIG.urls.neoforge_maven, mc_version.strip_prefix("1.").unwrap_or("unsupported"))
     };
 
-    let res: Versions = reqwest::get(filtered_versions_url).await?.json().await?;
+    let res: Versions = request::get(&filtered_versions_url).await?.json().await?;
     Ok(res.versions)
 }
 
@@ -1,16 +1,16 @@
 use serde::{Deserialize, Serialize};
 
-use crate::error::Result;
+use crate::{api::request, error::Result, CONFIG};
 
-const QUILT_META_URL: &str = "https://meta.quiltmc.org/";
+pub const QUILT_META_URL: &str = "https://meta.quiltmc.org";
 
 pub async fn fetch_supported_mc_versions() -> Result<Vec<String>> {
-    let versions: Vec<GameVersion> = reqwest::get(QUILT_META_URL.to_owned() + "/v3/versions/game").await?.json().await?;
+    let versions: Vec<GameVersion> = request::get(&format!("{}/v3/versions/game", CONFIG.urls.quilt_meta)).await?.json().await?;
     Ok(versions.into_iter().map(|v| v.version).collect())
 }
 
 pub async fn fetch_loader_versions() -> Result<Vec<String>> {
-    let loaders: Vec<LoaderVersion> = reqwest::get(QUILT_META_URL.to_owned() + "/v3/versions/loader").await?.json().await?;
+    let loaders: Vec<LoaderVersion> = request::get(&format!("{}/v3/versions/loader", CONFIG.urls.quilt_meta)).await?.json().await?;
     Ok(loaders.into_iter().map(|l| l.version).collect())
 }
 
@@ -0,0 +1,163 @@
+mod common;
+
+use common::TestPack;
+
+#[test]
+fn add_modrinth_with_dependencies() {
+    let pack = TestPack::init();
+    let out = pack.run(&["add", "mr", "sodium"]);
+    assert!(out.contains("Sodium"));
+
+    let sodium = pack.toml("index/sodium.toml");
+    assert_eq!(sodium["source"]["source"].as_str(), Some("modrinth"));
+    assert_eq!(sodium["source"]["id"].as_str(), Some("AANobbMI"));
+    assert_eq!(sodium["source"]["version"].as_str(), Some("4GyXKCLd"));
+    assert_eq!(sodium["side"].as_str(), Some("client"));
+
+    // required dependency
+    let fabric_api = pack.toml("index/fabric-api.toml");
+    assert_eq!(fabric_api["source"]["version"].as_str(), Some("xwNIYuPL"));
+    assert_eq!(fabric_api["side"].as_str(), Some("both"));
+
+    let lock = pack.toml("emm.lock");
+    assert_eq!(lock["loader_version"].as_str(), Some("0.15.11"));
+    assert_eq!(lock["addon"].as_array().unwrap().len(), 2);
+}
+
+#[test]
+fn add_modrinth_version() {
+    let pack = TestPack::init();
+    pack.run(&["add", "mr", "sodium", "-v", "OihdIimA"]);
+
+    assert_eq!(pack.toml("index/sodium.toml")["source"]["version"].as_str(), Some("OihdIimA"));
+}
+
+#[test]
+fn add_modrinth_incompatible() {
+    let pack = TestPack::init();
+    pack.write("pack.toml", &pack.read("pack.toml").replace("fabric", "forge"));
+    let out = pack.run(&["add", "mr", "sodium"]);
+
+    assert!(out.contains("No compatible versions"), "{out}");
+    assert!(!pack.path().join("index/sodium.toml").exists());
+}
+
+#[test]
+fn add_curseforge_slug() {
+    let pack = TestPack::init();
+    pack.run(&["add", "cf", "jei"]);
+
+    let jei = pack.toml("index/jei.toml");
+    assert_eq!(jei["source"]["source"].as_str(), Some("curseforge"));
+    assert_eq!(jei["source"]["id"].as_integer(), Some(238222));
+    assert_eq!(jei["source"]["version"].as_integer(), Some(5262120));
+    // read from the jar's fabric.mod.json
+    assert_eq!(jei["side"].as_str(), Some("both"));
+}
+
+#[test]
+fn add_already_added() {
+    let pack = TestPack::init();
+    pack.run(&["add", "mr", "sodium"]);
+    let out = pack.run(&["add", "mr", "sodium"]);
+
+    assert!(out.contains("already in the modpack"));
+}
+
+#[test]
+fn add_github_reads_jar() {
+    let pack = TestPack::init();
+    let out = pack.run(&["add", "gh", "tester/example-mod", "-t", "v1.1.0"]);
+    assert!(out.contains("depends on: fabric-api >=0.97.0"), "{out}");
+
+    let addon = pack.toml("index/example-mod.toml");
+    assert_eq!(addon["source"]["asset"].as_str(), Some("example-mod-*.jar"));
+    assert_eq!(addon["type"].as_str(), Some("mod"));
+    assert_eq!(addon["side"].as_str(), Some("client"));
+    assert_eq!(addon["source"]["tag"].as_str(), Some("v1.1.0"));
+}
+
+#[test]
+fn add_github_warns_incompatible() {
+    let pack = TestPack::init();
+    let out = pack.run(&["add", "gh", "https://github.com/tester/example-mod", "-t", "v1.0.0"]);
+
+    assert!(out.contains("example-mod 1.0.0 says it doesnt support"), "{out}");
+    assert!(pack.path().join("index/example-mod.toml").is_file());
+}
+
+#[test]
+fn add_github_forge_mod() {
+    let pack = TestPack::new();
+    pack.run(&["init", "-n", "Forge Pack", "-l", "-L", "forge"]);
+    let out = pack.run(&["add", "gh", "tester/forge-mod", "-t", "2.0.0"]);
+    assert!(!out.contains("doesnt support"), "{out}");
+
+    let addon = pack.toml("index/forge-mod.toml");
+    assert_eq!(addon["type"].as_str(), Some("mod"));
+    // displayTest = IGNORE_SERVER_VERSION
+    assert_eq!(addon["side"].as_str(), Some("client"));
+    assert_eq!(pack.toml("emm.lock")["loader_version"].as_str(), Some("49.1.0"));
+}
+
+#[test]
+fn add_github_quilt_mod() {
+    let pack = TestPack::new();
+    pack.run(&["init", "-n", "Quilt Pack", "-l", "-L", "quilt"]);
+    let out = pack.run(&["add", "gh", "tester/quilt-mod", "-t", "1.0.0"]);
+    assert!(!out.contains("doesnt support"), "{out}");
+    // qsl and quilted_fabric_api are listed as alternatives, so neither is required on its own
+    assert!(out.contains("depends on: owo-lib\n"), "{out}");
+
+    let addon = pack.toml("index/quilt-mod.toml");
+    assert_eq!(addon["type"].as_str(), Some("mod"));
+    assert_eq!(addon["side"].as_str(), Some("client"));
+    assert_eq!(pack.toml("emm.lock")["loader_version"].as_str(), Some("0.26.0"));
+}
+
+#[test]
+fn add_github_resourcepack() {
+    let pack = TestPack::init();
+    pack.run(&["add", "gh", "tester/example-resources", "-t", "1.0"]);
+
+    let addon = pack.toml("index/example-resources.toml");
+    assert_eq!(addon["type"].as_str(), Some("resourcepack"));
+    assert_eq!(addon["side"].as_str(), Some("unknown"));
+}
+
+#[test]
+fn add_modrinth_with_optional() {
+    let pack = TestPack::init();
+    let out = pack.run(&["add", "mr", "sodium", "--with-optional"]);
+    assert!(out.contains("Iris Shaders (optional)"), "{out}");
+
+    let iris = pack.toml("index/iris.toml");
+    assert_eq!(iris["source"]["version"].as_str(), Some("kuOV4Ece"));
+    assert_eq!(iris["options"]["optional"].as_bool(), Some(true));
+    assert!(pack.toml("index/sodium.toml")["options"].get("optional").is_none());
+    assert_eq!(pack.toml("emm.lock")["addon"].as_array().unwrap().len(), 3);
+}
+
+#[test]
+fn add_curseforge_with_optional() {
+    let pack = TestPack::init();
+    pack.run(&["add", "cf", "jei", "--with-optional"]);
+
+    let jer = pack.toml("index/just-enough-resources-jer.toml");
+    assert_eq!(jer["source"]["version"].as_integer(), Some(5270340));
+    assert_eq!(jer["options"]["optional"].as_bool(), Some(true));
+    assert_eq!(jer["side"].as_str(), Some("client"));
+}
+
+#[test]
+fn add_respects_release_channel() {
+    let pack = TestPack::init();
+    pack.write("pack.toml", &pack.read("pack.toml").replace("[options]", "[options]\nmin_release_channel = \"release\""));
+
+    // the only 1.20.4 versions of sodium and jei are betas
+    let out = pack.run(&["add", "mr", "sodium"]);
+    assert!(out.contains("No compatible versions"), "{out}");
+    let out = pack.run(&["add", "cf", "jei"]);
+    assert!(out.contains("No compatible versions"), "{out}");
+    assert!(!pack.path().join("index/sodium.toml").exists());
+}
\ No newline at end of file
@@ -0,0 +1,67 @@
+mod common;
+
+use std::{fs::File, time::{Duration, SystemTime}};
+
+use common::TestPack;
+use walkdir::WalkDir;
+
+// github assets are downloaded into the cache when they are locked
+fn cached_pack() -> TestPack {
+    let pack = TestPack::init();
+    pack.run(&["add", "gh", "tester/example-mod", "-t", "v1.0.0"]);
+    pack
+}
+
+#[test]
+fn cache_info() {
+    let pack = cached_pack();
+    let out = pack.run(&["cache", "info"]);
+    assert!(out.contains(&pack.cache_path().to_string_lossy().to_string()), "{out}");
+    assert!(out.contains("1 file"), "{out}");
+}
+
+#[test]
+fn cache_prune() {
+    let pack = cached_pack();
+    let out = pack.run(&["cache", "prune"]);
+    assert!(out.contains("Removed 0 unused files"), "{out}");
+
+    let old = SystemTime::now() - Duration::from_secs(40 * 24 * 60 * 60);
+    for entry in WalkDir::new(pack.cache_path().join("files")).into_iter().filter_map(|e| e.ok()) {
+        if entry.path().is_file() {
+            File::options().write(true).open(entry.path()).unwrap().set_modified(old).unwrap();
+        }
+    }
+
+    let out = pack.run(&["cache", "prune", "-d", "30"]);
+    assert!(out.contains("Removed 1 unused file"), "{out}");
+    assert!(pack.run(&["cache", "info"]).contains("0 files"));
+}
+
+#[test]
+fn cache_clear() {
+    let pack = cached_pack();
+    pack.run(&["cache", "clear"]);
+    assert!(!pack.cache_path().join("files").exists());
+    assert!(pack.run(&["cache", "info"]).contains("0 files"));
+
+    // the next lock downloads the asset again
+    pack.run(&["remove", "example-mod"]);
+    pack.run(&["add", "gh", "tester/example-mod", "-t", "v1.0.0"]);
+    assert!(pack.run(&["cache", "info"]).contains("1 file"));
+}
+
+#[test]
+fn read_only_cache() {
+    let pack = cached_pack();
+    let files: Vec<_> = WalkDir::new(pack.cache_path().join("files")).into_iter().filter_map(|e| e.ok()).filter(|e| e.path().is_file()).collect();
+    for entry in &files {
+        let mut permissions = entry.metadata().unwrap().permissions();
+        permissions.set_readonly(true);
+        std::fs::set_permissions(entry.path(), permissions).unwrap();
+    }
+
+    // cached files are still used when they cant be marked as used
+    pack.run(&["export", "mr"]);
+    assert!(pack.run(&["cache", "info"]).contains("1 file"));
+}
\ No newline at end of file
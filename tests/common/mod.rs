@@ -0,0 +1,522 @@
+// shared setup for the integration tests
+// every test gets its own pack folder, download cache and a stand-in http server
+// that answers modrinth, curseforge, github and loader meta requests from tests/fixtures
+#![allow(dead_code)]
+
+use std::{collections::BTreeMap, fs, io::{Cursor, Read, Write}, path::{Path, PathBuf}, process::{Command, Output}};
+
+use lazy_regex::regex_replace_all;
+use mockito::{Matcher, Mock, Request, Server, ServerGuard};
+use reqwest::Url;
+use serde_json::{json, Value};
+use sha1::{Digest, Sha1};
+use sha2::Sha512;
+use tempfile::TempDir;
+use walkdir::WalkDir;
+use zip::{write::SimpleFileOptions, DateTime, ZipArchive, ZipWriter};
+
+pub struct TestPack {
+    pub dir: TempDir,
+    cache_dir: TempDir,
+    server: ServerGuard,
+}
+
+impl TestPack {
+    pub fn new() -> Self {
+        let mut server = Server::new();
+        mock_fixtures(&mut server);
+
+        TestPack {
+            dir: TempDir::new().unwrap(),
+            cache_dir: TempDir::new().unwrap(),
+            server,
+        }
+    }
+
+    // a pack made with `emm init` on the latest release (1.20.4) with fabric
+    pub fn init() -> Self {
+        let pack = Self::new();
+        pack.run(&["init", "-n", "Test Pack", "-l", "-L", "fabric", "-a", "tester"]);
+        pack
+    }
+
+    pub fn path(&self) -> &Path {
+        self.dir.path()
+    }
+
+    // the download cache this pack's commands use
+    pub fn cache_path(&self) -> &Path {
+        self.cache_dir.path()
+    }
+
+    pub fn server_url(&self) -> String {
+        self.server.url()
+    }
+
+    pub fn emm(&self, args: &[&str]) -> Output {
+        let url = self.server.url();
+        Command::new(env!("CARGO_BIN_EXE_emm"))
+            .args(args)
+            .current_dir(self.path())
+            .env("EMM_CONFIG", self.cache_dir.path().join("config.toml"))
+            .env("EMM_CACHE_DIR", self.cache_dir.path())
+            .env_remove("GITHUB_TOKEN")
+            .env("EMM_MODRINTH_URL", format!("{url}/modrinth"))
+            .env("EMM_CURSEFORGE_URL", format!("{url}/curseforge"))
+            .env("EMM_GITHUB_URL", format!("{url}/github"))
+            .env("EMM_FABRIC_META_URL", format!("{url}/fabric"))
+            .env("EMM_QUILT_META_URL", format!("{url}/quilt"))
+            .env("EMM_FORGE_META_URL", format!("{url}/forge/maven-metadata.json"))
+            .env("EMM_NEOFORGE_MAVEN_URL", format!("{url}/neoforge"))
+            .env("EMM_MINECRAFT_META_URL", format!("{url}/minecraft/version_manifest_v2.json"))
+            .output()
+            .unwrap()
+    }
+
+    // run emm and fail the test if it didnt succeed, returns stdout
+    pub fn run(&self, args: &[&str]) -> String {
+        let output = self.emm(args);
+        assert!(
+            output.status.success(),
+            "`emm {}` failed\nstdout: {}\nstderr: {}",
+            args.join(" "),
+            String::from_utf8_lossy(&output.stdout),
+            String::from_utf8_lossy(&output.stderr)
+        );
+        String::from_utf8_lossy(&output.stdout).into_owned()
+    }
+
+    pub fn read(&self, path: &str) -> String {
+        fs::read_to_string(self.path().join(path)).unwrap()
+    }
+
+    pub fn write(&self, path: &str, contents: &str) {
+        let path = self.path().join(path);
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(path, contents).unwrap();
+    }
+
+    // answer the next requests to path with an error status before the fixtures take over
+    pub fn fail(&mut self, method: &str, path: &str, status: usize, times: usize, headers: &[(&str, &str)]) {
+        let mut mock = self.server.mock(method, path).with_status(status).expect(times);
+        for (name, value) in headers {
+            mock = mock.with_header(*name, value);
+        }
+        mock.create();
+
+        // once those are used up, the most recently created matching mock answers
+        mock_fixtures(&mut self.server);
+    }
+
+    // answer requests to path from the fixtures with extra headers, the returned mock counts them
+    pub fn expect(&mut self, method: &str, path: impl Into<Matcher>, times: usize, headers: &[(&str, &str)]) -> Mock {
+        let url = self.server.url();
+        let status_url = url.clone();
+        let mut mock = self.server.mock(method, path)
+            .with_status_code_from_request(move |req| respond(req, &status_url).0)
+            .with_body_from_request(move |req| respond(req, &url).1)
+            .expect(times);
+        for (name, value) in headers {
+            mock = mock.with_header(*name, value);
+        }
+        mock.create()
+    }
+
+    // answer conditional requests to path for this etag with 304 Not Modified
+    pub fn not_modified(&mut self, path: &str, etag: &str) -> Mock {
+        self.server.mock("GET", path)
+            .match_header("if-none-match", etag)
+            .with_status(304)
+            .expect(1)
+            .create()
+    }
+
+    // the emm config file this pack's commands use
+    pub fn write_config(&self, contents: &str) {
+        fs::write(self.cache_dir.path().join("config.toml"), contents).unwrap();
+    }
+
+    pub fn toml(&self, path: &str) -> toml::Value {
+        toml::from_str(&self.read(path)).unwrap()
+    }
+}
+
+fn mock_fixtures(server: &mut ServerGuard) {
+    let url = server.url();
+    for method in ["GET", "POST"] {
+        let status_url = url.clone();
+        let body_url = url.clone();
+        let link_url = url.clone();
+        server.mock(method, Matcher::Any)
+            .with_status_code_from_request(move |req| respond(req, &status_url).0)
+            .with_body_from_request(move |req| respond(req, &body_url).1)
+            .with_header_from_request("link", move |req| github_link(req, &link_url))
+            .create();
+    }
+}
+
+// zip file contents by path
+pub fn read_zip(path: &Path) -> BTreeMap<String, Vec<u8>> {
+    let mut zip = ZipArchive::new(fs::File::open(path).unwrap()).unwrap();
+    let mut files = BTreeMap::new();
+    for i in 0..zip.len() {
+        let mut file = zip.by_index(i).unwrap();
+        if file.is_dir() { continue; }
+
+        let mut bytes = Vec::new();
+        file.read_to_end(&mut bytes).unwrap();
+        files.insert(file.name().to_owned(), bytes);
+    }
+    files
+}
+
+pub fn write_zip(path: &Path, files: &[(&str, &[u8])]) {
+    let mut zip = ZipWriter::new(fs::File::create(path).unwrap());
+    for (name, bytes) in files {
+        zip.start_file(*name, SimpleFileOptions::default()).unwrap();
+        zip.write_all(bytes).unwrap();
+    }
+    zip.finish().unwrap();
+}
+
+pub fn fixtures_dir() -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR")).join("tests").join("fixtures")
+}
+
+// folders in fixtures/files are served as zips of their contents, that's how the test jars are stored
+pub fn fixture_file(name: &str) -> Vec<u8> {
+    try_fixture_file(name).unwrap()
+}
+
+fn try_fixture_file(name: &str) -> Option<Vec<u8>> {
+    let path = fixtures_dir().join("files").join(name);
+    if !path.is_dir() {
+        return fs::read(path).ok();
+    }
+
+    let mut zip = ZipWriter::new(Cursor::new(Vec::new()));
+    // fixed timestamps so the same folder always zips to the same bytes
+    let options = SimpleFileOptions::default().last_modified_time(DateTime::default());
+    for entry in WalkDir::new(&path).sort_by_file_name().into_iter().filter_map(|e| e.ok()) {
+        if !entry.path().is_file() { continue; }
+
+        let name = entry.path().strip_prefix(&path).unwrap().components()
+            .map(|c| c.as_os_str().to_string_lossy())
+            .collect::<Vec<_>>()
+            .join("/");
+        zip.start_file(name, options).unwrap();
+        zip.write_all(&fs::read(entry.path()).unwrap()).unwrap();
+    }
+
+    Some(zip.finish().unwrap().into_inner())
+}
+
+pub fn sha1(bytes: &[u8]) -> String {
+    format!("{:x}", Sha1::digest(bytes))
+}
+
+pub fn sha512(bytes: &[u8]) -> String {
+    format!("{:x}", Sha512::digest(bytes))
+}
+
+// curseforge's murmur2 variant, whitespace bytes are skipped
+pub fn fingerprint(bytes: &[u8]) -> u32 {
+    let bytes: Vec<u8> = bytes.iter().filter(|b| !matches!(b, 9 | 10 | 13 | 32)).copied().collect();
+    murmur2::murmur2(&bytes, 1)
+}
+
+// fixtures can use {{server}} for the stand-in server url
+// and {{sha1:file}}, {{sha512:file}}, {{size:file}}, {{fingerprint:file}} for files in fixtures/files
+pub fn fixture(name: &str, server_url: &str) -> Value {
+    let text = fs::read_to_string(fixtures_dir().join(name)).unwrap();
+    let text = regex_replace_all!(r"\{\{(\w+)(?::([^}]+))?\}\}", &text, |_, key: &str, file: &str| {
+        match key {
+            "server" => server_url.to_owned(),
+            "sha1" => sha1(&fixture_file(file)),
+            "sha512" => sha512(&fixture_file(file)),
+            "size" => fixture_file(file).len().to_string(),
+            "fingerprint" => fingerprint(&fixture_file(file)).to_string(),
+            _ => panic!("unknown fixture placeholder {key}"),
+        }
+    });
+
+    serde_json::from_str(&text).unwrap()
+}
+
+fn respond(req: &Request, server_url: &str) -> (usize, Vec<u8>) {
+    let url = Url::parse(&format!("{server_url}{}", req.path_and_query())).unwrap();
+    let body: Value = req.body().ok()
+        .and_then(|b| serde_json::from_slice(b).ok())
+        .unwrap_or_default();
+
+    let segments: Vec<&str> = url.path_segments().unwrap().collect();
+    let res = match (segments[0], &segments[1..]) {
+        ("files", [name]) => match try_fixture_file(name) {
+            Some(bytes) => return (200, bytes),
+            None => None,
+        },
+        ("minecraft", [file]) => Some(fixture(&format!("minecraft/{file}"), server_url)),
+        ("fabric", ["v2", "versions", "game"]) => Some(fixture("fabric/game.json", server_url)),
+        ("fabric", ["v2", "versions", "loader"]) => Some(fixture("fabric/loader.json", server_url)),
+        ("quilt", ["v3", "versions", "game"]) => Some(fixture("quilt/game.json", server_url)),
+        ("quilt", ["v3", "versions", "loader"]) => Some(fixture("quilt/loader.json", server_url)),
+        ("forge", ["maven-metadata.json"]) => Some(fixture("forge/maven-metadata.json", server_url)),
+        ("modrinth", path) => modrinth(req.method(), path, &url, &body, server_url),
+        ("curseforge", path) => curseforge(req.method(), path, &url, &body, server_url).map(|data| json!({ "data": data })),
+        ("github", path) => github(req, path, &url, server_url),
+        _ => None,
+    };
+
+    match res {
+        Some(value) => (200, serde_json::to_vec(&value).unwrap()),
+        None => (404, b"{\"error\":\"not_found\"}".to_vec()),
+    }
+}
+
+fn query_ids(url: &Url) -> Vec<String> {
+    url.query_pairs()
+        .find(|(k, _)| k == "ids")
+        .map(|(_, v)| serde_json::from_str(&v).unwrap())
+        .unwrap_or_default()
+}
+
+fn primary_sha1(version: &Value) -> &str {
+    version["files"][0]["hashes"]["sha1"].as_str().unwrap()
+}
+
+fn any_in(values: &Value, allowed: &Value) -> bool {
+    match allowed.as_array() {
+        Some(allowed) => values.as_array().unwrap().iter().any(|v| allowed.contains(v)),
+        None => true,
+    }
+}
+
+fn modrinth(method: &str, path: &[&str], url: &Url, body: &Value, server_url: &str) -> Option<Value> {
+    let projects = fixture("modrinth/projects.json", server_url).as_array().unwrap().clone();
+    let versions = fixture("modrinth/versions.json", server_url).as_array().unwrap().clone();
+    let find_project = |id: &str| projects.iter().find(|p| p["id"] == id || p["slug"] == id).cloned();
+
+    match (method, path) {
+        ("GET", ["project", id]) => find_project(id),
+        ("GET", ["project", id, "version"]) => {
+            let project = find_project(id)?;
+            Some(versions.iter().filter(|v| v["project_id"] == project["id"]).cloned().collect())
+        },
+        ("GET", ["projects"]) => {
+            let ids = query_ids(url);
+            Some(projects.iter().filter(|p| ids.iter().any(|id| p["id"] == id.as_str())).cloned().collect())
+        },
+        ("GET", ["version", id]) => versions.iter().find(|v| v["id"] == *id).cloned(),
+        ("GET", ["versions"]) => {
+            let ids = query_ids(url);
+            Some(versions.iter().filter(|v| ids.iter().any(|id| v["id"] == id.as_str())).cloned().collect())
+        },
+        ("GET", ["search"]) => {
+            let query = url.query_pairs().find(|(k, _)| k == "query").map(|(_, v)| v.to_lowercase()).unwrap_or_default();
+            let facets: Vec<Vec<String>> = url.query_pairs().find(|(k, _)| k == "facets")
+                .map(|(_, v)| serde_json::from_str(&v).unwrap())
+                .unwrap_or_default();
+
+            // versions and loaders come from the project's versions, like modrinth's search index
+            let matches_facet = |project: &Value, facet: &str| {
+                let (key, value) = facet.split_once(':').unwrap();
+                let value = json!(value.trim());
+                let mut project_versions = versions.iter().filter(|v| v["project_id"] == project["id"]);
+                match key {
+                    "project_type" => project["project_type"] == value,
+                    "versions" => project_versions.any(|v| v["game_versions"].as_array().unwrap().contains(&value)),
+                    "categories" => project_versions.any(|v| v["loaders"].as_array().unwrap().contains(&value)),
+                    _ => panic!("unknown search facet {facet}"),
+                }
+            };
+
+            let mut hits: Vec<Value> = projects.iter()
+                .filter(|p| p["title"].as_str().unwrap().to_lowercase().contains(&query))
+                .filter(|p| facets.iter().all(|group| group.iter().any(|f| matches_facet(p, f))))
+                .map(|p| {
+                    let mut loaders: Vec<&Value> = Vec::new();
+                    for loader in versions.iter().filter(|v| v["project_id"] == p["id"]).flat_map(|v| v["loaders"].as_array().unwrap()) {
+                        if !loaders.contains(&loader) { loaders.push(loader) }
+                    }
+                    json!({
+                        "title": p["title"], "project_id": p["id"], "slug": p["slug"], "author": "tester",
+                        "downloads": p["downloads"], "date_modified": p["updated"], "categories": loaders
+                    })
+                })
+                .collect();
+
+            match url.query_pairs().find(|(k, _)| k == "index").map(|(_, v)| v.into_owned()).as_deref() {
+                Some("downloads") => hits.sort_by_key(|h| std::cmp::Reverse(h["downloads"].as_u64())),
+                Some("updated") => hits.sort_by_key(|h| std::cmp::Reverse(h["date_modified"].as_str().map(|d| d.to_owned()))),
+                _ => (),
+            }
+            Some(json!({ "hits": hits, "offset": 0, "limit": 20, "total_hits": hits.len() }))
+        },
+        ("POST", ["version_files"]) => {
+            let mut res = serde_json::Map::new();
+            for hash in body["hashes"].as_array().unwrap() {
+                if let Some(version) = versions.iter().find(|v| primary_sha1(v) == hash) {
+                    res.insert(hash.as_str().unwrap().to_owned(), version.clone());
+                }
+            }
+            Some(res.into())
+        },
+        // the newest version of the same project that matches the filters
+        ("POST", ["version_files", "update"]) => {
+            let mut res = serde_json::Map::new();
+            for hash in body["hashes"].as_array().unwrap() {
+                let current = match versions.iter().find(|v| primary_sha1(v) == hash) {
+                    Some(version) => version,
+                    None => continue,
+                };
+
+                let latest = versions.iter()
+                    .filter(|v| v["project_id"] == current["project_id"])
+                    .filter(|v| any_in(&v["game_versions"], &body["game_versions"]) && any_in(&v["loaders"], &body["loaders"]))
+                    .max_by_key(|v| v["date_published"].as_str().unwrap().to_owned());
+
+                if let Some(latest) = latest {
+                    res.insert(hash.as_str().unwrap().to_owned(), latest.clone());
+                }
+            }
+            Some(res.into())
+        },
+        _ => None,
+    }
+}
+
+fn curseforge(method: &str, path: &[&str], url: &Url, body: &Value, server_url: &str) -> Option<Value> {
+    let mods = fixture("curseforge/mods.json", server_url).as_array().unwrap().clone();
+    let files = fixture("curseforge/files.json", server_url).as_array().unwrap().clone();
+    let id = |s: &str| s.parse::<i64>().ok();
+
+    match (method, path) {
+        ("GET", ["v1", "mods", "search"]) => {
+            let param = |name: &str| url.query_pairs().find(|(k, _)| k == name).map(|(_, v)| v.into_owned());
+            let list = |name: &str| param(name).map(|v| serde_json::from_str::<Vec<Value>>(&v).unwrap());
+            // loaders are listed in a file's game versions on curseforge
+            let loader_names: Option<Vec<Value>> = list("modLoaderTypes").map(|types| types.iter().map(|t| match t.as_i64() {
+                Some(1) => json!("Forge"),
+                Some(4) => json!("Fabric"),
+                Some(5) => json!("Quilt"),
+                Some(6) => json!("NeoForge"),
+                _ => panic!("unknown mod loader type {t}"),
+            }).collect());
+            let has_file = |m: &Value, names: &[Value]| files.iter()
+                .any(|f| f["modId"] == m["id"] && f["gameVersions"].as_array().unwrap().iter().any(|v| names.contains(v)));
+
+            let mut mods: Vec<Value> = mods.into_iter()
+                .filter(|m| param("slug").is_none_or(|slug| m["slug"] == slug.as_str()))
+                .filter(|m| param("classId").is_none_or(|id| m["classId"].as_i64() == id.parse().ok()))
+                .filter(|m| param("searchFilter").is_none_or(|q| m["name"].as_str().unwrap().to_lowercase().contains(&q.to_lowercase())))
+                .filter(|m| list("gameVersions").is_none_or(|versions| has_file(m, &versions)))
+                .filter(|m| loader_names.as_ref().is_none_or(|names| has_file(m, names)))
+                .collect();
+
+            match param("sortField").as_deref() {
+                Some("6") => mods.sort_by_key(|m| std::cmp::Reverse(m["downloadCount"].as_u64())),
+                Some("3") => mods.sort_by_key(|m| std::cmp::Reverse(m["dateModified"].as_str().map(|d| d.to_owned()))),
+                _ => (),
+            }
+            Some(mods.into())
+        },
+        ("GET", ["v1", "mods", mod_id]) => mods.into_iter().find(|m| m["id"].as_i64() == id(mod_id)),
+        ("GET", ["v1", "mods", mod_id, "files"]) => Some(files.into_iter().filter(|f| f["modId"].as_i64() == id(mod_id)).collect()),
+        ("GET", ["v1", "mods", mod_id, "files", file_id]) => files.into_iter()
+            .find(|f| f["modId"].as_i64() == id(mod_id) && f["id"].as_i64() == id(file_id)),
+        ("GET", ["v1", "mods", mod_id, "files", file_id, "changelog"]) => files.into_iter()
+            .find(|f| f["modId"].as_i64() == id(mod_id) && f["id"].as_i64() == id(file_id))
+            .map(|f| format!("<p>Changes in <b>{}</b></p><ul><li>Fixed &quot;crash&quot;</li></ul>", f["fileName"].as_str().unwrap()).into()),
+        ("POST", ["v1", "mods"]) => {
+            let ids = body["modIds"].as_array().unwrap();
+            Some(mods.into_iter().filter(|m| ids.contains(&m["id"])).collect())
+        },
+        ("POST", ["v1", "fingerprints"]) => {
+            let fingerprints = body["fingerprints"].as_array().unwrap();
+            let matches: Vec<Value> = files.into_iter()
+                .filter(|f| fingerprints.contains(&f["fileFingerprint"]))
+                .map(|f| json!({ "id": f["modId"], "file": f }))
+                .collect();
+            Some(json!({ "exactMatches": matches }))
+        },
+        _ => None,
+    }
+}
+
+pub const GITHUB_TOKEN: &str = "test-token";
+const GITHUB_PAGE_SIZE: usize = 30;
+
+// github fixtures are stored per repo in github/<owner>/<repo>/
+// repos starting with private- need the test token, like private repos would
+fn github(req: &Request, path: &[&str], url: &Url, server_url: &str) -> Option<Value> {
+    let fixture_path = |owner: &str, repo: &str, file: &str| format!("github/{owner}/{repo}/{file}");
+    let exists = |path: &str| fixtures_dir().join(path).is_file();
+    let releases = |owner: &str, repo: &str| match (owner, repo) {
+        ("tester", "many-releases") => Some(many_releases(server_url)),
+        _ if exists(&fixture_path(owner, repo, "releases.json")) => {
+            Some(fixture(&fixture_path(owner, repo, "releases.json"), server_url).as_array().unwrap().clone())
+        },
+        _ => None,
+    };
+
+    if path.get(2).is_some_and(|repo| repo.starts_with("private-")) && req.header("authorization").first().is_none_or(|h| *h != format!("Bearer {GITHUB_TOKEN}").as_str()) {
+        return None;
+    }
+
+    match path {
+        ["repos", owner, repo] if exists(&fixture_path(owner, repo, "repo.json")) => {
+            Some(fixture(&fixture_path(owner, repo, "repo.json"), server_url))
+        },
+        ["repos", owner, repo, "releases"] => {
+            let (page, per_page) = github_page(url);
+            Some(releases(owner, repo)?.into_iter().skip((page - 1) * per_page).take(per_page).collect())
+        },
+        ["repos", owner, repo, "releases", "tags", tag] => releases(owner, repo)?.into_iter().find(|r| r["tag_name"] == *tag),
+        _ => None,
+    }
+}
+
+// 150 releases, newest first, only the oldest 30 arent prereleases
+fn many_releases(server_url: &str) -> Vec<Value> {
+    (1..=150).rev().map(|n| json!({
+        "name": format!("{n}.0.0"),
+        "tag_name": format!("v{n}"),
+        "prerelease": n > 30,
+        "body": null,
+        "assets": [{
+            "browser_download_url": format!("{server_url}/files/example-mod-1.1.0.jar"),
+            "name": format!("many-releases-{n}.0.0.jar"),
+            "size": fixture_file("example-mod-1.1.0.jar").len()
+        }]
+    })).collect()
+}
+
+fn github_page(url: &Url) -> (usize, usize) {
+    let param = |key: &str, default: usize| url.query_pairs()
+        .find(|(k, _)| k == key)
+        .and_then(|(_, v)| v.parse().ok())
+        .unwrap_or(default);
+
+    (param("page", 1), param("per_page", GITHUB_PAGE_SIZE))
+}
+
+// points to the next page of release lists, like github does
+fn github_link(req: &Request, server_url: &str) -> String {
+    let url = Url::parse(&format!("{server_url}{}", req.path_and_query())).unwrap();
+    if !url.path().ends_with("/releases") {
+        return String::new();
+    }
+
+    let count = match respond(req, server_url) {
+        (200, body) => serde_json::from_slice::<Vec<Value>>(&body).unwrap().len(),
+        _ => return String::new(),
+    };
+    let (page, per_page) = github_page(&url);
+    if count < per_page {
+        return String::new();
+    }
+
+    let mut next = url.clone();
+    next.query_pairs_mut().clear().append_pair("per_page", &per_page.to_string()).append_pair("page", &(page + 1).to_string());
+    format!("<{next}>; rel=\"next\"")
+}
\ No newline at end of file
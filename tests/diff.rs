@@ -0,0 +1,117 @@
+mod common;
+
+use std::process::Command;
+
+use common::TestPack;
+use serde_json::Value;
+
+fn git(pack: &TestPack, args: &[&str]) {
+    let output = Command::new("git")
+        .args(["-c", "user.name=tester", "-c", "user.email=tester@example.com"])
+        .args(args)
+        .current_dir(pack.path())
+        .output()
+        .unwrap();
+    assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+}
+
+#[test]
+fn diff_git_revision() {
+    let pack = TestPack::init();
+    pack.run(&["add", "mr", "sodium", "-v", "OihdIimA"]);
+    pack.run(&["add", "cf", "jei"]);
+    git(&pack, &["init", "-q"]);
+    git(&pack, &["add", "-A"]);
+    git(&pack, &["commit", "-qm", "first"]);
+
+    pack.run(&["update", "sodium"]);
+    pack.run(&["remove", "238222"]);
+    pack.write("pack.toml", &pack.read("pack.toml").replace("minecraft = \"1.20.4\"", "minecraft = \"1.20.1\""));
+
+    let out = pack.run(&["diff", "HEAD"]);
+    assert!(out.contains("Minecraft 1.20.4 → 1.20.1"), "{out}");
+    assert!(out.contains("Removed (1)\n  - Just Enough Items (JEI) jei-1.20.4-fabric-17.3.0.49.jar"), "{out}");
+    assert!(out.contains("Updated (1)\n  ~ Sodium mc1.20.1-0.5.8 → mc1.20.4-0.5.9"), "{out}");
+    assert!(!out.contains("Added"), "{out}");
+
+    let out = pack.run(&["diff", "HEAD", "HEAD"]);
+    assert!(out.contains("No changes between HEAD and HEAD"), "{out}");
+
+    let output = pack.emm(&["diff", "nope"]);
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("nope is not an mrpack, a modpack folder or a git revision"));
+}
+
+#[test]
+fn diff_packwiz_markdown() {
+    let pack = TestPack::init();
+    pack.run(&["add", "mr", "sodium", "-v", "OihdIimA"]);
+    pack.run(&["add", "cf", "jei"]);
+    std::fs::create_dir(pack.path().join("old")).unwrap();
+    pack.run(&["export", "pw", "old"]);
+
+    pack.run(&["update"]);
+    pack.run(&["add", "mr", "HVnmMxH1"]);
+
+    let out = pack.run(&["diff", "old", "--markdown"]);
+    assert!(out.contains("### Added\n\n- [Complementary Reimagined](https://modrinth.com/project/HVnmMxH1) [r5.2](https://modrinth.com/project/HVnmMxH1/version/qX4Kj0ms)"), "{out}");
+    assert!(out.contains("### Updated\n\n- [Sodium](https://modrinth.com/project/AANobbMI) mc1.20.1-0.5.8 → [mc1.20.4-0.5.9](https://modrinth.com/project/AANobbMI/version/4GyXKCLd)"), "{out}");
+    // curseforge addons keep their ids through packwiz
+    assert!(!out.contains("Removed"), "{out}");
+    assert!(!out.contains("Loader"), "{out}");
+}
+
+#[test]
+fn diff_mrpack_json() {
+    let pack = TestPack::init();
+    pack.run(&["add", "mr", "sodium", "-v", "OihdIimA"]);
+    pack.run(&["add", "cf", "jei"]);
+    pack.run(&["export", "mr"]);
+
+    pack.run(&["update", "sodium"]);
+    pack.run(&["remove", "238222"]);
+
+    let out: Value = serde_json::from_str(&pack.run(&["diff", "Test Pack-0.1.0.mrpack", "--output", "json"])).unwrap();
+    assert_eq!(out["minecraft"], Value::Null);
+    assert_eq!(out["loader"], Value::Null);
+    assert_eq!(out["added"].as_array().unwrap().len(), 0);
+
+    // curseforge files in the overrides are found by their fingerprint
+    assert_eq!(out["removed"][0]["id"], "238222");
+    assert_eq!(out["removed"][0]["source"], "curseforge");
+
+    let sodium = &out["updated"][0];
+    assert_eq!(sodium["from"]["id"], "OihdIimA");
+    assert_eq!(sodium["to"]["id"], "4GyXKCLd");
+    assert_eq!(sodium["to"]["name"], "mc1.20.4-0.5.9");
+}
+
+#[test]
+fn diff_invalid_index_file() {
+    let pack = TestPack::init();
+    pack.run(&["add", "mr", "sodium"]);
+    pack.write("other/pack.toml", &pack.read("pack.toml"));
+    pack.write("other/index/broken.toml", "name = \"unterminated");
+
+    let out = pack.emm(&["diff", "other"]);
+    assert!(!out.status.success());
+    let stderr = String::from_utf8_lossy(&out.stderr);
+    assert!(stderr.contains("broken.toml is not a valid index file"), "{stderr}");
+    assert!(!stderr.contains("panicked"), "{stderr}");
+}
+
+#[test]
+fn diff_deleted_curseforge_file() {
+    let pack = TestPack::init();
+    pack.run(&["add", "cf", "jei"]);
+    // an old pack with a file curseforge doesnt have anymore
+    pack.write("old/pack.toml", &pack.read("pack.toml"));
+    pack.write("old/index/jei.toml", &pack.read("index/jei.toml").replace("5262120", "1111111"));
+
+    let out = pack.run(&["diff", "old", "--output", "json"]);
+    let out: Value = serde_json::from_str(&out).unwrap();
+    let jei = &out["updated"][0];
+    assert_eq!(jei["from"]["name"], "1111111");
+    assert_eq!(jei["from"]["url"], Value::Null);
+    assert_eq!(jei["to"]["name"], "jei-1.20.4-fabric-17.3.0.49.jar");
+}
\ No newline at end of file
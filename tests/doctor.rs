@@ -0,0 +1,70 @@
+mod common;
+
+use std::fs;
+
+use common::{fixture_file, TestPack};
+
+#[test]
+fn doctor_healthy() {
+    let pack = TestPack::init();
+    pack.run(&["add", "mr", "sodium"]);
+    pack.run(&["add", "cf", "jei"]);
+
+    let out = pack.run(&["doctor"]);
+    assert!(out.contains("No problems found"), "{out}");
+}
+
+#[test]
+fn doctor_missing_dependency() {
+    let pack = TestPack::init();
+    pack.run(&["add", "mr", "sodium"]);
+    fs::remove_file(pack.path().join("index/fabric-api.toml")).unwrap();
+
+    let out = pack.emm(&["check"]);
+    assert!(!out.status.success());
+    assert!(String::from_utf8_lossy(&out.stdout).contains("Sodium requires Fabric API, which is not in the modpack"));
+    assert!(String::from_utf8_lossy(&out.stderr).contains("Found 1 problem"));
+}
+
+#[test]
+fn doctor_incompatible_versions() {
+    let pack = TestPack::init();
+    pack.run(&["add", "mr", "sodium", "-v", "OihdIimA"]);
+    fs::create_dir(pack.path().join("jars")).unwrap();
+    fs::write(pack.path().join("jars/example-mod.jar"), fixture_file("example-mod-1.0.0.jar")).unwrap();
+    pack.run(&["add", "file", "jars/example-mod.jar", "-l"]);
+
+    let out = pack.emm(&["doctor"]);
+    assert!(!out.status.success());
+    let stdout = String::from_utf8_lossy(&out.stdout);
+    assert!(stdout.contains("Sodium mc1.20.1-0.5.8 is not compatible"), "{stdout}");
+    assert!(stdout.contains("example-mod says it doesnt support"), "{stdout}");
+}
+
+#[test]
+fn doctor_declared_incompatibility() {
+    let pack = TestPack::init();
+    pack.run(&["add", "mr", "sodium"]);
+    pack.run(&["add", "mr", "incompat-mod"]);
+    pack.run(&["add", "cf", "jei"]);
+    pack.run(&["add", "cf", "incompat-cf"]);
+
+    let out = pack.emm(&["doctor"]);
+    assert!(!out.status.success());
+    let stdout = String::from_utf8_lossy(&out.stdout);
+    assert!(stdout.contains("Incompat Mod is incompatible with Sodium"), "{stdout}");
+    assert!(stdout.contains("Incompat CF is incompatible with Just Enough Items (JEI)"), "{stdout}");
+    assert!(String::from_utf8_lossy(&out.stderr).contains("Found 2 problems"));
+}
+
+#[test]
+fn doctor_doesnt_write_lockfile() {
+    let pack = TestPack::init();
+    fs::create_dir(pack.path().join("jars")).unwrap();
+    fs::write(pack.path().join("jars/example-mod.jar"), fixture_file("example-mod-1.0.0.jar")).unwrap();
+    pack.run(&["add", "file", "jars/example-mod.jar", "-l"]);
+    fs::remove_file(pack.path().join("emm.lock")).unwrap();
+
+    pack.emm(&["doctor"]);
+    assert!(!pack.path().join("emm.lock").exists());
+}
\ No newline at end of file
@@ -0,0 +1,137 @@
+mod common;
+
+use common::{fixture_file, read_zip, sha1, sha512, TestPack};
+use serde_json::Value;
+
+fn pack_with_addons() -> TestPack {
+    let pack = TestPack::init();
+    pack.run(&["add", "mr", "sodium"]);
+    pack.run(&["add", "cf", "jei"]);
+    pack
+}
+
+#[test]
+fn export_modrinth() {
+    let pack = pack_with_addons();
+    pack.write("overrides/config/sodium-options.json", "{}");
+    pack.run(&["export", "mr", "-o", "overrides"]);
+
+    let files = read_zip(&pack.path().join("Test Pack-0.1.0.mrpack"));
+    let index: Value = serde_json::from_slice(&files["modrinth.index.json"]).unwrap();
+    assert_eq!(index["dependencies"]["minecraft"], "1.20.4");
+    assert_eq!(index["dependencies"]["fabric-loader"], "0.15.11");
+
+    let sodium = index["files"].as_array().unwrap().iter().find(|f| f["path"] == "mods/sodium-fabric-mc1.20.4-0.5.9.jar").unwrap();
+    assert_eq!(sodium["hashes"]["sha512"], sha512(&fixture_file("sodium-0.5.9.jar")));
+    assert_eq!(sodium["env"]["client"], "required");
+    assert_eq!(sodium["env"]["server"], "unsupported");
+
+    // curseforge files cant be mrpack downloads so they go in overrides
+    assert_eq!(files["overrides/mods/jei-1.20.4-fabric-17.3.0.49.jar"], fixture_file("jei-17.3.0.jar"));
+    assert_eq!(files["overrides/config/sodium-options.json"], b"{}");
+}
+
+#[test]
+fn export_curseforge() {
+    let pack = pack_with_addons();
+    pack.run(&["export", "cf"]);
+
+    let files = read_zip(&pack.path().join("Test Pack-0.1.0.zip"));
+    let manifest: Value = serde_json::from_slice(&files["manifest.json"]).unwrap();
+    assert_eq!(manifest["minecraft"]["version"], "1.20.4");
+    assert_eq!(manifest["minecraft"]["modLoaders"][0]["id"], "fabric-0.15.11");
+    assert_eq!(manifest["files"][0]["projectID"], 238222);
+    assert_eq!(manifest["files"][0]["fileID"], 5262120);
+    assert!(String::from_utf8_lossy(&files["modlist.html"]).contains("https://www.curseforge.com/minecraft/mc-mods/jei"));
+
+    assert_eq!(files["overrides/mods/sodium-fabric-mc1.20.4-0.5.9.jar"], fixture_file("sodium-0.5.9.jar"));
+    assert_eq!(files["overrides/mods/fabric-api-0.97.0+1.20.4.jar"], fixture_file("fabric-api-0.97.0.jar"));
+}
+
+#[test]
+fn export_packwiz() {
+    let pack = pack_with_addons();
+    std::fs::create_dir(pack.path().join("packwiz")).unwrap();
+    pack.run(&["export", "pw", "packwiz"]);
+
+    let pw_pack = pack.toml("packwiz/pack.toml");
+    assert_eq!(pw_pack["versions"]["minecraft"].as_str(), Some("1.20.4"));
+
+    let sodium = pack.toml("packwiz/mods/sodium.pw.toml");
+    assert_eq!(sodium["download"]["hash"].as_str(), Some(sha1(&fixture_file("sodium-0.5.9.jar")).as_str()));
+    assert_eq!(sodium["update"]["modrinth"]["version"].as_str(), Some("4GyXKCLd"));
+
+    let jei = pack.toml("packwiz/mods/just-enough-items-(jei).pw.toml");
+    assert_eq!(jei["update"]["curseforge"]["file-id"].as_integer(), Some(5262120));
+}
+
+#[test]
+fn export_locked_needs_lockfile() {
+    let pack = pack_with_addons();
+    std::fs::remove_file(pack.path().join("emm.lock")).unwrap();
+
+    let output = pack.emm(&["export", "mr", "--locked"]);
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("emm.lock"));
+}
+
+#[test]
+fn export_deleted_version() {
+    let pack = pack_with_addons();
+    // a version modrinth doesnt return anymore
+    pack.write("index/sodium.toml", &pack.read("index/sodium.toml").replace("4GyXKCLd", "gone0000"));
+    let lock = pack.read("emm.lock");
+
+    let output = pack.emm(&["export", "mr"]);
+    assert!(!output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("Unable to lock Sodium"), "{stderr}");
+    assert!(!stderr.contains("panicked"), "{stderr}");
+    assert_eq!(pack.read("emm.lock"), lock);
+}
+
+#[test]
+fn export_server() {
+    let pack = pack_with_addons();
+    // as if curseforge didnt say which side jei is for
+    pack.write("index/jei.toml", &pack.read("index/jei.toml").replace("side = \"both\"", "side = \"unknown\""));
+    pack.write("overrides/config/sodium-options.json", "{}");
+    pack.write("overrides/server.properties", "motd=test");
+    pack.write("overrides/resourcepacks/pack.zip", "client only");
+    pack.write("overrides/options.txt", "client only");
+
+    let out = pack.run(&["export", "server", "-o", "overrides"]);
+    assert!(out.contains("1 addon has an unknown side and were left out"), "{out}");
+    assert!(out.contains("Just Enough Items (JEI)"), "{out}");
+
+    let files = read_zip(&pack.path().join("Test Pack-0.1.0-server.zip"));
+    let info: Value = serde_json::from_slice(&files["server-info.json"]).unwrap();
+    assert_eq!(info["minecraft"], "1.20.4");
+    assert_eq!(info["loader_version"], "0.15.11");
+
+    assert_eq!(files["mods/fabric-api-0.97.0+1.20.4.jar"], fixture_file("fabric-api-0.97.0.jar"));
+    assert_eq!(files["config/sodium-options.json"], b"{}");
+    assert_eq!(files["server.properties"], b"motd=test");
+    // sodium is client only
+    assert!(!files.contains_key("mods/sodium-fabric-mc1.20.4-0.5.9.jar"));
+    assert!(!files.contains_key("mods/jei-1.20.4-fabric-17.3.0.49.jar"));
+    assert!(!files.keys().any(|f| f.starts_with("resourcepacks/") || f == "options.txt"), "{:?}", files.keys());
+
+    let out = pack.run(&["export", "server", "-o", "overrides", "-d", "server", "--include-unknown"]);
+    assert!(out.contains("were included"), "{out}");
+    assert!(pack.path().join("server/server-info.json").is_file());
+    assert!(pack.path().join("server/mods/jei-1.20.4-fabric-17.3.0.49.jar").is_file());
+    assert!(!pack.path().join("server/mods/sodium-fabric-mc1.20.4-0.5.9.jar").exists());
+    assert!(!pack.path().join("server/options.txt").exists());
+}
+
+#[test]
+fn export_curseforge_escapes_modlist() {
+    let pack = pack_with_addons();
+    pack.write("index/jei.toml", &pack.read("index/jei.toml").replace("Just Enough Items (JEI)", "JEI <b>& 'friends'"));
+    pack.run(&["export", "cf"]);
+
+    let files = read_zip(&pack.path().join("Test Pack-0.1.0.zip"));
+    let modlist = String::from_utf8_lossy(&files["modlist.html"]).into_owned();
+    assert!(modlist.contains(">JEI &lt;b&gt;&amp; &#39;friends&#39;</a>"), "{modlist}");
+}
\ No newline at end of file
@@ -0,0 +1,53 @@
+mod common;
+
+use common::{TestPack, GITHUB_TOKEN};
+use mockito::Matcher;
+
+#[test]
+fn github_token() {
+    let pack = TestPack::init();
+    let out = pack.emm(&["add", "gh", "tester/private-mod", "-t", "v1.1.0"]);
+    assert!(!out.status.success());
+    assert!(!pack.path().join("index/private-mod.toml").exists());
+
+    pack.write_config(&format!("github_token = \"{GITHUB_TOKEN}\""));
+    pack.run(&["add", "gh", "tester/private-mod", "-t", "v1.1.0"]);
+    assert_eq!(pack.toml("index/private-mod.toml")["source"]["asset"].as_str(), Some("private-mod-*.jar"));
+    assert_eq!(pack.toml("emm.lock")["addon"][0]["filename"].as_str(), Some("private-mod-1.1.0.jar"));
+}
+
+#[test]
+fn missing_tag() {
+    let pack = TestPack::init();
+    let out = pack.emm(&["add", "gh", "tester/example-mod", "-t", "v9.9.9"]);
+    assert!(!out.status.success());
+    assert!(String::from_utf8_lossy(&out.stderr).contains("Could not find a release tagged 'v9.9.9'"));
+}
+
+#[test]
+fn release_pagination() {
+    let pack = TestPack::init();
+    pack.run(&["add", "gh", "tester/many-releases", "-t", "v1"]);
+    pack.write("pack.toml", &pack.read("pack.toml").replace("[options]", "[options]\nmin_release_channel = \"release\""));
+
+    // the newest 120 releases are prereleases, so the update is on the second page
+    pack.run(&["update"]);
+    assert_eq!(pack.toml("index/many-releases.toml")["source"]["tag"].as_str(), Some("v30"));
+}
+
+#[test]
+fn update_stops_paging() {
+    let mut pack = TestPack::init();
+    pack.run(&["add", "gh", "tester/many-releases", "-t", "v1"]);
+
+    // the newest release is compatible, so the second page isnt needed
+    let path = "/github/repos/tester/many-releases/releases";
+    let next = format!("<{}{path}?per_page=100&page=2>; rel=\"next\"", pack.server_url());
+    let first = pack.expect("GET", format!("{path}?per_page=100").as_str(), 1, &[("link", &next)]);
+    let second = pack.expect("GET", Matcher::Regex(r"page=2".into()), 0, &[]);
+
+    pack.run(&["update"]);
+    assert_eq!(pack.toml("index/many-releases.toml")["source"]["tag"].as_str(), Some("v150"));
+    first.assert();
+    second.assert();
+}
\ No newline at end of file
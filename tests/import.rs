@@ -0,0 +1,102 @@
+mod common;
+
+use common::{fixture_file, sha1, sha512, write_zip, TestPack};
+use serde_json::json;
+
+#[test]
+fn import_modrinth() {
+    let pack = TestPack::new();
+    let sodium = fixture_file("sodium-0.5.8.jar");
+    let index = json!({
+        "formatVersion": 1,
+        "game": "minecraft",
+        "versionId": "1.2.0",
+        "name": "Imported Pack",
+        "files": [{
+            "path": "mods/sodium-fabric-mc1.20.1-0.5.8.jar",
+            "hashes": { "sha1": sha1(&sodium), "sha512": sha512(&sodium) },
+            "downloads": [format!("{}/files/sodium-0.5.8.jar", pack.server_url())],
+            "fileSize": sodium.len()
+        }],
+        "dependencies": { "minecraft": "1.20.1", "fabric-loader": "0.15.10" }
+    });
+    write_zip(&pack.path().join("imported.mrpack"), &[
+        ("modrinth.index.json", index.to_string().as_bytes()),
+        ("overrides/config/sodium-options.json", b"{}"),
+    ]);
+
+    pack.run(&["import", "mr", "imported.mrpack"]);
+
+    let modpack = pack.toml("pack.toml");
+    assert_eq!(modpack["name"].as_str(), Some("Imported Pack"));
+    assert_eq!(modpack["versions"]["minecraft"].as_str(), Some("1.20.1"));
+    assert_eq!(modpack["versions"]["loader_version"].as_str(), Some("0.15.10"));
+    assert_eq!(pack.toml("index/sodium.toml")["source"]["version"].as_str(), Some("OihdIimA"));
+    assert_eq!(pack.read("overrides/config/sodium-options.json"), "{}");
+}
+
+#[test]
+fn import_curseforge() {
+    let pack = TestPack::new();
+    let manifest = json!({
+        "minecraft": {
+            "version": "1.20.1",
+            "modLoaders": [{ "id": "fabric-0.15.10", "primary": true }]
+        },
+        "manifestType": "minecraftModpack",
+        "manifestVersion": 1,
+        "name": "Curse Pack",
+        "version": "2.0.0",
+        "author": "tester",
+        "files": [
+            { "projectID": 238222, "fileID": 5101366, "required": true },
+            { "projectID": 627557, "fileID": 5187513, "required": true },
+            { "projectID": 240630, "fileID": 1111111, "required": true },
+            { "projectID": 999999, "fileID": 2222222, "required": true }
+        ],
+        "overrides": "overrides"
+    });
+    write_zip(&pack.path().join("curse.zip"), &[
+        ("manifest.json", manifest.to_string().as_bytes()),
+        ("overrides/options.txt", b"fov:0.5"),
+    ]);
+
+    // files that cant be found anymore are skipped with a warning
+    let out = pack.run(&["import", "cf", "curse.zip"]);
+    assert!(out.contains("Skipping projectID 240630 fileID 1111111"), "{out}");
+    assert!(out.contains("Skipping projectID 999999 fileID 2222222"), "{out}");
+    assert!(!pack.path().join("index/just-enough-resources-jer.toml").exists());
+
+    let modpack = pack.toml("pack.toml");
+    assert_eq!(modpack["name"].as_str(), Some("Curse Pack"));
+    assert_eq!(modpack["version"].as_str(), Some("2.0.0"));
+    assert_eq!(modpack["versions"]["loader"].as_str(), Some("fabric"));
+    assert_eq!(modpack["options"]["overrides_path"].as_str(), Some("./overrides"));
+
+    let jei = pack.toml("index/jei.toml");
+    assert_eq!(jei["source"]["version"].as_integer(), Some(5101366));
+    // curseforge doesnt say which side a mod is for, so it comes from the jar's fabric.mod.json
+    assert_eq!(jei["side"].as_str(), Some("both"));
+    // shaders only matter on the client
+    assert_eq!(pack.toml("index/complementary-reimagined.toml")["side"].as_str(), Some("client"));
+    assert_eq!(pack.read("overrides/options.txt"), "fov:0.5");
+}
+
+#[test]
+fn import_round_trip() {
+    let pack = TestPack::init();
+    pack.run(&["add", "mr", "sodium"]);
+    pack.run(&["export", "mr"]);
+
+    let imported = TestPack::new();
+    std::fs::copy(pack.path().join("Test Pack-0.1.0.mrpack"), imported.path().join("pack.mrpack")).unwrap();
+    imported.run(&["import", "mr", "pack.mrpack"]);
+
+    assert_eq!(imported.toml("index/sodium.toml"), pack.toml("index/sodium.toml"));
+    // mrpacks dont know why an addon was added, so only the dependency relations are lost
+    let mut original = pack.toml("index/fabric-api.toml");
+    let options = original["options"].as_table_mut().unwrap();
+    assert!(options.remove("added_as_dependency").is_some());
+    assert!(options.remove("dependency_of").is_some());
+    assert_eq!(imported.toml("index/fabric-api.toml"), original);
+}
\ No newline at end of file
@@ -0,0 +1,35 @@
+mod common;
+
+use common::TestPack;
+
+#[test]
+fn init_creates_pack() {
+    let pack = TestPack::init();
+    let modpack = pack.toml("pack.toml");
+
+    assert_eq!(modpack["name"].as_str(), Some("Test Pack"));
+    assert_eq!(modpack["authors"][0].as_str(), Some("tester"));
+    assert_eq!(modpack["versions"]["minecraft"].as_str(), Some("1.20.4"));
+    assert_eq!(modpack["versions"]["loader"].as_str(), Some("fabric"));
+    assert_eq!(modpack["versions"]["loader_version"].as_str(), Some("latest"));
+}
+
+#[test]
+fn init_latest_snapshot() {
+    let pack = TestPack::new();
+    pack.run(&["init", "-n", "Snapshot Pack", "--latest-snapshot", "-L", "quilt"]);
+    let modpack = pack.toml("pack.toml");
+
+    assert_eq!(modpack["versions"]["minecraft"].as_str(), Some("24w14a"));
+    // quilt packs accept fabric mods by default
+    assert_eq!(modpack["options"]["acceptable_loaders"][0].as_str(), Some("fabric"));
+}
+
+#[test]
+fn init_does_not_overwrite() {
+    let pack = TestPack::init();
+    let out = pack.run(&["init", "-n", "Other Pack", "-l", "-L", "forge"]);
+
+    assert!(out.contains("already has a modpack"));
+    assert_eq!(pack.toml("pack.toml")["name"].as_str(), Some("Test Pack"));
+}
\ No newline at end of file
@@ -0,0 +1,85 @@
+mod common;
+
+use std::fs;
+
+use common::{fixture_file, TestPack};
+use serde_json::Value;
+
+#[test]
+fn install_fresh() {
+    let pack = TestPack::init();
+    pack.run(&["add", "mr", "sodium"]);
+    pack.run(&["add", "cf", "jei"]);
+
+    let out = pack.run(&["install", "instance"]);
+    assert!(out.contains("(3 installed, 0 removed, 0 unchanged)"), "{out}");
+
+    let mods = pack.path().join("instance/mods");
+    assert_eq!(fs::read(mods.join("sodium-fabric-mc1.20.4-0.5.9.jar")).unwrap(), fixture_file("sodium-0.5.9.jar"));
+    assert_eq!(fs::read(mods.join("jei-1.20.4-fabric-17.3.0.49.jar")).unwrap(), fixture_file("jei-17.3.0.jar"));
+
+    let manifest: Value = serde_json::from_str(&pack.read("instance/.emm-install.json")).unwrap();
+    assert_eq!(manifest["files"].as_object().unwrap().len(), 3);
+}
+
+#[test]
+fn install_again_is_noop() {
+    let pack = TestPack::init();
+    pack.run(&["add", "mr", "sodium"]);
+    pack.run(&["install", "instance"]);
+
+    let out = pack.run(&["install", "instance"]);
+    assert!(out.contains("(0 installed, 0 removed, 2 unchanged)"), "{out}");
+}
+
+#[test]
+fn install_removes_old_addons() {
+    let pack = TestPack::init();
+    pack.run(&["add", "mr", "sodium"]);
+    pack.run(&["add", "cf", "jei"]);
+    pack.run(&["install", "instance"]);
+    // files emm didnt install are left alone
+    pack.write("instance/mods/user-mod.jar", "not from emm");
+
+    pack.run(&["remove", "238222"]);
+    let out = pack.run(&["install", "instance"]);
+    assert!(out.contains("(0 installed, 1 removed, 2 unchanged)"), "{out}");
+    assert!(!pack.path().join("instance/mods/jei-1.20.4-fabric-17.3.0.49.jar").exists());
+    assert!(pack.path().join("instance/mods/user-mod.jar").exists());
+
+    let manifest: Value = serde_json::from_str(&pack.read("instance/.emm-install.json")).unwrap();
+    assert!(manifest["files"].get("mods/jei-1.20.4-fabric-17.3.0.49.jar").is_none());
+}
+
+#[test]
+fn install_rejects_unsafe_filenames() {
+    let pack = TestPack::init();
+    pack.run(&["add", "cf", "jei"]);
+    let lock = pack.read("emm.lock");
+    pack.write("emm.lock", &lock.replace("jei-1.20.4-fabric-17.3.0.49.jar", "../../escaped.jar"));
+
+    let out = pack.emm(&["install", "instance"]);
+    assert!(!out.status.success());
+    assert!(String::from_utf8_lossy(&out.stderr).contains("not a plain file name"));
+    assert!(!pack.path().join("escaped.jar").exists());
+}
+
+#[test]
+fn install_keeps_files_outside() {
+    let pack = TestPack::init();
+    pack.run(&["add", "mr", "sodium"]);
+    pack.run(&["install", "instance"]);
+
+    // an edited manifest cant remove files outside of the install folder
+    let outside = pack.path().join("outside.txt");
+    fs::write(&outside, "keep me").unwrap();
+    let mut manifest: Value = serde_json::from_str(&pack.read("instance/.emm-install.json")).unwrap();
+    manifest["files"]["../outside.txt"] = "0".into();
+    manifest["files"]["mods/../../outside.txt"] = "0".into();
+    manifest["files"][outside.to_string_lossy().as_ref()] = "0".into();
+    pack.write("instance/.emm-install.json", &manifest.to_string());
+
+    let out = pack.run(&["install", "instance"]);
+    assert!(out.contains("0 removed"), "{out}");
+    assert!(outside.is_file());
+}
\ No newline at end of file
@@ -0,0 +1,114 @@
+mod common;
+
+use std::fs;
+
+use common::{fixture_file, read_zip, sha1, TestPack};
+
+fn pack_with_file(path: &str, fixture: &str) -> TestPack {
+    let pack = TestPack::init();
+    let full_path = pack.path().join(path);
+    fs::create_dir_all(full_path.parent().unwrap()).unwrap();
+    fs::write(full_path, fixture_file(fixture)).unwrap();
+    pack
+}
+
+#[test]
+fn add_file_found_on_modrinth() {
+    let pack = pack_with_file("jars/sodium.jar", "sodium-0.5.9.jar");
+    pack.run(&["add", "file", "jars/sodium.jar"]);
+
+    let sodium = pack.toml("index/sodium.toml");
+    assert_eq!(sodium["source"]["source"].as_str(), Some("modrinth"));
+    assert_eq!(sodium["source"]["version"].as_str(), Some("4GyXKCLd"));
+    assert!(pack.path().join("index/fabric-api.toml").exists());
+}
+
+#[test]
+fn add_file_found_for_other_version() {
+    // sodium 0.5.8 is for 1.20.1, the pack is on 1.20.4
+    let pack = pack_with_file("jars/sodium.jar", "sodium-0.5.8.jar");
+    let out = pack.run(&["add", "file", "jars/sodium.jar"]);
+    assert!(out.contains("Found Sodium on modrinth but not for this modpack's version"), "{out}");
+
+    let index: Vec<_> = fs::read_dir(pack.path().join("index")).unwrap().map(|e| e.unwrap().path()).collect();
+    assert_eq!(index.len(), 1, "{index:?}");
+    let sodium: toml::Value = toml::from_str(&fs::read_to_string(&index[0]).unwrap()).unwrap();
+    assert_eq!(sodium["source"]["source"].as_str(), Some("local"));
+    assert!(!pack.path().join("index/fabric-api.toml").exists());
+}
+
+#[test]
+fn add_file_found_on_curseforge() {
+    let pack = pack_with_file("jars/jei.jar", "jei-17.3.0.jar");
+    pack.run(&["add", "file", "jars/jei.jar"]);
+
+    let jei = pack.toml("index/jei.toml");
+    assert_eq!(jei["source"]["source"].as_str(), Some("curseforge"));
+    assert_eq!(jei["source"]["version"].as_integer(), Some(5262120));
+}
+
+#[test]
+fn add_file_local() {
+    let pack = pack_with_file("jars/example-mod-1.1.0.jar", "example-mod-1.1.0.jar");
+    pack.run(&["add", "file", "jars/example-mod-1.1.0.jar", "-n", "Example Mod"]);
+
+    let example = pack.toml("index/example-mod.toml");
+    assert_eq!(example["source"]["source"].as_str(), Some("local"));
+    assert_eq!(example["source"]["path"].as_str(), Some("jars/example-mod-1.1.0.jar"));
+    assert_eq!(example["source"]["sha1"].as_str(), Some(sha1(&fixture_file("example-mod-1.1.0.jar")).as_str()));
+    assert_eq!(example["type"].as_str(), Some("mod"));
+    assert_eq!(example["side"].as_str(), Some("client"));
+
+    let out = pack.run(&["list"]);
+    assert!(out.contains("LF jars/example-mod-1.1.0.jar"), "{out}");
+}
+
+#[test]
+fn add_file_outside_pack() {
+    let pack = TestPack::init();
+    let outside = tempfile::NamedTempFile::new().unwrap();
+    let out = pack.emm(&["add", "file", &outside.path().to_string_lossy()]);
+
+    assert!(!out.status.success());
+    assert!(String::from_utf8_lossy(&out.stderr).contains("inside the modpack folder"));
+}
+
+#[test]
+fn export_local_file() {
+    let pack = pack_with_file("jars/example-mod-1.1.0.jar", "example-mod-1.1.0.jar");
+    pack.run(&["add", "file", "jars/example-mod-1.1.0.jar"]);
+    pack.run(&["export", "mr"]);
+
+    // local files cant be mrpack downloads so they go in overrides
+    let files = read_zip(&pack.path().join("Test Pack-0.1.0.mrpack"));
+    assert_eq!(files["overrides/mods/example-mod-1.1.0.jar"], fixture_file("example-mod-1.1.0.jar"));
+
+    fs::create_dir(pack.path().join("packwiz")).unwrap();
+    pack.run(&["export", "pw", "packwiz"]);
+
+    assert_eq!(fs::read(pack.path().join("packwiz/mods/example-mod-1.1.0.jar")).unwrap(), fixture_file("example-mod-1.1.0.jar"));
+    let index = pack.toml("packwiz/index.toml");
+    let entry = index["files"].as_array().unwrap().iter().find(|f| f["file"].as_str() == Some("mods/example-mod-1.1.0.jar")).unwrap();
+    assert!(entry.get("metafile").is_none());
+}
+
+#[test]
+fn changed_local_file() {
+    let pack = pack_with_file("jars/example-mod.jar", "example-mod-1.0.0.jar");
+    pack.run(&["add", "file", "jars/example-mod.jar"]);
+    fs::write(pack.path().join("jars/example-mod.jar"), fixture_file("example-mod-1.1.0.jar")).unwrap();
+
+    let out = pack.emm(&["export", "mr"]);
+    assert!(!out.status.success());
+    assert!(String::from_utf8_lossy(&out.stderr).contains("Hash mismatch"));
+
+    // changed files are only accepted when asked for
+    let out = pack.run(&["update"]);
+    assert!(out.contains("does not match the hash in the index anymore"), "{out}");
+    assert_eq!(pack.toml("index/example-mod.toml")["source"]["sha1"].as_str(), Some(sha1(&fixture_file("example-mod-1.0.0.jar")).as_str()));
+
+    let out = pack.run(&["update", "example-mod"]);
+    assert!(out.contains("file changed"), "{out}");
+    assert_eq!(pack.toml("index/example-mod.toml")["source"]["sha1"].as_str(), Some(sha1(&fixture_file("example-mod-1.1.0.jar")).as_str()));
+    pack.run(&["export", "mr"]);
+}
\ No newline at end of file
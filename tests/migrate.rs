@@ -0,0 +1,50 @@
+mod common;
+
+use common::TestPack;
+
+#[test]
+fn migrate_game_version() {
+    let pack = TestPack::init();
+    pack.run(&["add", "mr", "sodium"]);
+    pack.run(&["add", "cf", "jei"]);
+
+    let out = pack.run(&["migrate", "1.20.1", "--yes"]);
+    assert!(out.contains("Migrated to 1.20.1"), "{out}");
+
+    assert_eq!(pack.toml("pack.toml")["versions"]["minecraft"].as_str(), Some("1.20.1"));
+    assert_eq!(pack.toml("index/fabric-api.toml")["source"]["version"].as_str(), Some("YblXfKtI"));
+    assert_eq!(pack.toml("index/jei.toml")["source"]["version"].as_integer(), Some(5101366));
+
+    let lock = pack.toml("emm.lock");
+    assert_eq!(lock["minecraft"].as_str(), Some("1.20.1"));
+}
+
+#[test]
+fn migrate_keeps_incompatible() {
+    let pack = TestPack::init();
+    pack.run(&["add", "mr", "sodium"]);
+
+    let out = pack.run(&["migrate", "24w14a", "--yes"]);
+    assert!(out.contains("Migrateable mods: 0/2"), "{out}");
+    assert_eq!(pack.toml("index/sodium.toml")["source"]["version"].as_str(), Some("4GyXKCLd"));
+}
+
+#[test]
+fn migrate_github_from_jar() {
+    let pack = TestPack::init();
+    pack.run(&["add", "gh", "tester/example-mod", "-t", "v1.1.0"]);
+
+    // v1.0.0 is the newest release with a jar that supports 1.20.1
+    pack.run(&["migrate", "1.20.1", "--yes"]);
+    assert_eq!(pack.toml("index/example-mod.toml")["source"]["tag"].as_str(), Some("v1.0.0"));
+}
+
+#[test]
+fn migrate_github_incompatible() {
+    let pack = TestPack::init();
+    pack.run(&["add", "gh", "tester/example-mod", "-t", "v1.1.0"]);
+
+    let out = pack.run(&["migrate", "1.19.2", "--yes"]);
+    assert!(out.contains("1 incompatible"), "{out}");
+    assert_eq!(pack.toml("index/example-mod.toml")["source"]["tag"].as_str(), Some("v1.1.0"));
+}
\ No newline at end of file
@@ -0,0 +1,68 @@
+mod common;
+
+use common::TestPack;
+
+fn stderr(output: &std::process::Output) -> String {
+    String::from_utf8_lossy(&output.stderr).into_owned()
+}
+
+#[test]
+fn choices_without_default_fail() {
+    let pack = TestPack::new();
+    let output = pack.emm(&["init"]);
+    assert!(!output.status.success());
+    assert!(stderr(&output).contains("use --name/-n"), "{}", stderr(&output));
+
+    let output = pack.emm(&["init", "-n", "Test Pack", "-L", "fabric"]);
+    assert!(stderr(&output).contains("use --latest/-l or --latest-snapshot"), "{}", stderr(&output));
+
+    let output = pack.emm(&["init", "-n", "Test Pack", "-l"]);
+    assert!(stderr(&output).contains("use --loader/-L"), "{}", stderr(&output));
+    assert!(!pack.path().join("pack.toml").exists());
+
+    let pack = TestPack::init();
+    let output = pack.emm(&["migrate"]);
+    assert!(stderr(&output).contains("pass the version to migrate to"), "{}", stderr(&output));
+
+    let output = pack.emm(&["add", "mr", "sod"]);
+    assert!(stderr(&output).contains("Choosing a search result for 'sod' needs input"), "{}", stderr(&output));
+
+    let output = pack.emm(&["add", "mr", "sodium", "--prompt-optional"]);
+    assert!(stderr(&output).contains("use --with-optional"), "{}", stderr(&output));
+}
+
+#[test]
+fn questions_use_defaults() {
+    let pack = TestPack::init();
+    pack.run(&["add", "mr", "sodium"]);
+
+    // unused dependencies are removed by default
+    let out = pack.run(&["remove", "sodium"]);
+    assert!(out.contains("Removing Fabric API"), "{out}");
+    assert!(!pack.path().join("index/fabric-api.toml").exists());
+
+    let out = pack.run(&["pin", "sodum"]);
+    assert!(out.contains("'sodum' does not match any addon"), "{out}");
+}
+
+#[test]
+fn newest_github_release() {
+    let pack = TestPack::init();
+    pack.run(&["--yes", "add", "gh", "tester/example-mod"]);
+    assert_eq!(pack.toml("index/example-mod.toml")["source"]["tag"].as_str(), Some("v1.1.0"));
+
+    pack.write("pack.toml", &pack.read("pack.toml").replace("[options]", "[options]\nmin_release_channel = \"release\""));
+    pack.run(&["remove", "example-mod"]);
+    pack.run(&["add", "gh", "tester/example-mod", "--no-input"]);
+    assert_eq!(pack.toml("index/example-mod.toml")["source"]["tag"].as_str(), Some("v1.0.0"));
+}
+
+#[test]
+fn interactive_update_applies_all() {
+    let pack = TestPack::init();
+    pack.run(&["add", "mr", "sodium", "-v", "OihdIimA"]);
+
+    let out = pack.run(&["update", "--interactive"]);
+    assert!(out.contains("Sodium"), "{out}");
+    assert_eq!(pack.toml("index/sodium.toml")["source"]["version"].as_str(), Some("4GyXKCLd"));
+}
\ No newline at end of file
@@ -0,0 +1,104 @@
+mod common;
+
+use common::TestPack;
+use serde_json::Value;
+
+fn stderr(output: &std::process::Output) -> String {
+    String::from_utf8_lossy(&output.stderr).into_owned()
+}
+
+#[test]
+fn outdated_changes_nothing() {
+    let pack = TestPack::init();
+    pack.run(&["add", "mr", "sodium", "-v", "OihdIimA"]);
+    let old_index = pack.read("index/sodium.toml");
+    let old_lock = pack.read("emm.lock");
+
+    let out = pack.run(&["outdated"]);
+    assert!(out.contains("Sodium"), "{out}");
+    assert!(out.contains("mc1.20.1-0.5.8"), "{out}");
+    assert!(out.contains("mc1.20.4-0.5.9"), "{out}");
+    assert_eq!(pack.read("index/sodium.toml"), old_index);
+    assert_eq!(pack.read("emm.lock"), old_lock);
+}
+
+#[test]
+fn outdated_shows_pinned() {
+    let pack = TestPack::init();
+    pack.run(&["add", "mr", "sodium", "-v", "OihdIimA"]);
+    pack.run(&["pin", "sodium"]);
+
+    // pinned addons dont fail the check
+    let out = pack.run(&["outdated", "--exit-code"]);
+    assert!(out.contains("(pinned)"), "{out}");
+
+    pack.run(&["unpin", "sodium"]);
+    let output = pack.emm(&["outdated", "--exit-code"]);
+    assert!(!output.status.success());
+    assert!(stderr(&output).contains("1 update available"), "{}", stderr(&output));
+}
+
+#[test]
+fn outdated_markdown() {
+    let pack = TestPack::init();
+    pack.run(&["add", "mr", "sodium", "-v", "OihdIimA"]);
+
+    let out = pack.run(&["outdated", "--markdown"]);
+    assert!(out.starts_with("| Addon | Current | Latest |"), "{out}");
+    assert!(out.contains("| Sodium | mc1.20.1-0.5.8 | [mc1.20.4-0.5.9](https://modrinth.com/project/AANobbMI/version/4GyXKCLd) |"), "{out}");
+
+    pack.run(&["update"]);
+    let out = pack.run(&["outdated", "--markdown", "--exit-code"]);
+    assert!(out.contains("All addons are up to date"), "{out}");
+}
+
+#[test]
+fn outdated_markdown_escapes_names() {
+    let pack = TestPack::init();
+    pack.run(&["add", "mr", "sodium", "-v", "OihdIimA"]);
+    pack.write("index/sodium.toml", &pack.read("index/sodium.toml").replace("name = \"Sodium\"", "name = \"Sodium | <b>fork</b>\""));
+
+    let out = pack.run(&["outdated", "--markdown"]);
+    assert!(out.contains("| Sodium \\| &lt;b&gt;fork&lt;/b&gt; | mc1.20.1-0.5.8 |"), "{out}");
+}
+
+#[test]
+fn outdated_json() {
+    let pack = TestPack::init();
+    pack.run(&["add", "mr", "sodium", "-v", "OihdIimA"]);
+    pack.run(&["pin", "sodium"]);
+
+    let out: Value = serde_json::from_str(&pack.run(&["outdated", "--output", "json"])).unwrap();
+    let sodium = &out["outdated"][0];
+    assert_eq!(sodium["from"], "OihdIimA");
+    assert_eq!(sodium["to"], "4GyXKCLd");
+    assert_eq!(sodium["current"], "mc1.20.1-0.5.8");
+    assert_eq!(sodium["pinned"], true);
+}
+
+#[test]
+fn outdated_skips_missing_versions() {
+    let pack = TestPack::init();
+    pack.run(&["add", "mr", "sodium", "-v", "OihdIimA"]);
+    pack.run(&["add", "mr", "iris"]);
+    // a version modrinth doesnt return anymore
+    pack.write("index/iris.toml", &pack.read("index/iris.toml").replace("kuOV4Ece", "gone0000"));
+
+    let output = pack.emm(&["outdated"]);
+    assert!(output.status.success(), "{}", stderr(&output));
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("Skipping Iris Shaders, modrinth did not return its version"), "{stdout}");
+    assert!(stdout.contains("mc1.20.4-0.5.9"), "{stdout}");
+}
+
+#[test]
+fn outdated_skips_incompatible_versions() {
+    let pack = TestPack::init();
+    pack.run(&["add", "mr", "sodium", "-v", "OihdIimA"]);
+    // no sodium version is for 1.16.5
+    pack.write("pack.toml", &pack.read("pack.toml").replace("minecraft = \"1.20.4\"", "minecraft = \"1.16.5\""));
+
+    let out = pack.run(&["outdated"]);
+    assert!(out.contains("Skipping Sodium, there is no compatible version of it for this modpack"), "{out}");
+    assert!(!out.contains("did not return"), "{out}");
+}
\ No newline at end of file
@@ -0,0 +1,89 @@
+mod common;
+
+use common::{fixture_file, sha1, TestPack};
+use serde_json::Value;
+
+fn json(out: &str) -> Value {
+    serde_json::from_str(out).unwrap_or_else(|err| panic!("{err}\n{out}"))
+}
+
+#[test]
+fn list_json() {
+    let pack = TestPack::init();
+    pack.run(&["add", "mr", "sodium"]);
+    pack.run(&["add", "cf", "jei"]);
+    std::fs::remove_file(pack.path().join("emm.lock")).unwrap();
+
+    let out = json(&pack.run(&["list", "--output", "json"]));
+    assert!(!pack.path().join("emm.lock").exists());
+    let addons = out["addons"].as_array().unwrap();
+    assert_eq!(addons.len(), 3);
+
+    let sodium = addons.iter().find(|a| a["name"] == "Sodium").unwrap();
+    assert_eq!(sodium["source"]["source"], "modrinth");
+    assert_eq!(sodium["version"], "4GyXKCLd");
+    assert_eq!(sodium["url"], "https://modrinth.com/project/AANobbMI");
+    assert!(sodium["description"].is_string());
+    assert_eq!(sodium["file"]["filename"], "sodium-fabric-mc1.20.4-0.5.9.jar");
+
+    let jei = addons.iter().find(|a| a["id"] == "238222").unwrap();
+    assert_eq!(jei["file"]["sha1"], sha1(&fixture_file("jei-17.3.0.jar")));
+}
+
+#[test]
+fn update_json() {
+    let pack = TestPack::init();
+    pack.run(&["add", "mr", "sodium", "-v", "OihdIimA"]);
+
+    let out = json(&pack.run(&["update", "--dry-run", "--output", "json"]));
+    assert_eq!(out["dry_run"], true);
+    let update = &out["updates"][0];
+    assert_eq!(update["name"], "Sodium");
+    assert_eq!(update["from"], "OihdIimA");
+    assert_eq!(update["to"], "4GyXKCLd");
+    assert_eq!(update["version"], "mc1.20.4-0.5.9");
+    assert_eq!(update["applied"], false);
+
+    let out = json(&pack.run(&["update", "--output", "json"]));
+    assert_eq!(out["updates"][0]["applied"], true);
+    assert_eq!(pack.toml("index/sodium.toml")["source"]["version"].as_str(), Some("4GyXKCLd"));
+
+    let out = json(&pack.run(&["update", "--output", "json"]));
+    assert_eq!(out["updates"].as_array().unwrap().len(), 0);
+}
+
+#[test]
+fn migrate_json() {
+    let pack = TestPack::init();
+    pack.run(&["add", "mr", "sodium"]);
+
+    let out = json(&pack.run(&["migrate", "24w14a", "--yes", "--output", "json"]));
+    assert_eq!(out["from"], "1.20.4");
+    assert_eq!(out["to"], "24w14a");
+    assert_eq!(out["migrated"], true);
+    let sodium = out["addons"].as_array().unwrap().iter().find(|a| a["name"] == "Sodium").unwrap();
+    assert_eq!(sodium["compatibility"], "incompatible");
+    assert_eq!(sodium["to"], Value::Null);
+
+    let out = json(&pack.run(&["migrate", "1.20.1", "--yes", "--output", "json"]));
+    assert!(out["addons"].as_array().unwrap().iter().all(|a| a["compatibility"] == "compatible"), "{out}");
+}
+
+#[test]
+fn export_json() {
+    let pack = TestPack::init();
+    pack.run(&["add", "mr", "sodium"]);
+
+    let out = json(&pack.run(&["export", "mr", "--output", "json"]));
+    assert_eq!(out["format"], "modrinth");
+    let mrpack = pack.path().join("Test Pack-0.1.0.mrpack");
+    let artifact = &out["artifacts"][0];
+    assert_eq!(artifact["path"].as_str().map(std::path::PathBuf::from), Some(mrpack.canonicalize().unwrap()));
+    assert_eq!(artifact["sha1"], sha1(&std::fs::read(&mrpack).unwrap()));
+
+    std::fs::create_dir(pack.path().join("packwiz")).unwrap();
+    let out = json(&pack.run(&["export", "pw", "packwiz", "--output", "json"]));
+    let paths: Vec<&str> = out["artifacts"].as_array().unwrap().iter().map(|a| a["path"].as_str().unwrap()).collect();
+    assert!(paths.iter().any(|p| p.ends_with("pack.toml")), "{paths:?}");
+    assert!(paths.iter().any(|p| p.ends_with("sodium.pw.toml")), "{paths:?}");
+}
\ No newline at end of file
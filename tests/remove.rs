@@ -0,0 +1,46 @@
+mod common;
+
+use common::TestPack;
+
+#[test]
+fn dependency_relations() {
+    let pack = TestPack::init();
+    pack.run(&["add", "mr", "sodium"]);
+
+    let fabric_api = pack.toml("index/fabric-api.toml");
+    assert_eq!(fabric_api["options"]["added_as_dependency"].as_bool(), Some(true));
+    assert_eq!(fabric_api["options"]["dependency_of"][0].as_str(), Some("AANobbMI"));
+    assert!(pack.toml("index/sodium.toml")["options"].get("added_as_dependency").is_none());
+
+    let out = pack.run(&["why", "Fabric API"]);
+    assert!(out.contains("added as a dependency"), "{out}");
+    assert!(out.contains("Sodium"), "{out}");
+}
+
+#[test]
+fn remove_orphaned_dependencies() {
+    let pack = TestPack::init();
+    pack.run(&["add", "mr", "sodium"]);
+    let out = pack.run(&["remove", "sodium", "-d"]);
+
+    assert!(out.contains("Removing Fabric API"), "{out}");
+    assert!(!pack.path().join("index/sodium.toml").exists());
+    assert!(!pack.path().join("index/fabric-api.toml").exists());
+    assert!(pack.toml("emm.lock").get("addon").is_none_or(|a| a.as_array().unwrap().is_empty()));
+}
+
+#[test]
+fn keep_explicit_dependencies() {
+    let pack = TestPack::init();
+    pack.run(&["add", "mr", "sodium"]);
+    let out = pack.run(&["add", "mr", "fabric-api"]);
+    assert!(out.contains("now added explicitly"), "{out}");
+
+    pack.run(&["remove", "sodium", "-d"]);
+    let fabric_api = pack.toml("index/fabric-api.toml");
+    assert!(fabric_api["options"].get("added_as_dependency").is_none());
+    assert!(fabric_api["options"].get("dependency_of").is_none());
+
+    let out = pack.run(&["why", "Fabric API"]);
+    assert!(out.contains("added explicitly"), "{out}");
+}
\ No newline at end of file
@@ -0,0 +1,48 @@
+mod common;
+
+use common::TestPack;
+use mockito::Matcher;
+
+#[test]
+fn reuse_cached_responses() {
+    let mut pack = TestPack::init();
+    pack.run(&["add", "mr", "sodium"]);
+    pack.write_config("response_cache_ttl = 3600");
+
+    let versions = pack.expect("GET", Matcher::Regex(r"^/modrinth/versions\?ids=".into()), 2, &[]);
+    let first = pack.run(&["update", "--dry-run"]);
+    let second = pack.run(&["update", "--dry-run"]);
+    assert_eq!(first, second);
+
+    pack.run(&["update", "--dry-run", "--refresh"]);
+    versions.assert();
+}
+
+#[test]
+fn revalidate_with_etag() {
+    let mut pack = TestPack::init();
+    pack.run(&["add", "gh", "tester/example-mod", "-t", "v1.0.0"]);
+    pack.write_config("response_cache_ttl = 0");
+
+    let path = "/github/repos/tester/example-mod/releases?per_page=100";
+    let releases = pack.expect("GET", path, 1, &[("etag", "\"releases-v1\"")]);
+    let not_modified = pack.not_modified(path, "\"releases-v1\"");
+
+    let first = pack.run(&["update", "--dry-run"]);
+    let second = pack.run(&["update", "--dry-run"]);
+    assert_eq!(first, second);
+
+    releases.assert();
+    not_modified.assert();
+}
+
+#[test]
+fn off_by_default() {
+    let mut pack = TestPack::init();
+    pack.run(&["add", "mr", "sodium"]);
+
+    let versions = pack.expect("GET", Matcher::Regex(r"^/modrinth/versions\?ids=".into()), 2, &[]);
+    pack.run(&["update", "--dry-run"]);
+    pack.run(&["update", "--dry-run"]);
+    versions.assert();
+}
\ No newline at end of file
@@ -0,0 +1,53 @@
+mod common;
+
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use common::TestPack;
+
+#[test]
+fn retry_server_errors() {
+    let mut pack = TestPack::init();
+    pack.fail("GET", "/modrinth/project/sodium", 503, 2, &[]);
+
+    pack.run(&["add", "mr", "sodium"]);
+    assert!(pack.path().join("index/sodium.toml").exists());
+}
+
+#[test]
+fn wait_for_ratelimits() {
+    let mut pack = TestPack::init();
+    pack.fail("GET", "/modrinth/project/sodium", 429, 1, &[("X-Ratelimit-Reset", "1")]);
+    // modrinth used to panic without the reset header
+    pack.fail("GET", "/modrinth/project/sodium/version", 429, 1, &[]);
+    pack.fail("GET", "/curseforge/v1/mods/238222/files", 429, 1, &[("Retry-After", "1")]);
+
+    pack.run(&["add", "mr", "sodium"]);
+    pack.run(&["add", "cf", "238222"]);
+    assert!(pack.path().join("index/sodium.toml").exists());
+    assert!(pack.path().join("index/jei.toml").exists());
+}
+
+#[test]
+fn retry_loader_meta() {
+    let mut pack = TestPack::init();
+    pack.fail("GET", "/fabric/v2/versions/loader", 500, 1, &[]);
+
+    pack.run(&["add", "mr", "sodium"]);
+    assert_eq!(pack.toml("emm.lock")["loader_version"].as_str(), Some("0.15.11"));
+}
+
+#[test]
+fn long_ratelimits_fail() {
+    let mut pack = TestPack::init();
+    let reset = SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_secs() + 3600;
+    pack.fail("GET", "/github/repos/tester/example-mod/releases/tags/v1.1.0", 403, 1, &[
+        ("x-ratelimit-remaining", "0"),
+        ("x-ratelimit-reset", &reset.to_string()),
+    ]);
+
+    let out = pack.emm(&["add", "gh", "tester/example-mod", "-t", "v1.1.0"]);
+    assert!(!out.status.success());
+    let stderr = String::from_utf8_lossy(&out.stderr);
+    assert!(stderr.contains("You exceeded github's ratelimit"), "{stderr}");
+    assert!(stderr.contains("GITHUB_TOKEN"), "{stderr}");
+}
\ No newline at end of file
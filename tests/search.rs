@@ -0,0 +1,93 @@
+mod common;
+
+use common::TestPack;
+use mockito::Matcher;
+
+#[test]
+fn search_modrinth_by_type() {
+    let pack = TestPack::init();
+    pack.run(&["add", "mr", "Complementary Reimagined", "--type", "shader"]);
+
+    let addon = pack.toml("index/complementary-reimagined.toml");
+    assert_eq!(addon["type"].as_str(), Some("shader"));
+    assert_eq!(addon["source"]["version"].as_str(), Some("qX4Kj0ms"));
+}
+
+#[test]
+fn search_modrinth_skips_other_loaders() {
+    let pack = TestPack::init();
+    let out = pack.run(&["add", "mr", "Embeddium"]);
+
+    assert!(out.contains("Searching for Embeddium returned no results"), "{out}");
+    assert!(!pack.path().join("index/embeddium.toml").exists());
+}
+
+#[test]
+fn search_curseforge_by_type() {
+    let pack = TestPack::init();
+    let out = pack.run(&["add", "cf", "Complementary Reimagined"]);
+    assert!(out.contains("returned no results"), "{out}");
+
+    pack.run(&["add", "cf", "Complementary Reimagined", "--type", "shader"]);
+    let addon = pack.toml("index/complementary-reimagined.toml");
+    assert_eq!(addon["type"].as_str(), Some("shader"));
+    assert_eq!(addon["source"]["version"].as_integer(), Some(5187513));
+}
+
+#[test]
+fn search_table() {
+    let pack = TestPack::init();
+    pack.run(&["add", "mr", "sodium"]);
+
+    let out = pack.run(&["search", "s"]);
+    let lines: Vec<&str> = out.lines().collect();
+    assert!(lines[0].contains("TITLE") && lines[0].contains("DOWNLOADS"), "{out}");
+
+    let sodium = lines.iter().find(|l| l.contains("Sodium")).unwrap();
+    assert!(sodium.contains("61.2M") && sodium.contains("2024-04-05") && sodium.ends_with("fabric, quilt *") && sodium.ends_with('*'), "{out}");
+    let iris = lines.iter().find(|l| l.contains("Iris Shaders")).unwrap();
+    assert!(!iris.ends_with('*'), "{out}");
+    // jei is on 1.20.4 fabric on curseforge, embeddium only on forge
+    assert!(out.contains("Just Enough Items (JEI)") && out.contains("mezz"), "{out}");
+    assert!(!out.contains("Embeddium"), "{out}");
+}
+
+#[test]
+fn search_json() {
+    let pack = TestPack::init();
+    pack.run(&["add", "cf", "238222"]);
+
+    let out = pack.run(&["search", "just enough", "--source", "cf", "--sort", "updated", "--json"]);
+    let projects: serde_json::Value = serde_json::from_str(&out).unwrap();
+    let projects = projects.as_array().unwrap();
+
+    assert_eq!(projects.len(), 2);
+    assert_eq!(projects[0]["title"], "Just Enough Resources (JER)");
+    assert_eq!(projects[0]["in_index"], false);
+    assert_eq!(projects[1]["id"], "238222");
+    assert_eq!(projects[1]["source"], "curseforge");
+    assert_eq!(projects[1]["loaders"], serde_json::json!(["fabric", "forge"]));
+    assert_eq!(projects[1]["in_index"], true);
+}
+
+#[test]
+fn search_query_is_encoded() {
+    let mut pack = TestPack::init();
+    let modrinth = pack.expect("GET", Matcher::Regex(r"^/modrinth/search\?query=sodium\+%26\+iris&facets=".into()), 1, &[]);
+    let curseforge = pack.expect("GET", Matcher::Regex(r"^/curseforge/v1/mods/search\?.*&searchFilter=sodium\+%26\+iris&gameVersions=".into()), 1, &[]);
+
+    pack.run(&["search", "sodium & iris"]);
+    modrinth.assert();
+    curseforge.assert();
+}
+
+#[test]
+fn add_curseforge_slug_by_type() {
+    let pack = TestPack::init();
+    let out = pack.run(&["add", "cf", "complementary-reimagined", "--type", "shader"]);
+    assert!(!out.contains("returned no results"), "{out}");
+
+    let addon = pack.toml("index/complementary-reimagined.toml");
+    assert_eq!(addon["type"].as_str(), Some("shader"));
+    assert_eq!(addon["source"]["id"].as_integer(), Some(627557));
+}
\ No newline at end of file
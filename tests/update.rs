@@ -0,0 +1,114 @@
+mod common;
+
+use common::TestPack;
+
+#[test]
+fn update_modrinth() {
+    let pack = TestPack::init();
+    pack.run(&["add", "mr", "sodium", "-v", "OihdIimA"]);
+    let old_lock = pack.read("emm.lock");
+
+    let out = pack.run(&["update"]);
+    assert!(out.contains("Sodium"), "{out}");
+    assert_eq!(pack.toml("index/sodium.toml")["source"]["version"].as_str(), Some("4GyXKCLd"));
+    assert_ne!(pack.read("emm.lock"), old_lock);
+}
+
+#[test]
+fn update_nothing_new() {
+    let pack = TestPack::init();
+    pack.run(&["add", "mr", "sodium"]);
+
+    let out = pack.run(&["update"]);
+    assert!(out.contains("No new updates found"));
+}
+
+#[test]
+fn update_skips_pinned() {
+    let pack = TestPack::init();
+    pack.run(&["add", "mr", "sodium", "-v", "OihdIimA"]);
+    pack.run(&["pin", "sodium"]);
+
+    let out = pack.run(&["update"]);
+    assert!(out.contains("No new updates found"));
+    assert_eq!(pack.toml("index/sodium.toml")["source"]["version"].as_str(), Some("OihdIimA"));
+}
+
+#[test]
+fn update_dry_run() {
+    let pack = TestPack::init();
+    pack.run(&["add", "mr", "sodium", "-v", "OihdIimA"]);
+    let old_lock = pack.read("emm.lock");
+
+    let out = pack.run(&["update", "--dry-run"]);
+    assert!(out.contains("Would update:"), "{out}");
+    assert!(out.contains("mc1.20.4-0.5.9"), "{out}");
+    assert_eq!(pack.toml("index/sodium.toml")["source"]["version"].as_str(), Some("OihdIimA"));
+    assert_eq!(pack.read("emm.lock"), old_lock);
+}
+
+#[test]
+fn update_changelogs() {
+    let pack = TestPack::init();
+    pack.run(&["add", "mr", "sodium", "-v", "OihdIimA"]);
+    pack.run(&["add", "cf", "jei"]);
+    // the older jei file is for another minecraft version, so it cant be added directly
+    pack.write("index/jei.toml", &pack.read("index/jei.toml").replace("5262120", "5101366"));
+    pack.run(&["add", "gh", "tester/example-mod", "-t", "v1.0.0"]);
+
+    let out = pack.run(&["update", "--changelog"]);
+    assert!(out.contains("    Fixed flickering chunks\n    Updated to 1.20.4"), "{out}");
+    assert!(out.contains("    Changes in jei-1.20.4-fabric-17.3.0.49.jar\n    Fixed \"crash\""), "{out}");
+    assert!(out.contains("    Added a config screen"), "{out}");
+    assert_eq!(pack.toml("index/jei.toml")["source"]["version"].as_integer(), Some(5262120));
+}
+
+#[test]
+fn update_respects_release_channel() {
+    let pack = TestPack::init();
+    pack.run(&["add", "mr", "sodium", "-v", "OihdIimA"]);
+    pack.run(&["add", "gh", "tester/example-mod", "-t", "v1.0.0"]);
+    pack.write("pack.toml", &pack.read("pack.toml").replace("[options]", "[options]\nmin_release_channel = \"release\""));
+
+    // sodium 0.5.9 is a beta and example-mod 1.1.0 a prerelease
+    let out = pack.run(&["update"]);
+    assert!(out.contains("No new updates found"), "{out}");
+
+    // addons can allow less stable versions than the modpack
+    pack.write("index/sodium.toml", &pack.read("index/sodium.toml").replace("[options]", "[options]\nmin_release_channel = \"beta\""));
+    let out = pack.run(&["update"]);
+    assert!(out.contains("Sodium"), "{out}");
+    assert!(!out.contains("example-mod"), "{out}");
+    assert_eq!(pack.toml("index/sodium.toml")["source"]["version"].as_str(), Some("4GyXKCLd"));
+    assert_eq!(pack.toml("index/example-mod.toml")["source"]["tag"].as_str(), Some("v1.0.0"));
+}
+
+#[test]
+fn update_github_by_asset_pattern() {
+    let pack = TestPack::init();
+    // an index file from before assets were picked by name, the second asset of v1.0.0 is the mod
+    pack.write("index/sourced-mod.toml", "name = \"sourced-mod\"\ntype = \"mod\"\nside = \"both\"\n\n[source]\nsource = \"github\"\nrepo = \"tester/sourced-mod\"\ntag = \"v1.0.0\"\nasset_index = 1\n");
+
+    // v1.1.0 has its sources jar listed last, so the old index would point at it
+    let out = pack.run(&["update"]);
+    assert!(out.contains("Migrated sourced-mod to use assets matching 'sourced-mod-*.jar'"), "{out}");
+
+    let addon = pack.toml("index/sourced-mod.toml");
+    assert_eq!(addon["source"]["asset"].as_str(), Some("sourced-mod-*.jar"));
+    assert_eq!(addon["source"]["tag"].as_str(), Some("v1.1.0"));
+    assert!(addon["source"].get("asset_index").is_none());
+    assert_eq!(pack.toml("emm.lock")["addon"][0]["filename"].as_str(), Some("sourced-mod-1.1.0.jar"));
+}
+
+#[test]
+fn read_only_commands_dont_migrate() {
+    let pack = TestPack::init();
+    let old_index = "name = \"sourced-mod\"\ntype = \"mod\"\nside = \"both\"\n\n[source]\nsource = \"github\"\nrepo = \"tester/sourced-mod\"\ntag = \"v1.0.0\"\nasset_index = 1\n";
+    pack.write("index/sourced-mod.toml", old_index);
+
+    let out = pack.run(&["outdated"]);
+    assert!(!out.contains("Migrated"), "{out}");
+    pack.run(&["list"]);
+    pack.run(&["why", "sourced-mod"]);
+    assert_eq!(pack.read("index/sourced-mod.toml"), old_index);
+}
\ No newline at end of file
@@ -0,0 +1,73 @@
+mod common;
+
+use std::fs;
+
+use common::{fixture_file, read_zip, sha1, sha512, TestPack};
+use serde_json::Value;
+
+fn pack_with_url() -> (TestPack, String) {
+    let pack = TestPack::init();
+    let url = format!("{}/files/example-mod-1.1.0.jar", pack.server_url());
+    pack.run(&["add", "url", &url, "-n", "Example Mod"]);
+    (pack, url)
+}
+
+#[test]
+fn add_url() {
+    let (pack, url) = pack_with_url();
+
+    let example = pack.toml("index/example-mod.toml");
+    assert_eq!(example["source"]["source"].as_str(), Some("url"));
+    assert_eq!(example["source"]["url"].as_str(), Some(url.as_str()));
+    assert_eq!(example["source"]["sha512"].as_str(), Some(sha512(&fixture_file("example-mod-1.1.0.jar")).as_str()));
+    assert_eq!(example["side"].as_str(), Some("client"));
+
+    let lock = pack.toml("emm.lock");
+    let locked = &lock["addon"].as_array().unwrap()[0];
+    assert_eq!(locked["filename"].as_str(), Some("example-mod-1.1.0.jar"));
+    assert_eq!(locked["url"].as_str(), Some(url.as_str()));
+}
+
+#[test]
+fn add_url_wrong_hash() {
+    let pack = TestPack::init();
+    let url = format!("{}/files/example-mod-1.1.0.jar", pack.server_url());
+    let out = pack.emm(&["add", "url", &url, "--sha1", &sha1(b"something else")]);
+
+    assert!(!out.status.success());
+    assert!(String::from_utf8_lossy(&out.stderr).contains("Hash mismatch"));
+    assert!(!pack.path().join("index/example-mod-1.1.0.toml").exists());
+}
+
+#[test]
+fn export_url() {
+    let (pack, url) = pack_with_url();
+    pack.run(&["export", "mr"]);
+
+    let files = read_zip(&pack.path().join("Test Pack-0.1.0.mrpack"));
+    let index: Value = serde_json::from_slice(&files["modrinth.index.json"]).unwrap();
+    let example = index["files"].as_array().unwrap().iter().find(|f| f["path"] == "mods/example-mod-1.1.0.jar").unwrap();
+    assert_eq!(example["downloads"][0], url);
+    assert_eq!(example["hashes"]["sha1"], sha1(&fixture_file("example-mod-1.1.0.jar")));
+
+    fs::create_dir(pack.path().join("packwiz")).unwrap();
+    pack.run(&["export", "pw", "packwiz"]);
+
+    let pwmod = pack.toml("packwiz/mods/example-mod.pw.toml");
+    assert_eq!(pwmod["download"]["mode"].as_str(), Some("url"));
+    assert_eq!(pwmod["download"]["url"].as_str(), Some(url.as_str()));
+    assert_eq!(pwmod["download"]["hash-format"].as_str(), Some("sha512"));
+}
+
+#[test]
+fn changed_download() {
+    let (pack, _) = pack_with_url();
+    // as if the file behind the url changed after it was added
+    let index = pack.read("index/example-mod.toml");
+    let expected = sha1(&fixture_file("example-mod-1.1.0.jar"));
+    pack.write("index/example-mod.toml", &index.replace(&expected, &sha1(b"something else")));
+
+    let out = pack.emm(&["export", "mr"]);
+    assert!(!out.status.success());
+    assert!(String::from_utf8_lossy(&out.stderr).contains("Hash mismatch"));
+}
\ No newline at end of file